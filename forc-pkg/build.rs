@@ -0,0 +1,69 @@
+//! Identifies the build of the compiler for the build cache.
+//!
+//! Unreleased builds of the compiler share a crate version, so the sources of the crates that
+//! determine the output of compilation are hashed into `FORC_PKG_COMPILER_HASH`. When built from
+//! crates.io these sources are not available and the hash is empty, as the crate version alone
+//! identifies a release.
+
+use sha2::{Digest, Sha256};
+use std::{env, fs, path::PathBuf};
+
+/// The crates within the workspace whose sources determine the output of compilation.
+const COMPILER_CRATES: &[&str] = &[
+    "forc-pkg",
+    "sway-ast",
+    "sway-core",
+    "sway-error",
+    "sway-ir",
+    "sway-parse",
+    "sway-types",
+    "sway-utils",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let workspace_dir = manifest_dir.parent().unwrap();
+    let mut hasher = Sha256::new();
+    let mut hashed_any = false;
+    for name in COMPILER_CRATES {
+        let crate_dir = workspace_dir.join(name);
+        let src_dir = crate_dir.join("src");
+        if !src_dir.is_dir() {
+            continue;
+        }
+        hashed_any = true;
+        let manifest_path = crate_dir.join("Cargo.toml");
+        println!("cargo:rerun-if-changed={}", manifest_path.display());
+        println!("cargo:rerun-if-changed={}", src_dir.display());
+        let mut files = vec![manifest_path];
+        for entry in walkdir::WalkDir::new(&src_dir) {
+            let entry = entry.unwrap();
+            if entry.file_type().is_file() {
+                files.push(entry.into_path());
+            }
+        }
+        files.sort();
+        for path in files {
+            let rel_path = path.strip_prefix(workspace_dir).unwrap();
+            let rel_path: Vec<_> = rel_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            hasher.update(rel_path.join("/").as_bytes());
+            hasher.update(fs::read(&path).unwrap());
+        }
+    }
+    // Dependency versions may also affect the output of compilation.
+    let lock_path = workspace_dir.join("Cargo.lock");
+    if hashed_any && lock_path.is_file() {
+        println!("cargo:rerun-if-changed={}", lock_path.display());
+        hasher.update(fs::read(&lock_path).unwrap());
+    }
+    let hash = if hashed_any {
+        format!("{:x}", hasher.finalize())
+    } else {
+        String::new()
+    };
+    println!("cargo:rustc-env=FORC_PKG_COMPILER_HASH={}", hash);
+}
@@ -0,0 +1,65 @@
+//! Helpers for working with the types described by a program's JSON ABI.
+//!
+//! These are shared by the tools that encode the arguments of, or decode the values produced by, a
+//! program, e.g. the test runner and the client plugin.
+
+use fuel_tx::{self as tx, field::ScriptData};
+use fuels_types::{ProgramABI, TypeApplication, TypeDeclaration};
+use std::collections::HashMap;
+
+/// All types declared within the program ABI, by type ID.
+pub fn type_lookup(program_abi: &ProgramABI) -> HashMap<usize, TypeDeclaration> {
+    program_abi
+        .types
+        .iter()
+        .map(|decl| (decl.type_id, decl.clone()))
+        .collect()
+}
+
+/// The components of the type of the given ABI type application, i.e. the fields of a struct, the
+/// variants of an enum, the elements of a tuple or the element of an array.
+///
+/// Empty if the type application is unknown or the type has no components.
+pub fn type_components<'a>(
+    application: Option<&TypeApplication>,
+    type_lookup: &'a HashMap<usize, TypeDeclaration>,
+) -> &'a [TypeApplication] {
+    application
+        .and_then(|application| type_lookup.get(&application.type_id))
+        .and_then(|decl| decl.components.as_deref())
+        .unwrap_or_default()
+}
+
+/// The name of the struct or enum type of the given ABI type application, or an empty string for
+/// any other type.
+pub fn type_name<'a>(
+    application: Option<&TypeApplication>,
+    type_lookup: &'a HashMap<usize, TypeDeclaration>,
+) -> &'a str {
+    application
+        .and_then(|application| type_lookup.get(&application.type_id))
+        .and_then(|decl| {
+            let type_field = decl.type_field.as_str();
+            type_field
+                .strip_prefix("struct ")
+                .or_else(|| type_field.strip_prefix("enum "))
+        })
+        .unwrap_or_default()
+}
+
+/// The ABI type application of the elements of a vector with the given type application.
+pub fn vec_elem_application(application: Option<&TypeApplication>) -> Option<&TypeApplication> {
+    application
+        .and_then(|application| application.type_arguments.as_deref())
+        .and_then(<[_]>::first)
+}
+
+/// The VM memory address at which the script data of a script with the given bytecode begins.
+///
+/// This is required to resolve the pointers within encoded heap types, e.g. vectors.
+pub fn script_data_addr(bytecode: &[u8]) -> u64 {
+    let params = tx::ConsensusParameters::default();
+    let script =
+        tx::Transaction::script(0, 0, 0, bytecode.to_vec(), vec![], vec![], vec![], vec![]);
+    (params.tx_offset() + script.script_data_offset()) as u64
+}
@@ -0,0 +1,202 @@
+//! Metadata describing how a package was built.
+//!
+//! The build info of each package is written alongside its other build artifacts as
+//! `<name>-build-info.json`. It records everything that determines the package's bytecode, so
+//! that the build may later be reproduced from the same source tree by `forc verify`.
+//!
+//! The sources of `git` and registry dependencies are not recorded individually, as these are
+//! covered by the checksums recorded within `Forc.lock` and in turn by the `lock-hash`.
+
+use crate::{
+    checksum::slash_path,
+    manifest::{BuildProfile, PackageManifestFile},
+    pkg::{BuildPlan, NodeIx, SourcePinned},
+};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::fuel_prelude::fuel_crypto;
+
+/// The suffix of the file to which the build info of a package is written.
+pub const SWAY_BUILD_INFO_SUFFIX: &str = "-build-info.json";
+
+/// Metadata describing how a package was built.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildInfo {
+    /// The version of the compiler with which the package was built.
+    pub compiler_version: String,
+    /// The name of the built package.
+    pub package: String,
+    /// The build profile, omitting options that only affect what is printed during the build.
+    pub profile: BuildProfile,
+    /// The hash of the `Forc.lock` from which the package's dependencies were resolved.
+    pub lock_hash: Option<String>,
+    /// The hash of each source file of the package and of its member and path dependencies, keyed
+    /// by the package name and the file's path relative to the package's directory.
+    pub sources: BTreeMap<String, String>,
+    /// The hash of the resulting bytecode.
+    pub bytecode_hash: String,
+}
+
+impl BuildInfo {
+    /// Collect the build info for the package at the given node, built with `profile`.
+    pub(crate) fn new(
+        plan: &BuildPlan,
+        node: NodeIx,
+        profile: &BuildProfile,
+        bytecode: &[u8],
+    ) -> Result<Self> {
+        let pkg = &plan.graph()[node];
+        let manifest = &plan.manifest_map()[&pkg.id()];
+        let lock_path = manifest.lock_path()?;
+        let lock_hash = match lock_path.exists() {
+            true => Some(hash(&fs::read(&lock_path)?)),
+            false => None,
+        };
+        let mut sources = BTreeMap::new();
+        for dep in plan.node_deps(node) {
+            let dep_pkg = &plan.graph()[dep];
+            if !matches!(dep_pkg.source, SourcePinned::Member | SourcePinned::Path(_)) {
+                continue;
+            }
+            let dep_manifest = &plan.manifest_map()[&dep_pkg.id()];
+            for path in source_files(dep_manifest)? {
+                let rel_path = path.strip_prefix(dep_manifest.dir())?;
+                let key = format!("{}/{}", dep_pkg.name, slash_path(rel_path));
+                sources.insert(key, hash(&fs::read(&path)?));
+            }
+        }
+        let profile = BuildProfile {
+            print_ast: false,
+            print_dca_graph: false,
+            print_ir: false,
+            print_finalized_asm: false,
+            print_intermediate_asm: false,
+            terse: false,
+            time_phases: false,
+            ..profile.clone()
+        };
+        Ok(Self {
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            package: pkg.name.clone(),
+            profile,
+            lock_hash,
+            sources,
+            bytecode_hash: hash(bytecode),
+        })
+    }
+
+    /// Read the build info from the given path.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read build info from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("failed to parse build info {}: {}", path.display(), e))
+    }
+
+    /// Write the build info to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(&file, self)?;
+        Ok(())
+    }
+
+    /// Describe each of the inputs to the build that differ between `self` and `other`.
+    ///
+    /// The bytecode hash is not compared, as it is the output of the build.
+    pub fn diff_inputs(&self, other: &Self) -> Vec<String> {
+        let mut diffs = vec![];
+        if self.compiler_version != other.compiler_version {
+            diffs.push(format!(
+                "compiler version {} differs from {}",
+                self.compiler_version, other.compiler_version
+            ));
+        }
+        if self.package != other.package {
+            diffs.push(format!(
+                "package `{}` differs from `{}`",
+                self.package, other.package
+            ));
+        }
+        if self.lock_hash != other.lock_hash {
+            diffs.push("Forc.lock differs".to_string());
+        }
+        for (path, hash) in &self.sources {
+            match other.sources.get(path) {
+                None => diffs.push(format!("{path} is missing")),
+                Some(other_hash) if other_hash != hash => diffs.push(format!("{path} differs")),
+                Some(_) => (),
+            }
+        }
+        for path in other.sources.keys() {
+            if !self.sources.contains_key(path) {
+                diffs.push(format!("{path} was added"));
+            }
+        }
+        diffs
+    }
+}
+
+/// The files that make up the source of the package: its manifest and all files within the
+/// directory of its entry point.
+fn source_files(manifest: &PackageManifestFile) -> Result<Vec<PathBuf>> {
+    let entry_path = manifest.entry_path();
+    let src_dir = entry_path.parent().unwrap_or_else(|| manifest.dir());
+    let mut files = vec![manifest.path().to_path_buf()];
+    for entry in walkdir::WalkDir::new(src_dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The hex-encoded SHA-256 hash of the given bytes.
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", fuel_crypto::Hasher::hash(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BuildInfo;
+
+    fn build_info(sources: &[(&str, &str)]) -> BuildInfo {
+        BuildInfo {
+            compiler_version: "0.1.0".to_string(),
+            package: "foo".to_string(),
+            profile: Default::default(),
+            lock_hash: Some("00".to_string()),
+            sources: sources
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.to_string()))
+                .collect(),
+            bytecode_hash: "00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_inputs() {
+        let recorded = build_info(&[("foo/src/main.sw", "01"), ("foo/src/lib.sw", "02")]);
+        assert!(recorded.diff_inputs(&recorded).is_empty());
+
+        let mut rebuilt = build_info(&[("foo/src/main.sw", "03"), ("foo/src/other.sw", "04")]);
+        rebuilt.lock_hash = None;
+        rebuilt.bytecode_hash = "ff".to_string();
+        assert_eq!(
+            recorded.diff_inputs(&rebuilt),
+            vec![
+                "Forc.lock differs",
+                "foo/src/lib.sw is missing",
+                "foo/src/main.sw differs",
+                "foo/src/other.sw was added",
+            ]
+        );
+    }
+}
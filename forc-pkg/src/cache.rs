@@ -0,0 +1,464 @@
+//! An on-disk cache of compiled packages.
+//!
+//! Each compiled package is stored alongside a key derived from the package's sources, the keys of
+//! its dependencies, the build profile and the compiler. Packages whose key is unchanged since they
+//! were last built may be loaded from the cache rather than compiled.
+//!
+//! Entries are stored under `~/.forc/cache/build`, with a single entry for each package location
+//! and build profile. As a result, stale entries are overwritten rather than accumulated.
+//!
+//! A dependent that must be compiled also requires the namespaces of the libraries it depends on,
+//! which refer to the declarations and types within the compiler's engines. The libraries at the
+//! start of the compilation order, typically `core` and `std`, are compiled with empty engines, so
+//! their namespaces are cached along with a snapshot of the engines taken once they are compiled.
+//! Restoring the snapshot allows those libraries to be skipped when only their dependents changed.
+//! Any other library is recompiled whenever a package that depends on it must be compiled.
+
+use crate::{
+    manifest::{BuildProfile, PackageManifestFile},
+    pkg::{BuiltPackage, DepKind, Edge, Pinned, TestDecl},
+};
+use anyhow::{Context, Result};
+use forc_util::user_forc_directory;
+use fuels_types::ProgramABI;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::{
+    fuel_prelude::{fuel_crypto, fuel_tx::StorageSlot},
+    language::parsed::TreeType,
+    namespace,
+    source_map::SourceMap,
+    EngineSnapshot, FinalizedEntry, TypeEngine,
+};
+use sway_types::{Ident, SharedSpanSources, Span};
+
+/// The subset of a `BuiltPackage` that is written to the cache.
+#[derive(Serialize, Deserialize)]
+struct CachedPackage {
+    /// The key of the package at the time it was built.
+    key: String,
+    json_abi_program: ProgramABI,
+    json_abi_tests: Option<ProgramABI>,
+    storage_slots: Vec<StorageSlot>,
+    bytecode: Vec<u8>,
+    preamble_len: u64,
+    entries: Vec<FinalizedEntry>,
+    tree_type: CachedTreeType,
+    source_map: SourceMap,
+    test_decls: Vec<TestDecl>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedTreeType {
+    Predicate,
+    Script,
+    Contract,
+    Library { name: String },
+}
+
+/// The namespaces of a sequence of libraries, along with a snapshot of the engines they refer to.
+#[derive(Serialize, Deserialize)]
+struct CachedNamespaces {
+    /// The key of the libraries at the time they were built.
+    key: String,
+    /// The engine snapshot and the namespace of each library, serialized with `bincode`.
+    engines_and_namespaces: SharedSpanSources,
+}
+
+/// The directory in which compiled packages are cached.
+pub fn build_cache_directory() -> PathBuf {
+    user_forc_directory().join("cache").join("build")
+}
+
+/// Whether the output of compiling a package with the given profile may be cached.
+///
+/// Builds that print intermediate compiler output are never cached, as the output cannot be
+/// reproduced from the cached package.
+pub(crate) fn is_cacheable(profile: &BuildProfile) -> bool {
+    !(profile.print_ast
+        || profile.print_dca_graph
+        || profile.print_ir
+        || profile.print_finalized_asm
+        || profile.print_intermediate_asm
+        || profile.time_phases)
+}
+
+/// Produce the cache key for a package.
+///
+/// The key changes whenever the package's manifest or any file within its entry directory
+/// changes, whenever the key of one of its dependencies changes, or when the build profile or
+/// compiler differ.
+pub(crate) fn pkg_key<'a>(
+    manifest: &PackageManifestFile,
+    pkg: &Pinned,
+    profile: &BuildProfile,
+    deps: impl IntoIterator<Item = (&'a Edge, &'a str)>,
+) -> Result<String> {
+    pkg_key_for_compiler(&compiler_id(), manifest, pkg, profile, deps)
+}
+
+/// Produce the cache key for a package built by the compiler with the given ID.
+fn pkg_key_for_compiler<'a>(
+    compiler_id: &str,
+    manifest: &PackageManifestFile,
+    pkg: &Pinned,
+    profile: &BuildProfile,
+    deps: impl IntoIterator<Item = (&'a Edge, &'a str)>,
+) -> Result<String> {
+    let mut hasher = fuel_crypto::Hasher::default();
+    hasher.input(compiler_id.as_bytes());
+    hasher.input(pkg.name.as_bytes());
+    hasher.input(pkg.source.to_string().as_bytes());
+    hasher.input(serde_json::to_vec(profile)?);
+    hasher.input(fs::read(manifest.path())?);
+
+    // Hash the package's sources in a consistent order.
+    let entry_path = manifest.entry_path();
+    let src_dir = entry_path.parent().unwrap_or_else(|| manifest.dir());
+    let mut src_files = vec![];
+    for entry in walkdir::WalkDir::new(src_dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            src_files.push(entry.into_path());
+        }
+    }
+    src_files.sort();
+    for path in src_files {
+        let rel_path: Vec<_> = path
+            .strip_prefix(src_dir)?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect();
+        hasher.input(rel_path.join("/").as_bytes());
+        hasher.input(fs::read(&path)?);
+    }
+
+    // Hash the dependencies in a consistent order.
+    let mut deps: Vec<_> = deps.into_iter().collect();
+    deps.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+    for (edge, dep_key) in deps {
+        hasher.input(edge.name.as_bytes());
+        match edge.kind {
+            DepKind::Library => hasher.input([0u8]),
+            DepKind::Contract { salt } => {
+                hasher.input([1u8]);
+                hasher.input(salt);
+            }
+        }
+        hasher.input(dep_key.as_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Identifies the compiler by its crate version and, for unreleased versions, the hash of its
+/// sources produced by the build script.
+fn compiler_id() -> String {
+    format!(
+        "{}+{}",
+        env!("CARGO_PKG_VERSION"),
+        env!("FORC_PKG_COMPILER_HASH")
+    )
+}
+
+/// The path of the cache entry for the given package and profile.
+///
+/// The path is derived from a SHA-256 hash so that it remains stable across builds of forc.
+fn entry_path(manifest: &PackageManifestFile, pkg: &Pinned, profile: &BuildProfile) -> PathBuf {
+    let mut hasher = fuel_crypto::Hasher::default();
+    hasher.input(manifest.dir().to_string_lossy().as_bytes());
+    hasher.input(pkg.name.as_bytes());
+    hasher.input(pkg.source.to_string().as_bytes());
+    hasher.input(serde_json::to_vec(profile).unwrap_or_default());
+    let hash = hex::encode(&hasher.finalize()[..8]);
+    build_cache_directory().join(format!("{}-{}.json", pkg.name, hash))
+}
+
+/// Produce the key of the namespaces of a sequence of libraries from the keys of the libraries.
+pub(crate) fn namespaces_key<'a>(lib_keys: impl IntoIterator<Item = &'a str>) -> String {
+    let mut hasher = fuel_crypto::Hasher::default();
+    for key in lib_keys {
+        hasher.input(key.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// The path of the cache entry for the namespaces of the given sequence of libraries.
+fn namespaces_entry_path(libs: &[(&PackageManifestFile, &Pinned, BuildProfile)]) -> PathBuf {
+    let mut hasher = fuel_crypto::Hasher::default();
+    for (manifest, pkg, profile) in libs {
+        hasher.input(manifest.dir().to_string_lossy().as_bytes());
+        hasher.input(pkg.name.as_bytes());
+        hasher.input(pkg.source.to_string().as_bytes());
+        hasher.input(serde_json::to_vec(profile).unwrap_or_default());
+    }
+    let hash = hex::encode(&hasher.finalize()[..8]);
+    let name = libs.last().map(|(_, pkg, _)| &pkg.name[..]).unwrap_or("");
+    build_cache_directory().join(format!("{}-namespaces-{}.bin", name, hash))
+}
+
+/// Load the cached namespaces of the given sequence of libraries, if they were cached with the
+/// given key, restoring the engines to their state once the libraries were compiled.
+///
+/// The engines are only restored if they are empty.
+pub(crate) fn load_namespaces(
+    libs: &[(&PackageManifestFile, &Pinned, BuildProfile)],
+    key: &str,
+    type_engine: &TypeEngine,
+) -> Option<Vec<namespace::Module>> {
+    let path = namespaces_entry_path(libs);
+    let cached: CachedNamespaces = bincode::deserialize(&fs::read(path).ok()?).ok()?;
+    if cached.key != key {
+        return None;
+    }
+    let (engines, namespaces): (EngineSnapshot, Vec<namespace::Module>) = cached
+        .engines_and_namespaces
+        .deserialize(|bytes| bincode::deserialize(bytes))
+        .ok()?;
+    if namespaces.len() != libs.len() || !engines.restore(type_engine) {
+        return None;
+    }
+    Some(namespaces)
+}
+
+/// Write the namespaces of the given sequence of libraries to the cache under the given key, along
+/// with a snapshot of the engines.
+///
+/// The libraries must be the only packages that were compiled since the engines were empty.
+pub(crate) fn store_namespaces(
+    libs: &[(&PackageManifestFile, &Pinned, BuildProfile)],
+    key: &str,
+    type_engine: &TypeEngine,
+    namespaces: &[&namespace::Module],
+) -> Result<()> {
+    let engines_and_namespaces = SharedSpanSources::serialize(|| {
+        bincode::serialize(&(EngineSnapshot::take(type_engine), namespaces))
+    })?;
+    let cached = CachedNamespaces {
+        key: key.to_string(),
+        engines_and_namespaces,
+    };
+    let path = namespaces_entry_path(libs);
+    write_atomic(&path, &bincode::serialize(&cached)?)
+        .with_context(|| format!("failed to write build cache entry {}", path.display()))
+}
+
+/// Load the cached package with the given key, if one exists.
+pub(crate) fn load(
+    manifest: &PackageManifestFile,
+    pkg: &Pinned,
+    profile: &BuildProfile,
+    key: &str,
+) -> Option<BuiltPackage> {
+    let path = entry_path(manifest, pkg, profile);
+    let cached: CachedPackage = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    if cached.key != key {
+        return None;
+    }
+    let tree_type = match cached.tree_type {
+        CachedTreeType::Predicate => TreeType::Predicate,
+        CachedTreeType::Script => TreeType::Script,
+        CachedTreeType::Contract => TreeType::Contract,
+        CachedTreeType::Library { name } => TreeType::Library {
+            name: Ident::new(Span::from_string(name)),
+        },
+    };
+    Some(BuiltPackage {
+        json_abi_program: cached.json_abi_program,
+        json_abi_tests: cached.json_abi_tests,
+        storage_slots: cached.storage_slots,
+        bytecode: cached.bytecode,
+        preamble_len: cached.preamble_len,
+        entries: cached.entries,
+        tree_type,
+        source_map: cached.source_map,
+        pkg_name: pkg.name.clone(),
+        warnings: vec![],
+        build_info: None,
+        test_decls: cached.test_decls,
+    })
+}
+
+/// Write the given compiled package to the cache under the given key.
+pub(crate) fn store(
+    manifest: &PackageManifestFile,
+    pkg: &Pinned,
+    profile: &BuildProfile,
+    key: &str,
+    built: &BuiltPackage,
+) -> Result<()> {
+    let tree_type = match &built.tree_type {
+        TreeType::Predicate => CachedTreeType::Predicate,
+        TreeType::Script => CachedTreeType::Script,
+        TreeType::Contract => CachedTreeType::Contract,
+        TreeType::Library { name } => CachedTreeType::Library {
+            name: name.as_str().to_string(),
+        },
+    };
+    let cached = CachedPackage {
+        key: key.to_string(),
+        json_abi_program: built.json_abi_program.clone(),
+        json_abi_tests: built.json_abi_tests.clone(),
+        storage_slots: built.storage_slots.clone(),
+        bytecode: built.bytecode.clone(),
+        preamble_len: built.preamble_len,
+        entries: built.entries.clone(),
+        tree_type,
+        source_map: built.source_map.clone(),
+        test_decls: built.test_decls.clone(),
+    };
+    let path = entry_path(manifest, pkg, profile);
+    write_atomic(&path, &serde_json::to_vec(&cached)?)
+        .with_context(|| format!("failed to write build cache entry {}", path.display()))
+}
+
+/// Write to a temporary file before renaming, so that concurrent builds never observe a partially
+/// written file.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().expect("cache entry has a parent directory");
+    fs::create_dir_all(dir)?;
+    let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pkg::SourcePinned,
+        test_util::{self, TempDir},
+    };
+    use sway_core::{source_map::SourceMap, FinalizedEntry, OptLevel};
+
+    /// Write a library named `foo` to a new temporary directory and return its manifest.
+    fn write_foo() -> (TempDir, PackageManifestFile) {
+        let dir = test_util::temp_dir();
+        test_util::write_lib(dir.path(), "foo", "");
+        let manifest = PackageManifestFile::from_dir(dir.path()).unwrap();
+        (dir, manifest)
+    }
+
+    fn member(name: &str) -> Pinned {
+        Pinned {
+            name: name.to_string(),
+            source: SourcePinned::Member,
+        }
+    }
+
+    #[test]
+    fn test_pkg_key_invalidation() {
+        let (_dir, manifest) = write_foo();
+        let pkg = member("foo");
+        let profile = BuildProfile::debug();
+        let dep = Edge::new("bar".to_string(), DepKind::Library);
+        let key = |compiler_id: &str, profile: &BuildProfile, dep_key: &str| {
+            pkg_key_for_compiler(compiler_id, &manifest, &pkg, profile, [(&dep, dep_key)]).unwrap()
+        };
+        let original = key("forc 0.32.2", &profile, "bar-key");
+        assert_eq!(original, key("forc 0.32.2", &profile, "bar-key"));
+
+        // The key of a dependency changes.
+        assert_ne!(original, key("forc 0.32.2", &profile, "new-bar-key"));
+        // The build profile changes.
+        let size_profile = BuildProfile {
+            opt_level: OptLevel::Size,
+            ..BuildProfile::debug()
+        };
+        assert_ne!(original, key("forc 0.32.2", &size_profile, "bar-key"));
+        // The compiler changes.
+        assert_ne!(original, key("forc 0.32.3", &profile, "bar-key"));
+        // The kind of a dependency changes.
+        let contract_dep = Edge::new(
+            "bar".to_string(),
+            DepKind::Contract {
+                salt: Default::default(),
+            },
+        );
+        let contract_dep_key = pkg_key_for_compiler(
+            "forc 0.32.2",
+            &manifest,
+            &pkg,
+            &profile,
+            [(&contract_dep, "bar-key")],
+        )
+        .unwrap();
+        assert_ne!(original, contract_dep_key);
+
+        // A source file is edited, added or removed.
+        let src_dir = manifest.dir().join("src");
+        fs::write(
+            src_dir.join("lib.sw"),
+            "library foo;\n\nconst X: u64 = 1;\n",
+        )
+        .unwrap();
+        let edited = key("forc 0.32.2", &profile, "bar-key");
+        assert_ne!(original, edited);
+        fs::write(src_dir.join("other.sw"), "library other;\n").unwrap();
+        let added = key("forc 0.32.2", &profile, "bar-key");
+        assert_ne!(edited, added);
+        fs::remove_file(src_dir.join("other.sw")).unwrap();
+        assert_eq!(edited, key("forc 0.32.2", &profile, "bar-key"));
+    }
+
+    #[test]
+    fn test_store_and_load() {
+        // Entries are written within the user's forc directory.
+        test_util::with_forc_home(|_| {
+            let (_dir, manifest) = write_foo();
+            let pkg = member("foo");
+            let profile = BuildProfile {
+                include_tests: true,
+                ..BuildProfile::debug()
+            };
+            let test_decl = TestDecl {
+                args: vec![("should_revert".to_string(), Some("42".to_string()))],
+                path: Some(manifest.entry_path()),
+                start: 0,
+                end: 12,
+            };
+            let built = BuiltPackage {
+                json_abi_program: ProgramABI::default(),
+                json_abi_tests: Some(ProgramABI::default()),
+                storage_slots: vec![StorageSlot::new([1; 32].into(), [2; 32].into())],
+                bytecode: vec![1, 2, 3, 4],
+                preamble_len: 6,
+                entries: vec![FinalizedEntry {
+                    fn_name: "test_foo".to_string(),
+                    imm: 7,
+                    selector: None,
+                    test_decl_id: None,
+                }],
+                tree_type: TreeType::Library {
+                    name: Ident::new(Span::from_string("foo".to_string())),
+                },
+                source_map: SourceMap::new(),
+                pkg_name: "foo".to_string(),
+                warnings: vec![],
+                build_info: None,
+                test_decls: vec![test_decl.clone()],
+            };
+            store(&manifest, &pkg, &profile, "key", &built).unwrap();
+
+            assert!(load(&manifest, &pkg, &profile, "other-key").is_none());
+            assert!(load(&manifest, &pkg, &BuildProfile::debug(), "key").is_none());
+            let loaded = load(&manifest, &pkg, &profile, "key").unwrap();
+            assert_eq!(loaded.json_abi_tests, built.json_abi_tests);
+            assert_eq!(loaded.storage_slots, built.storage_slots);
+            assert_eq!(loaded.bytecode, built.bytecode);
+            assert_eq!(loaded.preamble_len, built.preamble_len);
+            assert_eq!(loaded.entries[0].fn_name, "test_foo");
+            assert_eq!(loaded.entries[0].imm, 7);
+            assert_eq!(loaded.tree_type, built.tree_type);
+            assert_eq!(loaded.pkg_name, "foo");
+            assert_eq!(loaded.test_decls, vec![test_decl.clone()]);
+            assert_eq!(
+                loaded.test_decls[0].span().unwrap().as_str(),
+                "library foo;"
+            );
+        });
+    }
+}
@@ -0,0 +1,168 @@
+//! Checksums over the fetched sources of git and registry packages.
+//!
+//! The checksum of a git or registry package covers its own directory along with those of any
+//! path dependencies rooted at it, e.g. `core` within the same git checkout as `std`. Checksums
+//! are recorded within `Forc.lock` so that a tampered or partially fetched source is detected
+//! upon the next build, and within `vendor/vendor.toml` for each vendored source.
+//!
+//! Checksums are always computed from the contents of each file. Metadata such as sizes and
+//! modification times is trivially preserved by an edit, and any record of previously computed
+//! checksums could be rewritten along with the source it describes.
+
+use crate::{
+    pkg::{source_root, Graph, ManifestMap, Pinned, PinnedId, SourcePinned},
+    vendor::Vendor,
+};
+use anyhow::{anyhow, Result};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::fuel_prelude::fuel_crypto;
+
+/// The checksum of every git and registry package within a graph.
+pub type Checksums = HashMap<PinnedId, String>;
+
+/// Whether or not packages of the given source have a checksum.
+///
+/// Member and path packages are excluded as their sources are expected to change between builds.
+pub fn is_checksummed(source: &SourcePinned) -> bool {
+    matches!(source, SourcePinned::Git(_) | SourcePinned::Registry(_))
+}
+
+/// Compute the checksum of every git and registry package within the `graph`.
+///
+/// Vendored packages are checksummed from their vendored copy.
+pub(crate) fn graph_checksums(
+    graph: &Graph,
+    manifest_map: &ManifestMap,
+    vendor: Option<&Vendor>,
+) -> Result<Checksums> {
+    graph
+        .node_indices()
+        .map(|n| &graph[n])
+        .filter(|pinned| is_checksummed(&pinned.source))
+        .map(|pinned| {
+            let root = match vendor {
+                Some(vendor) => vendor.root(pinned)?,
+                None => source_root(pinned)?,
+            };
+            let dirs = source_dirs(graph, manifest_map, pinned, &root)?;
+            Ok((pinned.id(), dirs_checksum(&root, &dirs)?))
+        })
+        .collect()
+}
+
+/// The directories of the given package and the path dependencies rooted at it, relative to the
+/// `root` of its source.
+///
+/// Directories nested within another of the returned directories are omitted.
+pub(crate) fn source_dirs(
+    graph: &Graph,
+    manifest_map: &ManifestMap,
+    pinned: &Pinned,
+    root: &Path,
+) -> Result<Vec<PathBuf>> {
+    let id = pinned.id();
+    let mut dirs = vec![];
+    for node in graph.node_indices() {
+        let pkg = &graph[node];
+        let in_source = match &pkg.source {
+            SourcePinned::Path(path) => path.path_root == id,
+            _ => *pkg == *pinned,
+        };
+        if !in_source {
+            continue;
+        }
+        let manifest = manifest_map
+            .get(&pkg.id())
+            .ok_or_else(|| anyhow!("missing manifest for `{}`", pkg.name))?;
+        let rel_dir = manifest.dir().strip_prefix(root).map_err(|_| {
+            anyhow!(
+                "`{}` at {} is not located within the source of `{}` at {}",
+                pkg.name,
+                manifest.dir().display(),
+                pinned.name,
+                root.display()
+            )
+        })?;
+        dirs.push(rel_dir.to_path_buf());
+    }
+    dirs.sort();
+    let mut outer: Vec<PathBuf> = vec![];
+    for dir in dirs {
+        if !outer.iter().any(|outer_dir| dir.starts_with(outer_dir)) {
+            outer.push(dir);
+        }
+    }
+    Ok(outer)
+}
+
+/// A checksum over the relative paths and contents of all files within the given directories of
+/// `root`, skipping `.git` directories.
+pub fn dirs_checksum(root: &Path, dirs: &[PathBuf]) -> Result<String> {
+    let mut files = vec![];
+    for dir in dirs {
+        let walker = walkdir::WalkDir::new(root.join(dir))
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git");
+        for entry in walker {
+            let entry = entry?;
+            if entry.file_type().is_file() {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files.sort();
+    let mut hasher = fuel_crypto::Hasher::default();
+    for file in files {
+        let rel_path = file.strip_prefix(root)?;
+        let contents = fs::read(&file)?;
+        hasher.input(slash_path(rel_path).as_bytes());
+        hasher.input((contents.len() as u64).to_be_bytes());
+        hasher.input(contents);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The given relative path with its components joined by `/`, so that checksums are the same on
+/// every platform.
+pub(crate) fn slash_path(rel_path: &Path) -> String {
+    let components: Vec<_> = rel_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    components.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dirs_checksum;
+    use crate::test_util;
+    use std::{fs, path::PathBuf};
+
+    #[test]
+    fn test_dirs_checksum_covers_paths_and_contents() {
+        let dir = test_util::temp_dir();
+        let root = dir.path();
+        fs::create_dir_all(root.join("pkg/src")).unwrap();
+        fs::create_dir_all(root.join("pkg/.git")).unwrap();
+        fs::write(root.join("pkg/src/lib.sw"), "library foo;").unwrap();
+        fs::write(root.join("other.sw"), "library other;").unwrap();
+        let dirs = [PathBuf::from("pkg")];
+        let checksum = dirs_checksum(root, &dirs).unwrap();
+
+        // Files outside of the given directories and within `.git` are ignored.
+        fs::write(root.join("other.sw"), "library changed;").unwrap();
+        fs::write(root.join("pkg/.git/HEAD"), "ref: refs/heads/master").unwrap();
+        assert_eq!(checksum, dirs_checksum(root, &dirs).unwrap());
+
+        // Changing the contents or path of a file changes the checksum.
+        fs::write(root.join("pkg/src/lib.sw"), "library bar;").unwrap();
+        let modified = dirs_checksum(root, &dirs).unwrap();
+        assert_ne!(checksum, modified);
+        fs::rename(root.join("pkg/src/lib.sw"), root.join("pkg/src/main.sw")).unwrap();
+        assert_ne!(modified, dirs_checksum(root, &dirs).unwrap());
+    }
+}
@@ -4,13 +4,24 @@
 //! The project should consist of one or more Sway modules under a `src` directory. It may also
 //! declare a set of forc package dependencies within its manifest.
 
+pub mod abi;
+pub mod build_info;
+mod cache;
+pub mod checksum;
 pub mod lock;
+pub mod logs;
 pub mod manifest;
 mod pkg;
+pub mod registry;
+mod resolve;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod vendor;
 
 pub use lock::Lock;
 pub use manifest::{
-    BuildProfile, PackageManifest, PackageManifestFile, WorkspaceManifest, WorkspaceManifestFile,
+    BuildProfile, PackageManifest, PackageManifestFile, TestConfig, WorkspaceManifest,
+    WorkspaceManifestFile,
 };
 #[doc(inline)]
 pub use pkg::*;
@@ -1,4 +1,4 @@
-use crate::{pkg, DepKind, Edge};
+use crate::{checksum::Checksums, pkg, DepKind, Edge};
 use anyhow::{anyhow, Result};
 use forc_tracing::{println_green, println_red};
 use petgraph::{visit::EdgeRef, Direction};
@@ -37,6 +37,8 @@ pub struct PkgLock {
     version: Option<semver::Version>,
     // Short-hand string describing where this package is sourced from.
     source: String,
+    // Checksum of the fetched source of git and registry packages. See `checksum::dirs_checksum`.
+    checksum: Option<String>,
     dependencies: Option<Vec<PkgDepLine>>,
     contract_dependencies: Option<Vec<PkgDepLine>>,
 }
@@ -57,11 +59,17 @@ pub type PkgDepLine = String;
 
 impl PkgLock {
     /// Construct a package lock given a package's entry in the package graph.
-    pub fn from_node(graph: &pkg::Graph, node: pkg::NodeIx, disambiguate: &HashSet<&str>) -> Self {
+    pub fn from_node(
+        graph: &pkg::Graph,
+        node: pkg::NodeIx,
+        disambiguate: &HashSet<&str>,
+        checksums: &Checksums,
+    ) -> Self {
         let pinned = &graph[node];
         let name = pinned.name.clone();
+        let checksum = checksums.get(&pinned.id()).cloned();
         let version = match &pinned.source {
-            pkg::SourcePinned::Registry(reg) => Some(reg.source.version.clone()),
+            pkg::SourcePinned::Registry(reg) => Some(reg.version.clone()),
             _ => None,
         };
         let source = pinned.source.to_string();
@@ -121,6 +129,7 @@ impl PkgLock {
             name,
             version,
             source,
+            checksum,
             dependencies,
             contract_dependencies,
         }
@@ -159,15 +168,41 @@ impl Lock {
         toml::de::from_str(&string).map_err(|e| anyhow!("failed to parse lock file: {}", e))
     }
 
-    /// Given a graph of pinned packages, create a `Lock` representing the `Forc.lock` file
-    /// structure.
-    pub fn from_graph(graph: &pkg::Graph) -> Self {
+    /// Given a graph of pinned packages and the checksums of their sources, create a `Lock`
+    /// representing the `Forc.lock` file structure.
+    pub fn from_graph(graph: &pkg::Graph, checksums: &Checksums) -> Self {
         let names = graph.node_indices().map(|n| &graph[n].name[..]);
         let disambiguate: HashSet<_> = names_requiring_disambiguation(names).collect();
         // Collect the packages.
         let package: BTreeSet<_> = graph
             .node_indices()
-            .map(|node| PkgLock::from_node(graph, node, &disambiguate))
+            .map(|node| PkgLock::from_node(graph, node, &disambiguate, checksums))
+            .collect();
+        Self { package }
+    }
+
+    /// The checksum recorded for the given package, if any.
+    pub fn checksum(&self, pinned: &pkg::Pinned) -> Option<&str> {
+        let source = pinned.source.to_string();
+        self.package
+            .iter()
+            .find(|pkg| pkg.name == pinned.name && pkg.source == source)
+            .and_then(|pkg| pkg.checksum.as_deref())
+    }
+
+    /// A copy of this `Lock` with all checksums omitted.
+    ///
+    /// Useful for comparing the pinned packages of locks without regard for whether or not either
+    /// lock records their checksums.
+    pub fn without_checksums(&self) -> Self {
+        let package = self
+            .package
+            .iter()
+            .cloned()
+            .map(|pkg| PkgLock {
+                checksum: None,
+                ..pkg
+            })
             .collect();
         Self { package }
     }
@@ -0,0 +1,207 @@
+//! Decoding the `Log` and `LogData` receipts produced by the execution of a program.
+
+use crate::abi;
+use fuel_tx as tx;
+use fuels_core::{abi_decoder::ABIDecoder, Token};
+use fuels_types::{param_types::ParamType, ProgramABI};
+use std::{collections::HashMap, fmt};
+
+/// A value logged during the execution of a test.
+#[derive(Clone, Debug)]
+pub enum DecodedLog {
+    /// A log whose type was found within the package's `logged_types` and decoded accordingly.
+    Decoded { log_id: u64, token: Token },
+    /// A log that could not be decoded, e.g. one emitted by a contract called from the program.
+    Raw { log_id: u64, data: Vec<u8> },
+}
+
+/// Decode all `Log` and `LogData` receipts using the `logged_types` of the given program ABI.
+///
+/// Only logs emitted by the program with the given ID are decoded, as log IDs are only meaningful
+/// for the ABI of the program that emitted them. This is the zeroed ID for a test or script, or
+/// the contract's ID for a contract call. All remaining logs are returned in their raw form.
+pub fn decode_logs(
+    receipts: &[tx::Receipt],
+    program_abi: &ProgramABI,
+    program_id: &tx::ContractId,
+) -> Vec<DecodedLog> {
+    let type_lookup = abi::type_lookup(program_abi);
+    let logged_types = program_abi
+        .logged_types
+        .iter()
+        .flatten()
+        .map(|logged_type| (logged_type.log_id, &logged_type.application))
+        .collect::<HashMap<_, _>>();
+    receipts
+        .iter()
+        .filter_map(|receipt| {
+            let (id, log_id, data) = match receipt {
+                tx::Receipt::Log { id, ra, rb, .. } => (id, *rb, ra.to_be_bytes().to_vec()),
+                tx::Receipt::LogData { id, rb, data, .. } => (id, *rb, data.clone()),
+                _ => return None,
+            };
+            let token = (id == program_id)
+                .then(|| logged_types.get(&log_id))
+                .flatten()
+                .and_then(|application| {
+                    ParamType::try_from_type_application(application, &type_lookup).ok()
+                })
+                .and_then(|param_type| ABIDecoder::decode_single(&param_type, &data).ok());
+            let log = match token {
+                Some(token) => DecodedLog::Decoded { log_id, token },
+                None => DecodedLog::Raw { log_id, data },
+            };
+            Some(log)
+        })
+        .collect()
+}
+
+impl fmt::Display for DecodedLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodedLog::Decoded { log_id, token } => write!(f, "[log {log_id}] {token}"),
+            DecodedLog::Raw { log_id, data } => {
+                write!(f, "[log {log_id}] 0x{}", hex::encode(data))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_logs, DecodedLog};
+    use fuel_tx as tx;
+    use fuels_core::Token;
+    use fuels_types::{LoggedType, ProgramABI, TypeApplication, TypeDeclaration};
+
+    const U64_LOG_ID: u64 = 0;
+    const STRUCT_LOG_ID: u64 = 1;
+
+    fn type_application(name: &str, type_id: usize) -> TypeApplication {
+        TypeApplication {
+            name: name.to_string(),
+            type_id,
+            type_arguments: None,
+        }
+    }
+
+    /// The ABI of a program that logs a `u64` and a `Point { x: u64, y: u64 }`.
+    fn program_abi() -> ProgramABI {
+        let u64_decl = TypeDeclaration {
+            type_id: 0,
+            type_field: "u64".to_string(),
+            components: None,
+            type_parameters: None,
+        };
+        let point_decl = TypeDeclaration {
+            type_id: 1,
+            type_field: "struct Point".to_string(),
+            components: Some(vec![type_application("x", 0), type_application("y", 0)]),
+            type_parameters: None,
+        };
+        let logged_types = vec![
+            LoggedType {
+                log_id: U64_LOG_ID,
+                application: type_application("", 0),
+            },
+            LoggedType {
+                log_id: STRUCT_LOG_ID,
+                application: type_application("", 1),
+            },
+        ];
+        ProgramABI {
+            types: vec![u64_decl, point_decl],
+            functions: vec![],
+            logged_types: Some(logged_types),
+            messages_types: None,
+        }
+    }
+
+    fn log(id: tx::ContractId, value: u64, log_id: u64) -> tx::Receipt {
+        tx::Receipt::log(id, value, log_id, 0, 0, 0, 0)
+    }
+
+    fn log_data(id: tx::ContractId, data: Vec<u8>, log_id: u64) -> tx::Receipt {
+        tx::Receipt::log_data(id, 0, log_id, 0, Default::default(), data, 0, 0)
+    }
+
+    fn point_data(x: u64, y: u64) -> Vec<u8> {
+        [x.to_be_bytes(), y.to_be_bytes()].concat()
+    }
+
+    #[test]
+    fn test_decode_log_and_log_data() {
+        let id = tx::ContractId::zeroed();
+        let receipts = [
+            log(id, 42, U64_LOG_ID),
+            tx::Receipt::ret(id, 0, 0, 0),
+            log_data(id, point_data(1, 2), STRUCT_LOG_ID),
+        ];
+        let logs = decode_logs(&receipts, &program_abi(), &id);
+        assert_eq!(logs.len(), 2);
+        assert!(matches!(
+            &logs[0],
+            DecodedLog::Decoded {
+                log_id: U64_LOG_ID,
+                token: Token::U64(42)
+            }
+        ));
+        match &logs[1] {
+            DecodedLog::Decoded {
+                log_id: STRUCT_LOG_ID,
+                token: Token::Struct(fields),
+            } => assert_eq!(fields, &[Token::U64(1), Token::U64(2)]),
+            log => panic!("unexpected log {log:?}"),
+        }
+        assert_eq!(logs[0].to_string(), "[log 0] U64(42)");
+    }
+
+    #[test]
+    fn test_unknown_log_id_stays_raw() {
+        let id = tx::ContractId::zeroed();
+        let receipts = [log(id, 42, 7), log_data(id, vec![1, 2], 8)];
+        let logs: Vec<String> = decode_logs(&receipts, &program_abi(), &id)
+            .iter()
+            .map(|log| log.to_string())
+            .collect();
+        assert_eq!(logs, ["[log 7] 0x000000000000002a", "[log 8] 0x0102"]);
+    }
+
+    #[test]
+    fn test_logs_of_other_contracts_stay_raw() {
+        let program_id = tx::ContractId::zeroed();
+        let other_id = tx::ContractId::from([1u8; 32]);
+        let receipts = [
+            log(other_id, 42, U64_LOG_ID),
+            log_data(other_id, point_data(1, 2), STRUCT_LOG_ID),
+            log(program_id, 42, U64_LOG_ID),
+        ];
+        let logs = decode_logs(&receipts, &program_abi(), &program_id);
+        assert!(matches!(
+            &logs[0],
+            DecodedLog::Raw { log_id: U64_LOG_ID, data } if data == &42u64.to_be_bytes()
+        ));
+        assert!(matches!(
+            &logs[1],
+            DecodedLog::Raw { log_id: STRUCT_LOG_ID, data } if data == &point_data(1, 2)
+        ));
+        assert!(matches!(
+            &logs[2],
+            DecodedLog::Decoded {
+                token: Token::U64(42),
+                ..
+            }
+        ));
+
+        // Logs of the contract itself are decoded when its ID is given.
+        let logs = decode_logs(&receipts, &program_abi(), &other_id);
+        assert!(matches!(
+            &logs[0],
+            DecodedLog::Decoded {
+                token: Token::U64(42),
+                ..
+            }
+        ));
+        assert!(matches!(&logs[2], DecodedLog::Raw { .. }));
+    }
+}
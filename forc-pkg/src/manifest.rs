@@ -9,7 +9,9 @@ use std::{
     sync::Arc,
 };
 
-use sway_core::{fuel_prelude::fuel_tx, language::parsed::TreeType, parse_tree_type};
+use sway_core::{
+    fuel_prelude::fuel_tx, language::parsed::TreeType, parse_tree_type, OptLevel, OptPass,
+};
 pub use sway_types::ConfigTimeConstant;
 use sway_utils::constants;
 
@@ -143,8 +145,11 @@ pub struct PackageManifest {
     pub patch: Option<BTreeMap<String, PatchMap>>,
     /// A list of [configuration-time constants](https://github.com/FuelLabs/sway/issues/1498).
     pub constants: Option<BTreeMap<String, ConfigTimeConstant>>,
+    #[serde(default, deserialize_with = "deserialize_build_profiles")]
     build_profile: Option<BTreeMap<String, BuildProfile>>,
     pub contract_dependencies: Option<BTreeMap<String, ContractDependency>>,
+    /// The VM environment in which the package's unit tests are executed.
+    pub test: Option<TestConfig>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -152,6 +157,7 @@ pub struct PackageManifest {
 pub struct Project {
     pub authors: Option<Vec<String>>,
     pub name: String,
+    pub version: Option<semver::Version>,
     pub organization: Option<String>,
     pub license: String,
     #[serde(default = "default_entry")]
@@ -188,7 +194,7 @@ pub enum Dependency {
     Detailed(DependencyDetails),
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct DependencyDetails {
     pub(crate) version: Option<String>,
@@ -198,6 +204,9 @@ pub struct DependencyDetails {
     pub(crate) tag: Option<String>,
     pub(crate) package: Option<String>,
     pub(crate) rev: Option<String>,
+    pub(crate) registry: Option<String>,
+    /// Inherit the dependency from the `[workspace.dependencies]` table of the workspace.
+    pub(crate) workspace: Option<bool>,
 }
 
 /// Parameters to pass through to the `sway_core::BuildConfig` during compilation.
@@ -212,6 +221,88 @@ pub struct BuildProfile {
     pub terse: bool,
     pub time_phases: bool,
     pub include_tests: bool,
+    /// The level of optimization applied to the IR: 2 or "size".
+    ///
+    /// Within a manifest, defaults to the level of the built-in profile of the same name.
+    #[serde(default)]
+    pub opt_level: OptLevel,
+    /// The optimization passes to run, in order, in place of those implied by `opt-level`.
+    pub passes: Option<Vec<OptPass>>,
+    /// The maximum number of instructions in a function for it to be inlined regardless of how
+    /// many times it is called, in place of the threshold implied by `opt-level`.
+    pub inline_threshold: Option<usize>,
+}
+
+/// A `[build-profile.<name>]` table, whose omitted fields default to those of the built-in
+/// profile of the same name.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BuildProfileTable {
+    print_ast: bool,
+    print_dca_graph: bool,
+    print_ir: bool,
+    print_finalized_asm: bool,
+    print_intermediate_asm: bool,
+    terse: bool,
+    time_phases: bool,
+    include_tests: bool,
+    opt_level: Option<OptLevel>,
+    passes: Option<Vec<OptPass>>,
+    inline_threshold: Option<usize>,
+}
+
+/// Configuration of the transaction and VM environment in which each unit test is executed.
+///
+/// Unspecified fields fall back to the defaults used by `forc test`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TestConfig {
+    /// The block height at which tests are executed.
+    pub block_height: Option<u32>,
+    /// The gas limit of each test transaction.
+    pub gas_limit: Option<fuel_tx::Word>,
+    /// The gas price of each test transaction.
+    pub gas_price: Option<fuel_tx::Word>,
+    /// The maturity of each test transaction.
+    pub maturity: Option<fuel_tx::Word>,
+    /// The coins provided as inputs to each test transaction. These replace the single coin of
+    /// the base asset that is provided by default.
+    pub coins: Option<Vec<TestCoin>>,
+    /// Outputs added to each test transaction, in addition to those for any deployed contracts.
+    pub outputs: Option<Vec<TestOutput>>,
+}
+
+/// A coin provided as an input to each test transaction.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct TestCoin {
+    pub amount: fuel_tx::Word,
+    /// The asset of the coin. Defaults to the base asset.
+    #[serde(default)]
+    pub asset_id: fuel_tx::AssetId,
+}
+
+/// An output of each test transaction.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum TestOutput {
+    /// Transfers the given amount of an asset to the given address.
+    #[serde(rename_all = "kebab-case")]
+    Coin {
+        to: fuel_tx::Address,
+        amount: fuel_tx::Word,
+        #[serde(default)]
+        asset_id: fuel_tx::AssetId,
+    },
+    /// Returns any unspent amount of the given asset to the given address.
+    #[serde(rename_all = "kebab-case")]
+    Change {
+        to: fuel_tx::Address,
+        #[serde(default)]
+        asset_id: fuel_tx::AssetId,
+    },
+    /// An output that may be set during execution, e.g. by `transfer_to_output`.
+    Variable,
 }
 
 impl Dependency {
@@ -222,6 +313,38 @@ impl Dependency {
             Self::Detailed(ref det) => det.package.as_deref(),
         }
     }
+
+    /// Whether the dependency is to be inherited from the workspace, i.e. `workspace = true`.
+    pub fn is_workspace(&self) -> bool {
+        match *self {
+            Self::Simple(_) => false,
+            Self::Detailed(ref det) => det.workspace == Some(true),
+        }
+    }
+
+    /// Resolve any relative `path` or `registry` location against the given directory, so that
+    /// the dependency may be declared in one manifest and used from another.
+    fn rebase(&mut self, dir: &Path) -> Result<()> {
+        if let Self::Detailed(ref mut det) = *self {
+            if let Some(ref mut path) = det.path {
+                *path = dir.join(&*path).to_string_lossy().to_string();
+            }
+            if let Some(ref mut registry) = det.registry {
+                *registry = crate::registry::parse_index_location(registry, dir)?.to_string();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DependencyDetails {
+    /// The details of a dependency declared only with `workspace = true`.
+    fn workspace() -> Self {
+        Self {
+            workspace: Some(true),
+            ..Default::default()
+        }
+    }
 }
 
 impl PackageManifestFile {
@@ -233,10 +356,23 @@ impl PackageManifestFile {
     /// If `core` and `std` are unspecified, `std` will be added to the `dependencies` table
     /// implicitly. In this case, the git tag associated with the version of this crate is used to
     /// specify the pinned commit at which we fetch `std`.
+    ///
+    /// If the package is a member of a workspace, any dependencies declared with
+    /// `workspace = true`, along with the workspace's `[patch]` and `[build-profile]` tables, are
+    /// inherited from the workspace manifest.
     pub fn from_file(path: PathBuf) -> Result<Self> {
         let path = path.canonicalize()?;
-        let manifest = PackageManifest::from_file(&path)?;
-        Ok(Self { manifest, path })
+        let manifest = PackageManifest::parse(&path)?;
+        let mut manifest_file = Self { manifest, path };
+        if let Some(workspace) = manifest_file.workspace()? {
+            manifest_file.manifest.inherit_workspace(&workspace)?;
+        }
+        let manifest = &mut manifest_file.manifest;
+        manifest.check_no_workspace_deps()?;
+        manifest.implicitly_include_std_if_missing();
+        manifest.implicitly_include_default_build_profiles_if_missing();
+        manifest.validate()?;
+        Ok(manifest_file)
     }
 
     /// Read the manifest from the `Forc.toml` in the directory specified by the given `path` or
@@ -358,19 +494,17 @@ impl PackageManifestFile {
             None => return Ok(None),
             Some(dir) => dir,
         };
-        let ws_manifest = match WorkspaceManifestFile::from_dir(parent_dir) {
-            Ok(manifest) => manifest,
-            Err(e) => {
-                // Check if the error is missing workspace manifest file. Do not return that error if that
-                // is the case as we do not want to return error if this is a single project
-                // without a workspace.
-                if e.to_string().contains("could not find") {
-                    return Ok(None);
-                } else {
-                    return Err(e);
-                }
-            }
+        // The nearest manifest may be that of another package rather than a workspace, in which
+        // case this package is not a member of a workspace.
+        let ws_dir = match find_manifest_dir(parent_dir) {
+            None => return Ok(None),
+            Some(dir) => dir,
         };
+        let ws_path = ws_dir.join(constants::MANIFEST_FILE_NAME);
+        if !WorkspaceManifest::is_workspace_manifest(&ws_path)? {
+            return Ok(None);
+        }
+        let ws_manifest = WorkspaceManifestFile::from_file(ws_path)?;
         if ws_manifest.is_member_path(self.dir())? {
             Ok(Some(ws_manifest))
         } else {
@@ -405,7 +539,20 @@ impl PackageManifest {
     /// If `core` and `std` are unspecified, `std` will be added to the `dependencies` table
     /// implicitly. In this case, the git tag associated with the version of this crate is used to
     /// specify the pinned commit at which we fetch `std`.
+    ///
+    /// Dependencies declared with `workspace = true` are left as declared. Use
+    /// `PackageManifestFile::from_file` to inherit these from the package's workspace.
     pub fn from_file(path: &Path) -> Result<Self> {
+        let mut manifest = Self::parse(path)?;
+        manifest.implicitly_include_std_if_missing();
+        manifest.implicitly_include_default_build_profiles_if_missing();
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Read and deserialize the `Forc.toml` at the given path without applying any implicit
+    /// dependencies or build profiles.
+    fn parse(path: &Path) -> Result<Self> {
         // While creating a `ManifestFile` we need to check if the given path corresponds to a
         // package or a workspace. While doing so, we should be printing the warnings if the given
         // file parses so that we only see warnings for the correct type of manifest.
@@ -413,7 +560,7 @@ impl PackageManifest {
         let manifest_str = std::fs::read_to_string(path)
             .map_err(|e| anyhow!("failed to read manifest at {:?}: {}", path, e))?;
         let toml_de = &mut toml::de::Deserializer::new(&manifest_str);
-        let mut manifest: Self = serde_ignored::deserialize(toml_de, |path| {
+        let manifest: Self = serde_ignored::deserialize(toml_de, |path| {
             let warning = format!("  WARNING! unused manifest key: {}", path);
             warnings.push(warning);
         })
@@ -421,12 +568,84 @@ impl PackageManifest {
         for warning in warnings {
             println_yellow_err(&warning);
         }
-        manifest.implicitly_include_std_if_missing();
-        manifest.implicitly_include_default_build_profiles_if_missing();
-        manifest.validate()?;
         Ok(manifest)
     }
 
+    /// Inherit dependencies, patches and build profiles from the given workspace.
+    ///
+    /// Dependencies declared with `workspace = true` are replaced with the workspace's declaration
+    /// of the same name. Patches and build profiles declared by the workspace are added unless the
+    /// package declares its own patch or build profile of the same name.
+    fn inherit_workspace(&mut self, workspace: &WorkspaceManifestFile) -> Result<()> {
+        let pkg_name = &self.project.name;
+        let inherit = |dep_name: &str, dep: &mut Dependency| -> Result<()> {
+            if !dep.is_workspace() {
+                return Ok(());
+            }
+            match dep {
+                Dependency::Detailed(det) if *det == DependencyDetails::workspace() => (),
+                _ => bail!(
+                    "dependency {:?} of {:?} specifies `workspace = true` alongside other fields",
+                    dep_name,
+                    pkg_name,
+                ),
+            }
+            *dep = workspace.dep(dep_name).cloned().ok_or_else(|| {
+                anyhow!(
+                    "dependency {:?} of {:?} is inherited from the workspace, but no \
+                    dependency of that name is declared under `[workspace.dependencies]` in {}",
+                    dep_name,
+                    pkg_name,
+                    workspace.path().display(),
+                )
+            })?;
+            Ok(())
+        };
+        for (dep_name, dep) in self.dependencies.iter_mut().flatten() {
+            inherit(dep_name, dep)?;
+        }
+        for (dep_name, dep) in self.contract_dependencies.iter_mut().flatten() {
+            inherit(dep_name, &mut dep.dependency)?;
+        }
+        for (patch_name, ws_patch) in workspace.patches() {
+            let patch = self
+                .patch
+                .get_or_insert_with(Default::default)
+                .entry(patch_name.clone())
+                .or_default();
+            for (dep_name, dep) in ws_patch {
+                patch.entry(dep_name.clone()).or_insert_with(|| dep.clone());
+            }
+        }
+        for (profile_name, profile) in workspace.build_profiles() {
+            self.build_profile
+                .get_or_insert_with(Default::default)
+                .entry(profile_name.clone())
+                .or_insert_with(|| profile.clone());
+        }
+        Ok(())
+    }
+
+    /// Ensure that no dependencies remain that are to be inherited from a workspace.
+    fn check_no_workspace_deps(&self) -> Result<()> {
+        let deps = self.deps().chain(
+            self.contract_deps()
+                .map(|(name, dep)| (name, &dep.dependency)),
+        );
+        for (dep_name, dep) in deps {
+            if dep.is_workspace() {
+                bail!(
+                    "dependency {:?} of {:?} is inherited from the workspace, but {:?} is not \
+                    a member of a workspace",
+                    dep_name,
+                    self.project.name,
+                    self.project.name,
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Validate the `PackageManifest`.
     ///
     /// This checks the project and organization names against a set of reserved/restricted
@@ -603,6 +822,9 @@ impl BuildProfile {
             terse: false,
             time_phases: false,
             include_tests: false,
+            opt_level: OptLevel::O2,
+            passes: None,
+            inline_threshold: None,
         }
     }
 
@@ -616,10 +838,60 @@ impl BuildProfile {
             terse: false,
             time_phases: false,
             include_tests: false,
+            opt_level: OptLevel::O2,
+            passes: None,
+            inline_threshold: None,
+        }
+    }
+
+    /// The built-in profile of the given name, or the `debug` profile if there is none.
+    pub fn named(name: &str) -> Self {
+        match name {
+            Self::RELEASE => Self::release(),
+            _ => Self::debug(),
         }
     }
 }
 
+impl BuildProfileTable {
+    fn into_profile(self, name: &str) -> BuildProfile {
+        let default = BuildProfile::named(name);
+        BuildProfile {
+            print_ast: self.print_ast,
+            print_dca_graph: self.print_dca_graph,
+            print_ir: self.print_ir,
+            print_finalized_asm: self.print_finalized_asm,
+            print_intermediate_asm: self.print_intermediate_asm,
+            terse: self.terse,
+            time_phases: self.time_phases,
+            include_tests: self.include_tests,
+            opt_level: self.opt_level.unwrap_or(default.opt_level),
+            passes: self.passes,
+            inline_threshold: self.inline_threshold,
+        }
+    }
+}
+
+/// Deserialize the `[build-profile]` tables of a manifest, defaulting omitted fields to those of
+/// the built-in profile of the same name.
+fn deserialize_build_profiles<'de, D>(
+    deserializer: D,
+) -> Result<Option<BTreeMap<String, BuildProfile>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let tables = Option::<BTreeMap<String, BuildProfileTable>>::deserialize(deserializer)?;
+    Ok(tables.map(|tables| {
+        tables
+            .into_iter()
+            .map(|(name, table)| {
+                let profile = table.into_profile(&name);
+                (name, profile)
+            })
+            .collect()
+    }))
+}
+
 impl std::ops::Deref for PackageManifestFile {
     type Target = PackageManifest;
     fn deref(&self) -> &Self::Target {
@@ -693,12 +965,20 @@ pub struct WorkspaceManifestFile {
 #[serde(rename_all = "kebab-case")]
 pub struct WorkspaceManifest {
     workspace: Workspace,
+    /// Patches applied to the dependencies of every member.
+    pub patch: Option<BTreeMap<String, PatchMap>>,
+    /// Build profiles available to every member. Members may override a profile by declaring a
+    /// profile of the same name.
+    #[serde(default, deserialize_with = "deserialize_build_profiles")]
+    build_profile: Option<BTreeMap<String, BuildProfile>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct Workspace {
     pub members: Vec<PathBuf>,
+    /// Dependencies that members may inherit by declaring them with `workspace = true`.
+    pub dependencies: Option<BTreeMap<String, Dependency>>,
 }
 
 impl WorkspaceManifestFile {
@@ -784,7 +1064,24 @@ impl WorkspaceManifestFile {
 }
 
 impl WorkspaceManifest {
+    /// Whether the `Forc.toml` at the given path declares a `[workspace]` table.
+    fn is_workspace_manifest(path: &Path) -> Result<bool> {
+        #[derive(Deserialize)]
+        struct Tables {
+            workspace: Option<toml::value::Table>,
+        }
+        let manifest_str = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read manifest at {:?}: {}", path, e))?;
+        let tables: Tables = toml::from_str(&manifest_str)
+            .map_err(|e| anyhow!("failed to parse manifest: {}.", e))?;
+        Ok(tables.workspace.is_some())
+    }
+
     /// Given a path to a `Forc.toml`, read it and construct a `WorkspaceManifest`.
+    ///
+    /// Relative paths within the `[workspace.dependencies]` and `[patch]` tables are resolved
+    /// against the directory containing the manifest, as they are inherited by members in other
+    /// directories.
     pub fn from_file(path: &Path) -> Result<Self> {
         // While creating a `ManifestFile` we need to check if the given path corresponds to a
         // package or a workspace. While doing so, we should be printing the warnings if the given
@@ -793,7 +1090,7 @@ impl WorkspaceManifest {
         let manifest_str = std::fs::read_to_string(path)
             .map_err(|e| anyhow!("failed to read manifest at {:?}: {}", path, e))?;
         let toml_de = &mut toml::de::Deserializer::new(&manifest_str);
-        let manifest: Self = serde_ignored::deserialize(toml_de, |path| {
+        let mut manifest: Self = serde_ignored::deserialize(toml_de, |path| {
             let warning = format!("  WARNING! unused manifest key: {}", path);
             warnings.push(warning);
         })
@@ -801,9 +1098,50 @@ impl WorkspaceManifest {
         for warning in warnings {
             println_yellow_err(&warning);
         }
+        let dir = path
+            .parent()
+            .ok_or_else(|| anyhow!("Cannot get parent dir of {:?}", path))?;
+        let deps = manifest.workspace.dependencies.iter_mut().flatten();
+        let patches = manifest.patch.iter_mut().flatten().flat_map(|(_, p)| p);
+        for (_, dep) in deps.chain(patches) {
+            dep.rebase(dir)?;
+        }
         Ok(manifest)
     }
 
+    /// Retrieve a reference to the workspace dependency with the given name.
+    pub fn dep(&self, dep_name: &str) -> Option<&Dependency> {
+        self.workspace
+            .dependencies
+            .as_ref()
+            .and_then(|deps| deps.get(dep_name))
+    }
+
+    /// Produce an iterator yielding all listed workspace dependencies.
+    pub fn deps(&self) -> impl Iterator<Item = (&String, &Dependency)> {
+        self.workspace
+            .dependencies
+            .as_ref()
+            .into_iter()
+            .flat_map(|deps| deps.iter())
+    }
+
+    /// Produce an iterator yielding all listed patches.
+    pub fn patches(&self) -> impl Iterator<Item = (&String, &PatchMap)> {
+        self.patch
+            .as_ref()
+            .into_iter()
+            .flat_map(|patches| patches.iter())
+    }
+
+    /// Produce an iterator yielding all listed build profiles.
+    pub fn build_profiles(&self) -> impl Iterator<Item = (&String, &BuildProfile)> {
+        self.build_profile
+            .as_ref()
+            .into_iter()
+            .flat_map(|profiles| profiles.iter())
+    }
+
     /// Validate the `WorkspaceManifest`
     ///
     /// This checks if the listed members in the `WorkspaceManifest` are indeed in the given `Forc.toml`'s directory.
@@ -828,3 +1166,183 @@ impl std::ops::Deref for WorkspaceManifestFile {
         &self.manifest
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, TempDir};
+    use std::fs;
+
+    /// The `[build-profile.<name>]` table of a profile that is `terse` or not.
+    fn profile_table(name: &str, terse: bool) -> String {
+        format!(
+            "[build-profile.{name}]\nprint-ast = false\nprint-dca-graph = false\nprint-ir = false\n\
+            print-finalized-asm = false\nprint-intermediate-asm = false\nterse = {terse}\n\
+            time-phases = false\ninclude-tests = false\n"
+        )
+    }
+
+    /// Write a workspace with the given tables and a single library member named `app`, declaring
+    /// the given member tables. Returns the workspace directory and the member's manifest.
+    fn write_workspace(
+        ws_tables: &str,
+        app_tables: &str,
+    ) -> (TempDir, Result<PackageManifestFile>) {
+        let dir = test_util::temp_dir();
+        let manifest = format!("[workspace]\nmembers = [\"app\"]\n\n{ws_tables}");
+        fs::write(dir.path().join(constants::MANIFEST_FILE_NAME), manifest).unwrap();
+        let app_dir = dir.path().join("app");
+        test_util::write_lib(&app_dir, "app", app_tables);
+        let app = PackageManifestFile::from_dir(&app_dir);
+        (dir, app)
+    }
+
+    #[test]
+    fn test_inherit_workspace_dep() {
+        let ws_tables = "[workspace.dependencies]\nshared = { path = \"shared\" }\n";
+        let app_tables = "[dependencies]\nshared = { workspace = true }\n";
+        let (dir, app) = write_workspace(ws_tables, app_tables);
+        let app = app.unwrap();
+        let expected = dir.path().canonicalize().unwrap().join("shared");
+        let details = app.dep_detailed("shared").unwrap();
+        assert_eq!(details.path.as_deref(), Some(expected.to_str().unwrap()));
+        assert_eq!(details.workspace, None);
+    }
+
+    #[test]
+    fn test_inherit_workspace_dep_with_other_fields() {
+        let ws_tables = "[workspace.dependencies]\nshared = { path = \"shared\" }\n";
+        let app_tables = "[dependencies]\nshared = { workspace = true, path = \"../shared\" }\n";
+        let (_dir, app) = write_workspace(ws_tables, app_tables);
+        let err = app.unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "dependency \"shared\" of \"app\" specifies `workspace = true` alongside other fields"
+        );
+    }
+
+    #[test]
+    fn test_inherit_missing_workspace_dep() {
+        let ws_tables = "[workspace.dependencies]\nshared = { path = \"shared\" }\n";
+        let app_tables = "[contract-dependencies]\nother = { workspace = true }\n";
+        let (dir, app) = write_workspace(ws_tables, app_tables);
+        let err = app.unwrap_err().to_string();
+        let ws_path = dir
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join(constants::MANIFEST_FILE_NAME);
+        assert_eq!(
+            err,
+            format!(
+                "dependency \"other\" of \"app\" is inherited from the workspace, but no \
+                dependency of that name is declared under `[workspace.dependencies]` in {}",
+                ws_path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_member_overrides_workspace_patches_and_profiles() {
+        let ws_tables = format!(
+            "[patch.'https://github.com/fuellabs/sway']\nstd = {{ path = \"ws-std\" }}\n\
+            core = {{ path = \"ws-core\" }}\n\n{}\n{}",
+            profile_table("ci", false),
+            profile_table("audit", false),
+        );
+        let app_tables = format!(
+            "[patch.'https://github.com/fuellabs/sway']\nstd = {{ path = \"../app-std\" }}\n\n{}",
+            profile_table("ci", true),
+        );
+        let (dir, app) = write_workspace(&ws_tables, &app_tables);
+        let app = app.unwrap();
+        let patch = app.patch("https://github.com/fuellabs/sway").unwrap();
+
+        // The member's own patch and profile take precedence over those of the workspace.
+        let std_patch = match &patch["std"] {
+            Dependency::Detailed(det) => det.path.as_deref(),
+            Dependency::Simple(_) => None,
+        };
+        assert_eq!(std_patch, Some("../app-std"));
+        assert!(app.build_profile("ci").unwrap().terse);
+
+        // Those the member does not declare are inherited.
+        let ws_dir = dir.path().canonicalize().unwrap();
+        let core_patch = match &patch["core"] {
+            Dependency::Detailed(det) => det.path.clone(),
+            Dependency::Simple(_) => None,
+        };
+        assert_eq!(
+            core_patch.as_deref(),
+            Some(ws_dir.join("ws-core").to_str().unwrap())
+        );
+        assert!(!app.build_profile("audit").unwrap().terse);
+    }
+
+    #[test]
+    fn test_build_profile_opt_level() {
+        let profile = |opt_level: &str| {
+            let table = profile_table("release", false);
+            let manifest = format!("{table}opt-level = {opt_level}\n");
+            let mut tables: BTreeMap<String, BTreeMap<String, BuildProfile>> =
+                toml::from_str(&manifest)?;
+            Ok::<_, toml::de::Error>(tables.remove("build-profile").unwrap().remove("release"))
+        };
+        assert_eq!(profile("2").unwrap().unwrap().opt_level, OptLevel::O2);
+        assert_eq!(
+            profile("\"size\"").unwrap().unwrap().opt_level,
+            OptLevel::Size
+        );
+        // Lower levels are refused until they compile every program correctly.
+        for invalid in ["0", "1", "3", "\"fast\""] {
+            assert!(profile(invalid).is_err());
+        }
+
+        // Levels are serialized as they are declared.
+        for (opt_level, expected) in [(OptLevel::O2, "2"), (OptLevel::Size, "\"size\"")] {
+            let profile = BuildProfile {
+                opt_level,
+                ..BuildProfile::release()
+            };
+            let serialized = toml::to_string(&profile).unwrap();
+            assert!(serialized.contains(&format!("opt-level = {expected}\n")));
+            let deserialized: BuildProfile = toml::from_str(&serialized).unwrap();
+            assert_eq!(deserialized.opt_level, opt_level);
+        }
+
+        // Both built-in profiles use the default level, optimizing for size is opt-in.
+        assert_eq!(BuildProfile::debug().opt_level, OptLevel::O2);
+        assert_eq!(BuildProfile::release().opt_level, OptLevel::O2);
+    }
+
+    #[test]
+    fn test_build_profile_opt_level_defaults_to_named_profile() {
+        let dir = test_util::temp_dir();
+        let tables = format!(
+            "{}\n{}\n{}",
+            profile_table(BuildProfile::DEBUG, false),
+            profile_table(BuildProfile::RELEASE, false),
+            profile_table("ci", false),
+        );
+        test_util::write_lib(dir.path(), "app", &tables);
+        let app = PackageManifestFile::from_dir(dir.path()).unwrap();
+
+        // Tables that omit `opt-level` take it from the built-in profile of the same name.
+        for name in [BuildProfile::DEBUG, BuildProfile::RELEASE, "ci"] {
+            assert_eq!(
+                app.build_profile(name).unwrap().opt_level,
+                BuildProfile::named(name).opt_level
+            );
+        }
+    }
+
+    #[test]
+    fn test_nested_package_is_not_a_workspace_member() {
+        let dir = test_util::temp_dir();
+        test_util::write_lib(dir.path(), "outer", "");
+        let inner_dir = dir.path().join("inner");
+        test_util::write_lib(&inner_dir, "inner", "");
+        let inner = PackageManifestFile::from_dir(&inner_dir).unwrap();
+        assert!(inner.workspace().unwrap().is_none());
+    }
+}
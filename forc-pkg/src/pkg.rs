@@ -1,15 +1,20 @@
 use crate::{
+    build_info::{BuildInfo, SWAY_BUILD_INFO_SUFFIX},
+    cache,
+    checksum::{self, Checksums},
     lock::Lock,
     manifest::{
         BuildProfile, ConfigTimeConstant, Dependency, ManifestFile, MemberManifestFiles,
         PackageManifest, PackageManifestFile,
     },
+    registry, resolve,
+    vendor::{self, Vendor},
     CORE, PRELUDE, STD,
 };
 use anyhow::{anyhow, bail, Context, Error, Result};
 use forc_util::{
     default_output_directory, find_file_name, git_checkouts_directory, kebab_to_snake_case,
-    print_on_failure, print_on_success, user_forc_directory,
+    print_on_failure, print_on_fresh, print_on_success, user_forc_directory,
 };
 use petgraph::{
     self,
@@ -24,7 +29,9 @@ use std::{
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
 };
+use sway_ast::{literal::LitBoolType, Literal};
 use sway_core::{
     fuel_prelude::{
         fuel_crypto,
@@ -39,10 +46,11 @@ use sway_core::{
     },
     semantic_analysis::namespace,
     source_map::SourceMap,
+    transform::AttributeKind,
     CompileResult, CompiledBytecode, FinalizedEntry,
 };
-use sway_error::error::CompileError;
-use sway_types::Ident;
+use sway_error::{error::CompileError, warning::CompileWarning};
+use sway_types::{Ident, Span, Spanned};
 use sway_utils::constants;
 use tracing::{info, warn};
 use url::Url;
@@ -88,12 +96,94 @@ pub struct PinnedId(u64);
 #[derive(Debug, Clone)]
 pub struct BuiltPackage {
     pub json_abi_program: fuels_types::ProgramABI,
+    /// A JSON ABI describing each of the package's unit tests as a function. `None` unless the
+    /// package was built with tests included.
+    pub json_abi_tests: Option<fuels_types::ProgramABI>,
     pub storage_slots: Vec<StorageSlot>,
     pub bytecode: Vec<u8>,
+    /// The number of instructions in the bytecode's preamble, after which the first entry begins.
+    pub preamble_len: u64,
+    /// The entry points of the program, including any tests.
     pub entries: Vec<FinalizedEntry>,
     pub tree_type: TreeType,
-    source_map: SourceMap,
+    pub(crate) source_map: SourceMap,
     pub pkg_name: String,
+    /// The warnings produced while compiling the package. Always empty for packages loaded from
+    /// the build cache, as only packages without warnings are cached.
+    pub warnings: Vec<CompileWarning>,
+    /// Metadata describing how the package was built. Only present for the packages requested as
+    /// outputs of the build.
+    pub build_info: Option<BuildInfo>,
+    /// The declaration of each test, in the order of the test entries within `entries`.
+    pub test_decls: Vec<TestDecl>,
+}
+
+/// The declaration of a unit test.
+///
+/// Unlike the declaration ID of a test entry, this remains meaningful beyond the compilation that
+/// produced it, allowing the tests of packages loaded from the build cache to be executed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestDecl {
+    /// The name of each argument of the test's `#[test]` attribute along with its value, if any.
+    pub args: Vec<(String, Option<String>)>,
+    /// The file in which the test is declared.
+    pub path: Option<PathBuf>,
+    /// The byte offset of the start of the test's declaration within its file.
+    pub start: usize,
+    /// The byte offset of the end of the test's declaration within its file.
+    pub end: usize,
+}
+
+impl TestDecl {
+    /// Record the declaration of the given test entry point.
+    fn from_entry(entry: &FinalizedEntry) -> Result<Self> {
+        let decl_id = entry
+            .test_decl_id
+            .clone()
+            .expect("test entry point is missing declaration id");
+        let span = decl_id.span();
+        let decl = sway_core::declaration_engine::de_get_function(decl_id, &span)
+            .map_err(|e| anyhow!("missing declaration for test: {:?}", e))?;
+        let args = decl
+            .attributes
+            .get(&AttributeKind::Test)
+            .into_iter()
+            .flatten()
+            .flat_map(|attr| attr.args.iter())
+            .map(|arg| {
+                let value = arg.value.as_ref().map(|value| match value {
+                    Literal::String(lit) => lit.parsed.clone(),
+                    Literal::Char(lit) => lit.parsed.to_string(),
+                    Literal::Int(lit) => lit.parsed.to_string(),
+                    Literal::Bool(lit) => (lit.kind == LitBoolType::True).to_string(),
+                });
+                (arg.name.to_string(), value)
+            })
+            .collect();
+        Ok(Self {
+            args,
+            path: decl.span.path().map(|path| path.to_path_buf()),
+            start: decl.span.start(),
+            end: decl.span.end(),
+        })
+    }
+
+    /// The span of the test's declaration, read from the file in which it is declared.
+    pub fn span(&self) -> Result<Span> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(Span::dummy()),
+        };
+        let src = fs::read_to_string(path)
+            .with_context(|| format!("failed to read test source {}", path.display()))?;
+        Span::new(
+            src.into(),
+            self.start,
+            self.end,
+            Some(Arc::new(path.clone())),
+        )
+        .ok_or_else(|| anyhow!("test declaration is outside of {}", path.display()))
+    }
 }
 
 /// The result of successfully compiling a workspace.
@@ -138,7 +228,7 @@ pub enum Source {
     Git(SourceGit),
     /// A path to a directory with a `Forc.toml` manifest at its root.
     Path(PathBuf),
-    /// A forc project hosted on the official registry.
+    /// A forc project published to a registry index.
     Registry(SourceRegistry),
 }
 
@@ -163,10 +253,14 @@ pub enum GitReference {
     DefaultBranch,
 }
 
-/// A package from the official registry.
+/// A package published to a registry index.
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
 pub struct SourceRegistry {
+    /// The URL of the registry index in which the package is published.
+    pub index: Url,
     /// The base version specified for the package.
+    ///
+    /// The latest published version that is semver-compatible with this version is selected.
     pub version: semver::Version,
 }
 
@@ -222,6 +316,7 @@ pub struct BuildPlan {
     graph: Graph,
     manifest_map: ManifestMap,
     compilation_order: Vec<NodeIx>,
+    checksums: Checksums,
 }
 
 /// Error returned upon failed parsing of `PinnedId::from_str`.
@@ -241,6 +336,14 @@ pub enum SourceGitPinnedParseError {
     CommitHash,
 }
 
+/// Error returned upon failed parsing of `SourceRegistryPinned::from_str`.
+#[derive(Clone, Debug)]
+pub enum SourceRegistryPinnedParseError {
+    Prefix,
+    Url,
+    Version,
+}
+
 /// Represents the Head's commit hash and time (in seconds) from epoch
 type HeadWithTime = (String, i64);
 
@@ -323,6 +426,8 @@ pub struct BuildOpts {
     pub time_phases: bool,
     /// Include all test functions within the build.
     pub tests: bool,
+    /// Build every package from source without loading from or writing to the build cache.
+    pub no_cache: bool,
 }
 
 impl GitSourceIndex {
@@ -341,6 +446,14 @@ impl Edge {
 }
 
 impl BuiltPackage {
+    /// The package's test entry points, along with the declaration of each test.
+    pub fn tests(&self) -> impl Iterator<Item = (&FinalizedEntry, &TestDecl)> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_test())
+            .zip(&self.test_decls)
+    }
+
     /// Writes bytecode of the BuiltPackage to the given `path`.
     pub fn write_bytecode(&self, path: &Path) -> Result<()> {
         fs::write(path, &self.bytecode)?;
@@ -383,6 +496,11 @@ impl BuiltPackage {
             };
             res?
         }
+        if let Some(build_info) = &self.build_info {
+            let build_info_path =
+                output_dir.join(format!("{}{}", pkg_name, SWAY_BUILD_INFO_SUFFIX));
+            build_info.write(&build_info_path)?;
+        }
         info!("  Bytecode size is {} bytes.", self.bytecode.len());
         // Additional ops required depending on the program type
         match self.tree_type {
@@ -468,20 +586,50 @@ impl BuildPlan {
     pub fn from_manifests(manifests: &MemberManifestFiles, offline: bool) -> Result<Self> {
         // Check toolchain version
         validate_version(manifests)?;
+        let vendor = Vendor::from_manifests(manifests)?;
+        let vendor = vendor.as_ref();
         let mut graph = Graph::default();
         let mut manifest_map = ManifestMap::default();
-        fetch_graph(manifests, offline, &mut graph, &mut manifest_map)?;
+        fetch_graph(manifests, offline, vendor, &mut graph, &mut manifest_map)?;
         // Validate the graph, since we constructed the graph from scratch the paths will not be a
         // problem but the version check is still needed
-        validate_graph(&graph, manifests)?;
+        validate_graph(&graph, manifests, vendor)?;
         let compilation_order = compilation_order(&graph)?;
+        let checksums = checksum::graph_checksums(&graph, &manifest_map, vendor)?;
         Ok(Self {
             graph,
             manifest_map,
             compilation_order,
+            checksums,
         })
     }
 
+    /// Create a new build plan from the given `BuildOpts`.
+    ///
+    /// Loads the manifest at the path specified within the options (or the current directory)
+    /// along with its lock file, and constructs the build plan via `from_lock_and_manifests`.
+    pub fn from_build_opts(build_options: &BuildOpts) -> Result<Self> {
+        let path = &build_options.pkg.path;
+        let manifest_dir = if let Some(ref path) = path {
+            PathBuf::from(path)
+        } else {
+            std::env::current_dir()?
+        };
+        let manifest_file = ManifestFile::from_dir(&manifest_dir)?;
+        let member_manifests = manifest_file.member_manifests()?;
+        // Check if we have members to build so that we are not trying to build an empty workspace.
+        if member_manifests.is_empty() {
+            bail!("No member found to build")
+        }
+        let lock_path = manifest_file.lock_path()?;
+        Self::from_lock_and_manifests(
+            &lock_path,
+            &member_manifests,
+            build_options.pkg.locked,
+            build_options.pkg.offline,
+        )
+    }
+
     /// Create a new build plan taking into account the state of both the PackageManifest and the existing
     /// lock file if there is one.
     ///
@@ -503,6 +651,44 @@ impl BuildPlan {
         manifests: &MemberManifestFiles,
         locked: bool,
         offline: bool,
+    ) -> Result<Self> {
+        let vendor = Vendor::from_lock_path(lock_path)?;
+        Self::from_lock_and_manifests_with_vendor(
+            lock_path,
+            manifests,
+            locked,
+            offline,
+            vendor.as_ref(),
+            false,
+        )
+    }
+
+    /// Create a new build plan in the same manner as `from_lock_and_manifests`, but without
+    /// regard for the project's vendored sources, if any.
+    ///
+    /// Git and registry dependencies are resolved from the user's forc directory and fetched as
+    /// necessary. This is used to vendor the sources in the first place.
+    pub fn from_lock_and_manifests_unvendored(
+        lock_path: &Path,
+        manifests: &MemberManifestFiles,
+        locked: bool,
+        offline: bool,
+    ) -> Result<Self> {
+        Self::from_lock_and_manifests_with_vendor(
+            lock_path, manifests, locked, offline, None, false,
+        )
+    }
+
+    /// In the case that the source of a package does not match the checksum recorded within the
+    /// lock, the source is fetched again and the plan is reconstructed with `refetched` set. The
+    /// mismatch is an error if it persists, if the source is vendored or if `locked` is specified.
+    fn from_lock_and_manifests_with_vendor(
+        lock_path: &Path,
+        manifests: &MemberManifestFiles,
+        locked: bool,
+        offline: bool,
+        vendor: Option<&Vendor>,
+        refetched: bool,
     ) -> Result<Self> {
         // Check toolchain version
         validate_version(manifests)?;
@@ -525,12 +711,20 @@ impl BuildPlan {
             Graph::default()
         });
 
+        // Registry packages are locked to an exact version. Fetch any that are missing from the
+        // user's forc directory, rather than have validation remove them and re-pin them to the
+        // latest compatible version.
+        if vendor.is_none() {
+            let fetch_id = fetch_id(lock_path, std::time::Instant::now());
+            fetch_locked_registry_pkgs(&graph, fetch_id, offline)?;
+        }
+
         // Since the lock file was last created there are many ways in which it might have been
         // invalidated. E.g. a package's manifest `[dependencies]` table might have changed, a user
         // might have edited the `Forc.lock` file when they shouldn't have, a path dependency no
         // longer exists at its specified location, etc. We must first remove all invalid nodes
         // before we can determine what we need to fetch.
-        let invalid_deps = validate_graph(&graph, manifests)?;
+        let invalid_deps = validate_graph(&graph, manifests, vendor)?;
         let members: HashSet<String> = manifests
             .iter()
             .map(|(member_name, _)| member_name.clone())
@@ -539,25 +733,75 @@ impl BuildPlan {
 
         // We know that the remaining nodes have valid paths, otherwise they would have been
         // removed. We can safely produce an initial `manifest_map`.
-        let mut manifest_map = graph_to_manifest_map(manifests, &graph)?;
+        let mut manifest_map = graph_to_manifest_map(manifests, &graph, vendor)?;
 
         // Attempt to fetch the remainder of the graph.
-        let _added = fetch_graph(manifests, offline, &mut graph, &mut manifest_map)?;
+        let _added = fetch_graph(manifests, offline, vendor, &mut graph, &mut manifest_map)?;
 
         // Determine the compilation order.
         let compilation_order = compilation_order(&graph)?;
 
+        // Verify the fetched sources against the checksums recorded within the lock.
+        let checksums = checksum::graph_checksums(&graph, &manifest_map, vendor)?;
+        let mismatched: Vec<&Pinned> = graph
+            .node_indices()
+            .map(|n| &graph[n])
+            .filter(|pinned| match lock.checksum(pinned) {
+                Some(recorded) => checksums.get(&pinned.id()).map(|s| &s[..]) != Some(recorded),
+                None => false,
+            })
+            .collect();
+        if !mismatched.is_empty() {
+            let pkgs: Vec<String> = mismatched
+                .iter()
+                .map(|pinned| format!("`{}` ({})", pinned.name, pinned.source))
+                .collect();
+            let help = if vendor.is_some() {
+                "Run `forc vendor --verify` for details."
+            } else if locked {
+                "The sources may have been modified or partially fetched. Build without `--locked` \
+                to fetch them again."
+            } else if refetched {
+                "The pinned sources have changed since the lock file was created. Run `forc update` \
+                to record their current checksums."
+            } else {
+                let fetch_id = fetch_id(lock_path, std::time::Instant::now());
+                for (pinned, pkg) in mismatched.iter().zip(&pkgs) {
+                    warn!(
+                        "  Checksum of {} does not match the lock file, fetching it again",
+                        pkg
+                    );
+                    fetch_source(fetch_id, pinned, offline, true)?;
+                }
+                return Self::from_lock_and_manifests_with_vendor(
+                    lock_path, manifests, locked, offline, vendor, true,
+                );
+            };
+            bail!(
+                "the sources of the following packages do not match the checksums recorded within \
+                {}:\n  {}\n{}",
+                lock_path.display(),
+                pkgs.join("\n  "),
+                help,
+            );
+        }
+
         let plan = Self {
             graph,
             manifest_map,
             compilation_order,
+            checksums,
         };
 
-        // Construct the new lock and check the diff.
-        let new_lock = Lock::from_graph(plan.graph());
-        let lock_diff = new_lock.diff(&lock);
+        // Construct the new lock and check the diff. Packages are compared without regard for
+        // their checksums, as these were verified above and may be missing from older locks.
+        let new_lock = Lock::from_graph(plan.graph(), plan.checksums());
+        let (new_pkgs, old_pkgs) = (new_lock.without_checksums(), lock.without_checksums());
+        let lock_diff = new_pkgs.diff(&old_pkgs);
         if !lock_diff.removed.is_empty() || !lock_diff.added.is_empty() {
             new_lock_cause.get_or_insert(anyhow!("lock file did not match manifest"));
+        } else if !locked && !new_lock.diff(&lock).added.is_empty() {
+            new_lock_cause.get_or_insert(anyhow!("lock file did not record package checksums"));
         }
 
         // If there was some change in the lock file, write the new one and print the cause.
@@ -607,6 +851,11 @@ impl BuildPlan {
         &self.manifest_map
     }
 
+    /// The checksum of the fetched source of each git and registry package within the plan.
+    pub fn checksums(&self) -> &Checksums {
+        &self.checksums
+    }
+
     /// The order in which nodes are compiled, determined via a toposort of the package graph.
     pub fn compilation_order(&self) -> &[NodeIx] {
         &self.compilation_order
@@ -625,7 +874,19 @@ impl BuildPlan {
         bfs.iter(&self.graph)
     }
 
+    /// Produce an iterator yielding indices for all contract dependencies of the given node, both
+    /// direct and transitive, in BFS order.
+    ///
+    /// These are the contracts that must be deployed in order for the given node to call into them.
+    pub fn contract_deps(&self, n: NodeIx) -> impl '_ + Iterator<Item = NodeIx> {
+        self.node_deps(n)
+            .filter(move |&dep| dep != n && is_contract_dependency(&self.graph, dep))
+    }
+
     /// Produce an iterator yielding build profiles from the member nodes of this BuildPlan.
+    ///
+    /// The build profiles of workspace members include those inherited from the workspace, with
+    /// any profile declared by the member itself taking precedence.
     pub fn build_profiles(&self) -> impl '_ + Iterator<Item = (String, BuildProfile)> {
         let manifest_map = &self.manifest_map;
         let graph = &self.graph;
@@ -696,7 +957,7 @@ fn validate_pkg_version(pkg_manifest: &PackageManifestFile) -> Result<()> {
     Ok(())
 }
 
-fn member_nodes(g: &Graph) -> impl Iterator<Item = NodeIx> + '_ {
+pub(crate) fn member_nodes(g: &Graph) -> impl Iterator<Item = NodeIx> + '_ {
     g.node_indices()
         .filter(|&n| g[n].source == SourcePinned::Member)
 }
@@ -704,7 +965,11 @@ fn member_nodes(g: &Graph) -> impl Iterator<Item = NodeIx> + '_ {
 /// Validates the state of the pinned package graph against the given ManifestFile.
 ///
 /// Returns the set of invalid dependency edges.
-fn validate_graph(graph: &Graph, manifests: &MemberManifestFiles) -> Result<BTreeSet<EdgeIx>> {
+fn validate_graph(
+    graph: &Graph,
+    manifests: &MemberManifestFiles,
+    vendor: Option<&Vendor>,
+) -> Result<BTreeSet<EdgeIx>> {
     let mut member_pkgs: HashMap<&String, &PackageManifestFile> = manifests.iter().collect();
     let member_nodes: Vec<_> = member_nodes(graph)
         .filter_map(|n| member_pkgs.remove(&graph[n].name).map(|pkg| (n, pkg)))
@@ -718,32 +983,35 @@ fn validate_graph(graph: &Graph, manifests: &MemberManifestFiles) -> Result<BTre
     let mut visited = HashSet::new();
     let edges = member_nodes
         .into_iter()
-        .flat_map(move |(n, _)| validate_deps(graph, n, manifests, &mut visited))
+        .flat_map(move |(n, pkg)| validate_deps(graph, n, pkg, manifests, vendor, &mut visited))
         .collect();
 
     Ok(edges)
 }
 
-/// Recursively validate all dependencies of the given `node`.
+/// Recursively validate all dependencies of the given `node` against its manifest.
 ///
 /// Returns the set of invalid dependency edges.
 fn validate_deps(
     graph: &Graph,
     node: NodeIx,
+    node_manifest: &PackageManifestFile,
     manifests: &MemberManifestFiles,
+    vendor: Option<&Vendor>,
     visited: &mut HashSet<NodeIx>,
 ) -> BTreeSet<EdgeIx> {
     let mut remove = BTreeSet::default();
     for edge in graph.edges_directed(node, Direction::Outgoing) {
         let dep_name = edge.weight();
         let dep_node = edge.target();
-        match validate_dep(graph, manifests, dep_name, dep_node) {
+        match validate_dep(graph, node_manifest, manifests, vendor, dep_name, dep_node) {
             Err(_) => {
                 remove.insert(edge.id());
             }
-            Ok(_) => {
+            Ok(dep_manifest) => {
                 if visited.insert(dep_node) {
-                    let rm = validate_deps(graph, dep_node, manifests, visited);
+                    let rm =
+                        validate_deps(graph, dep_node, &dep_manifest, manifests, vendor, visited);
                     remove.extend(rm);
                 }
                 continue;
@@ -753,21 +1021,20 @@ fn validate_deps(
     remove
 }
 
-/// Check the validity of a node's dependency within the graph.
+/// Check the validity of a node's dependency within the graph against the node's manifest.
 ///
 /// Returns the `ManifestFile` in the case that the dependency is valid.
 fn validate_dep(
     graph: &Graph,
+    node_manifest: &PackageManifestFile,
     manifests: &MemberManifestFiles,
+    vendor: Option<&Vendor>,
     dep_edge: &Edge,
     dep_node: NodeIx,
 ) -> Result<PackageManifestFile> {
     let dep_name = &dep_edge.name;
-    let node_manifest = manifests
-        .get(dep_name)
-        .ok_or_else(|| anyhow!("Couldn't find manifest file for {}", dep_name))?;
     // Check the validity of the dependency path, including its path root.
-    let dep_path = dep_path(graph, node_manifest, dep_node, manifests).map_err(|e| {
+    let dep_path = dep_path(graph, node_manifest, dep_node, manifests, vendor).map_err(|e| {
         anyhow!(
             "failed to construct path for dependency {:?}: {}",
             dep_name,
@@ -778,13 +1045,19 @@ fn validate_dep(
     // Ensure the manifest is accessible.
     let dep_manifest = PackageManifestFile::from_dir(&dep_path)?;
 
-    // Check that the dependency's source matches the entry in the parent manifest.
-    let dep_entry = node_manifest
-        .dep(dep_name)
-        .ok_or_else(|| anyhow!("no entry in parent manifest"))?;
+    // Check that the dependency's source matches the entry in the parent manifest, including the
+    // salt of a contract dependency.
+    let dep_entry = match dep_edge.kind {
+        DepKind::Library => node_manifest.dep(dep_name),
+        DepKind::Contract { salt } => node_manifest
+            .contract_dep(dep_name)
+            .filter(|contract_dep| contract_dep.salt == salt)
+            .map(|contract_dep| &contract_dep.dependency),
+    }
+    .ok_or_else(|| anyhow!("no matching entry in parent manifest"))?;
     let dep_source = dep_to_source_patched(node_manifest, dep_name, dep_entry, manifests)?;
     let dep_pkg = graph[dep_node].unpinned(&dep_path);
-    if dep_pkg.source != dep_source {
+    if !resolve::satisfies(&dep_pkg, &graph[dep_node].source, &dep_source) {
         bail!("dependency node's source does not match manifest entry");
     }
 
@@ -833,10 +1106,16 @@ fn dep_path(
     node_manifest: &PackageManifestFile,
     dep_node: NodeIx,
     manifests: &MemberManifestFiles,
+    vendor: Option<&Vendor>,
 ) -> Result<PathBuf> {
     let dep = &graph[dep_node];
     let dep_name = &dep.name;
     match &dep.source {
+        // Vendored git and registry packages are only ever sourced from the vendor directory.
+        SourcePinned::Git(_) | SourcePinned::Registry(_) if vendor.is_some() => {
+            let root = vendor.expect("checked above").root(dep)?;
+            vendor::find_pkg_dir(&root, &dep.name, &dep.source.to_string())
+        }
         SourcePinned::Git(git) => {
             let repo_path = git_commit_path(&dep.name, &git.source.repo, &git.commit_hash);
             // Co-ordinate access to the git checkout directory using an advisory file lock.
@@ -853,12 +1132,19 @@ fn dep_path(
         SourcePinned::Path(src) => {
             validate_path_root(graph, dep_node, src.path_root)?;
 
-            // Check if the path is directly from the dependency.
+            // Check if the path is directly from the dependency or contract dependency.
             if let Some(path) = node_manifest.dep_path(dep_name) {
                 if path.exists() {
                     return Ok(path);
                 }
             }
+            if let Some(details) = node_manifest.contract_dependency_detailed(dep_name) {
+                if let Some(ref rel_path) = details.path {
+                    if let Ok(path) = node_manifest.dir().join(rel_path).canonicalize() {
+                        return Ok(path);
+                    }
+                }
+            }
 
             // Otherwise, check if it comes from a patch.
             for (_, patch_map) in node_manifest.patches() {
@@ -879,7 +1165,20 @@ fn dep_path(
                 node_manifest.project.name
             )
         }
-        SourcePinned::Registry(_reg) => unreachable!("registry dependencies not yet supported"),
+        SourcePinned::Registry(reg) => {
+            let path = registry::package_path(&reg.source.index, &dep.name, &reg.version);
+            // Co-ordinate access to the registry package directory using an advisory file lock.
+            let lock = path_lock(&path)?;
+            let _guard = lock.read()?;
+            if !path.exists() {
+                bail!(
+                    "registry package `{}` {} has not been fetched",
+                    dep.name,
+                    reg
+                );
+            }
+            Ok(path)
+        }
         SourcePinned::Member => {
             // If a node has a root dependency it is a member of the workspace.
             manifests
@@ -1025,6 +1324,10 @@ impl SourceGitPinned {
     pub const PREFIX: &'static str = "git";
 }
 
+impl SourceRegistryPinned {
+    pub const PREFIX: &'static str = "registry";
+}
+
 impl fmt::Display for PinnedId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Format the inner `u64` as hex.
@@ -1053,6 +1356,20 @@ impl fmt::Display for SourceGitPinned {
     }
 }
 
+impl fmt::Display for SourceRegistryPinned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // registry+<url/to/index>?<base_version>#<pinned_version>
+        write!(
+            f,
+            "{}+{}?{}#{}",
+            Self::PREFIX,
+            self.source.index,
+            self.source.version,
+            self.version
+        )
+    }
+}
+
 impl fmt::Display for GitReference {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -1070,7 +1387,7 @@ impl fmt::Display for SourcePinned {
             SourcePinned::Member => write!(f, "member"),
             SourcePinned::Path(src) => src.fmt(f),
             SourcePinned::Git(src) => src.fmt(f),
-            SourcePinned::Registry(_reg) => unimplemented!("pkg registries not yet implemented"),
+            SourcePinned::Registry(src) => src.fmt(f),
         }
     }
 }
@@ -1163,6 +1480,41 @@ impl FromStr for SourceGitPinned {
     }
 }
 
+impl FromStr for SourceRegistryPinned {
+    type Err = SourceRegistryPinnedParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // registry+<url/to/index>?<base_version>#<pinned_version>
+        let s = s.trim();
+
+        // Check for "registry+" at the start.
+        let prefix_plus = format!("{}+", Self::PREFIX);
+        if s.find(&prefix_plus) != Some(0) {
+            return Err(SourceRegistryPinnedParseError::Prefix);
+        }
+        let s = &s[prefix_plus.len()..];
+
+        // Versions never contain `?` or `#`, so split from the end to allow for these within the
+        // index URL.
+        let (s, version) = s
+            .rsplit_once('#')
+            .ok_or(SourceRegistryPinnedParseError::Version)?;
+        let (index, base_version) = s
+            .rsplit_once('?')
+            .ok_or(SourceRegistryPinnedParseError::Version)?;
+        let index = Url::parse(index).map_err(|_| SourceRegistryPinnedParseError::Url)?;
+        let base_version = semver::Version::parse(base_version)
+            .map_err(|_| SourceRegistryPinnedParseError::Version)?;
+        let version =
+            semver::Version::parse(version).map_err(|_| SourceRegistryPinnedParseError::Version)?;
+
+        let source = SourceRegistry {
+            index,
+            version: base_version,
+        };
+        Ok(Self { source, version })
+    }
+}
+
 impl FromStr for SourcePinned {
     type Err = SourcePinnedParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -1174,8 +1526,9 @@ impl FromStr for SourcePinned {
             SourcePinned::Path(src)
         } else if let Ok(src) = SourceGitPinned::from_str(s) {
             SourcePinned::Git(src)
+        } else if let Ok(src) = SourceRegistryPinned::from_str(s) {
+            SourcePinned::Registry(src)
         } else {
-            // TODO: Try parse registry source.
             return Err(SourcePinnedParseError);
         };
         Ok(source)
@@ -1238,11 +1591,17 @@ pub fn compilation_order(graph: &Graph) -> Result<Vec<NodeIx>> {
 /// Given a graph collects ManifestMap while taking in to account that manifest can be a
 /// ManifestFile::Workspace. In the case of a workspace each pkg manifest map is collected and
 /// their added node lists are merged.
-fn graph_to_manifest_map(manifests: &MemberManifestFiles, graph: &Graph) -> Result<ManifestMap> {
+fn graph_to_manifest_map(
+    manifests: &MemberManifestFiles,
+    graph: &Graph,
+    vendor: Option<&Vendor>,
+) -> Result<ManifestMap> {
     let mut manifest_map = HashMap::new();
     for pkg_manifest in manifests.values() {
         let pkg_name = &pkg_manifest.project.name;
-        manifest_map.extend(pkg_graph_to_manifest_map(manifests, pkg_name, graph)?);
+        manifest_map.extend(pkg_graph_to_manifest_map(
+            manifests, pkg_name, graph, vendor,
+        )?);
     }
     Ok(manifest_map)
 }
@@ -1258,6 +1617,7 @@ fn pkg_graph_to_manifest_map(
     manifests: &MemberManifestFiles,
     pkg_name: &str,
     graph: &Graph,
+    vendor: Option<&Vendor>,
 ) -> Result<ManifestMap> {
     let proj_manifest = manifests
         .get(pkg_name)
@@ -1289,13 +1649,14 @@ fn pkg_graph_to_manifest_map(
             })
             .next()
             .ok_or_else(|| anyhow!("more than one root package detected in graph"))?;
-        let dep_path = dep_path(graph, parent_manifest, dep_node, manifests).map_err(|e| {
-            anyhow!(
-                "failed to construct path for dependency {:?}: {}",
-                dep_name,
-                e
-            )
-        })?;
+        let dep_path =
+            dep_path(graph, parent_manifest, dep_node, manifests, vendor).map_err(|e| {
+                anyhow!(
+                    "failed to construct path for dependency {:?}: {}",
+                    dep_name,
+                    e
+                )
+            })?;
         let dep_manifest = PackageManifestFile::from_dir(&dep_path)?;
         let dep = &graph[dep_node];
         manifest_map.insert(dep.id(), dep_manifest);
@@ -1365,6 +1726,7 @@ pub fn fetch_id(path: &Path, timestamp: std::time::Instant) -> u64 {
 fn fetch_graph(
     member_manifests: &MemberManifestFiles,
     offline: bool,
+    vendor: Option<&Vendor>,
     graph: &mut Graph,
     manifest_map: &mut ManifestMap,
 ) -> Result<HashSet<NodeIx>> {
@@ -1373,11 +1735,15 @@ fn fetch_graph(
         added_nodes.extend(&fetch_pkg_graph(
             member_pkg_manifest,
             offline,
+            vendor,
             graph,
             manifest_map,
             member_manifests,
         )?);
     }
+    resolve::remove_unreachable(graph, manifest_map);
+    added_nodes.retain(|&n| graph.contains_node(n));
+    resolve::validate_unique_pkgs(graph, manifest_map)?;
     validate_contract_deps(graph)?;
     Ok(added_nodes)
 }
@@ -1398,6 +1764,7 @@ fn fetch_graph(
 fn fetch_pkg_graph(
     proj_manifest: &PackageManifestFile,
     offline: bool,
+    vendor: Option<&Vendor>,
     graph: &mut Graph,
     manifest_map: &mut ManifestMap,
     member_manifests: &MemberManifestFiles,
@@ -1432,6 +1799,7 @@ fn fetch_pkg_graph(
     fetch_deps(
         fetch_id,
         offline,
+        vendor,
         proj_node,
         path_root,
         graph,
@@ -1449,6 +1817,7 @@ fn fetch_pkg_graph(
 fn fetch_deps(
     fetch_id: u64,
     offline: bool,
+    vendor: Option<&Vendor>,
     node: NodeIx,
     path_root: PinnedId,
     graph: &mut Graph,
@@ -1482,16 +1851,45 @@ fn fetch_deps(
         let source = dep_to_source_patched(parent_manifest, &name, &dep, member_manifests)
             .context("Failed to source dependency")?;
 
-        // If we haven't yet fetched this dependency, fetch it, pin it and add it to the graph.
-        let dep_pkg = Pkg { name, source };
-        let dep_node = match fetched.entry(dep_pkg) {
-            hash_map::Entry::Occupied(entry) => *entry.get(),
-            hash_map::Entry::Vacant(entry) => {
-                let dep_pinned = pin_pkg(fetch_id, path_root, entry.key(), manifest_map, offline)?;
-                let dep_node = graph.add_node(dep_pinned);
-                added.insert(dep_node);
-                *entry.insert(dep_node)
+        let dep_node = match source {
+            // Registry dependencies are unified with any existing semver-compatible node.
+            Source::Registry(ref reg) => {
+                let (dep_node, pinned) = resolve::fetch_registry_dep(
+                    fetch_id,
+                    node,
+                    &dep_name,
+                    &name,
+                    reg,
+                    graph,
+                    manifest_map,
+                    member_manifests,
+                    offline,
+                    vendor,
+                )?;
+                // A re-pinned node's dependencies must be traversed again.
+                if pinned {
+                    visited.remove(&dep_node);
+                    added.insert(dep_node);
+                }
+                dep_node
             }
+            // If we haven't yet fetched this dependency, fetch it, pin it and add it to the graph.
+            source => match fetched.entry(Pkg { name, source }) {
+                hash_map::Entry::Occupied(entry) => *entry.get(),
+                hash_map::Entry::Vacant(entry) => {
+                    let dep_pinned = pin_pkg(
+                        fetch_id,
+                        path_root,
+                        entry.key(),
+                        manifest_map,
+                        offline,
+                        vendor,
+                    )?;
+                    let dep_node = graph.add_node(dep_pinned);
+                    added.insert(dep_node);
+                    *entry.insert(dep_node)
+                }
+            },
         };
 
         let dep_edge = Edge::new(dep_name.to_string(), dep_kind.clone());
@@ -1524,6 +1922,7 @@ fn fetch_deps(
         added.extend(fetch_deps(
             fetch_id,
             offline,
+            vendor,
             dep_node,
             path_root,
             graph,
@@ -1671,12 +2070,16 @@ pub fn pin_git(fetch_id: u64, name: &str, source: SourceGit) -> Result<SourceGit
 ///
 /// The `path_root` is required for `Path` dependencies and must specify the package that is the
 /// root of the current subgraph of path dependencies.
-fn pin_pkg(
+///
+/// If the project's sources are vendored, git and registry packages are pinned to their vendored
+/// commit or version without accessing the network or the user's forc directory.
+pub(crate) fn pin_pkg(
     fetch_id: u64,
     path_root: PinnedId,
     pkg: &Pkg,
     manifest_map: &mut ManifestMap,
     offline: bool,
+    vendor: Option<&Vendor>,
 ) -> Result<Pinned> {
     let name = pkg.name.clone();
     let pinned = match &pkg.source {
@@ -1697,6 +2100,26 @@ fn pin_pkg(
             manifest_map.insert(id, manifest);
             pinned
         }
+        Source::Git(_) | Source::Registry(_) if vendor.is_some() => {
+            let vendor = vendor.expect("checked above");
+            let (source, root) = match &pkg.source {
+                Source::Git(git_source) => {
+                    let (pinned_git, root) = vendor.pin_git(&name, git_source)?;
+                    (SourcePinned::Git(pinned_git), root)
+                }
+                Source::Registry(reg_source) => {
+                    let (pinned_reg, root) = vendor.pin_registry(&name, reg_source)?;
+                    (SourcePinned::Registry(pinned_reg), root)
+                }
+                _ => unreachable!("only git and registry sources are vendored"),
+            };
+            let pinned = Pinned { name, source };
+            if let hash_map::Entry::Vacant(entry) = manifest_map.entry(pinned.id()) {
+                let path = vendor::find_pkg_dir(&root, &pinned.name, &pinned.source.to_string())?;
+                entry.insert(PackageManifestFile::from_dir(&path)?);
+            }
+            pinned
+        }
         Source::Git(ref git_source) => {
             // If the git source directly specifies a full commit hash, we should check
             // to see if we have a local copy. Otherwise we cannot know what commit we should pin
@@ -1784,13 +2207,41 @@ fn pin_pkg(
             }
             pinned
         }
-        Source::Registry(ref _source) => {
-            if offline {
-                bail!("Unable to fetch pkg {:?} in offline mode", name);
+        Source::Registry(ref reg_source) => {
+            // Select the latest semver-compatible version published to the index.
+            let index_path = registry::fetch_index(fetch_id, &reg_source.index, offline)?;
+            let entry = registry::resolve(&index_path, &name, &reg_source.version)?;
+            let pinned_reg = SourceRegistryPinned {
+                source: reg_source.clone(),
+                version: entry.version.clone(),
+            };
+            let source = SourcePinned::Registry(pinned_reg.clone());
+            let pinned = Pinned { name, source };
+            let id = pinned.id();
+            if let hash_map::Entry::Vacant(entry_map) = manifest_map.entry(id) {
+                let path = registry::package_path(&reg_source.index, &pinned.name, &entry.version);
+                // Co-ordinate access to the registry package directory using an advisory file lock.
+                let mut lock = path_lock(&path)?;
+                {
+                    let _guard = lock.write()?;
+                    if !path.exists() {
+                        info!("  Fetching {} {}", pinned.name, pinned_reg.version);
+                        registry::fetch_pkg(
+                            fetch_id,
+                            &reg_source.index,
+                            &index_path,
+                            &entry,
+                            offline,
+                        )?;
+                    }
+                }
+                let manifest = {
+                    let _guard = lock.read()?;
+                    PackageManifestFile::from_dir(&path)?
+                };
+                entry_map.insert(manifest);
             }
-            // TODO: determine registry pkg git URL, fetch to determine latest available
-            // semver-compatible version
-            bail!("registry dependencies are not yet supported");
+            pinned
         }
     };
     Ok(pinned)
@@ -1828,7 +2279,7 @@ fn fd_lock_path(path: &Path) -> PathBuf {
 /// Create an advisory lock over the given path.
 ///
 /// See [fd_lock_path] for details.
-fn path_lock(path: &Path) -> Result<fd_lock::RwLock<File>> {
+pub(crate) fn path_lock(path: &Path) -> Result<fd_lock::RwLock<File>> {
     let lock_path = fd_lock_path(path);
     let lock_dir = lock_path
         .parent()
@@ -1862,6 +2313,94 @@ pub fn git_commit_path(name: &str, repo: &Url, commit_hash: &str) -> PathBuf {
 /// to the git repository checkout path.
 pub fn fetch_git(fetch_id: u64, name: &str, pinned: &SourceGitPinned) -> Result<PathBuf> {
     let path = git_commit_path(name, &pinned.source.repo, &pinned.commit_hash);
+    checkout_git(fetch_id, name, pinned, &path)?;
+    Ok(path)
+}
+
+/// The local root of the given git or registry source within the user's forc directory.
+pub(crate) fn source_root(pinned: &Pinned) -> Result<PathBuf> {
+    match &pinned.source {
+        SourcePinned::Git(git) => Ok(git_commit_path(
+            &pinned.name,
+            &git.source.repo,
+            &git.commit_hash,
+        )),
+        SourcePinned::Registry(reg) => Ok(registry::package_path(
+            &reg.source.index,
+            &pinned.name,
+            &reg.version,
+        )),
+        _ => bail!("`{}` is not a git or registry package", pinned.name),
+    }
+}
+
+/// Fetch the locked version of each registry package within the graph that is missing from the
+/// user's forc directory.
+fn fetch_locked_registry_pkgs(graph: &Graph, fetch_id: u64, offline: bool) -> Result<()> {
+    for pinned in graph.node_weights() {
+        if let SourcePinned::Registry(reg) = &pinned.source {
+            let path = registry::package_path(&reg.source.index, &pinned.name, &reg.version);
+            if !path.exists() {
+                fetch_source(fetch_id, pinned, offline, false)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ensure the given git or registry source is available within the user's forc directory,
+/// fetching it if necessary, and return its root.
+///
+/// If `refetch` is specified, any existing copy of the source is removed and fetched again.
+pub(crate) fn fetch_source(
+    fetch_id: u64,
+    pinned: &Pinned,
+    offline: bool,
+    refetch: bool,
+) -> Result<PathBuf> {
+    let root = source_root(pinned)?;
+    // Co-ordinate access to the source directory using an advisory file lock.
+    let mut lock = path_lock(&root)?;
+    let _guard = lock.write()?;
+    if root.exists() {
+        if !refetch {
+            return Ok(root);
+        }
+        fs::remove_dir_all(&root)?;
+    }
+    match &pinned.source {
+        SourcePinned::Git(git) => {
+            if offline {
+                bail!(
+                    "Unable to fetch pkg {:?} from {} in offline mode",
+                    pinned.name,
+                    git
+                );
+            }
+            info!("  Fetching {}", git);
+            fetch_git(fetch_id, &pinned.name, git)?;
+        }
+        SourcePinned::Registry(reg) => {
+            let index_path = registry::fetch_index(fetch_id, &reg.source.index, offline)?;
+            let entry = registry::find_entry(&index_path, &pinned.name, &reg.version)?;
+            info!("  Fetching {} {}", pinned.name, reg.version);
+            registry::fetch_pkg(fetch_id, &reg.source.index, &index_path, &entry, offline)?;
+        }
+        _ => unreachable!("`source_root` only succeeds for git and registry packages"),
+    }
+    Ok(root)
+}
+
+/// Fetch the repo at the given git package's URL and checkout the pinned commit to `path`.
+///
+/// NOTE: This function assumes that the caller has aquired an advisory lock to co-ordinate access
+/// to `path`.
+pub(crate) fn checkout_git(
+    fetch_id: u64,
+    name: &str,
+    pinned: &SourceGitPinned,
+    path: &Path,
+) -> Result<()> {
     // Checkout the pinned hash to the path.
     with_tmp_git_repo(fetch_id, name, &pinned.source, |repo| {
         // Change HEAD to point to the pinned commit.
@@ -1871,13 +2410,13 @@ pub fn fetch_git(fetch_id: u64, name: &str, pinned: &SourceGitPinned) -> Result<
         // If the directory exists, remove it. Note that we already check for an existing,
         // cached checkout directory for re-use prior to reaching the `fetch_git` function.
         if path.exists() {
-            let _ = fs::remove_dir_all(&path);
+            let _ = fs::remove_dir_all(path);
         }
-        fs::create_dir_all(&path)?;
+        fs::create_dir_all(path)?;
 
         // Checkout HEAD to the target directory.
         let mut checkout = git2::build::CheckoutBuilder::new();
-        checkout.force().target_dir(&path);
+        checkout.force().target_dir(path);
         repo.checkout_head(Some(&mut checkout))?;
 
         // Fetch HEAD time and create an index
@@ -1898,8 +2437,7 @@ pub fn fetch_git(fetch_id: u64, name: &str, pinned: &SourceGitPinned) -> Result<
             serde_json::to_string(&source_index)?,
         )?;
         Ok(())
-    })?;
-    Ok(path)
+    })
 }
 
 /// Search local checkout dir for git sources, for non-branch git references tries to find the
@@ -2042,12 +2580,9 @@ fn dep_to_source(
 ) -> Result<Source> {
     let source = match dep {
         Dependency::Simple(ref ver_str) => {
-            bail!(
-                "Unsupported dependency declaration in \"{}\": `{}` - \
-                currently only `git` and `path` dependencies are supported",
-                pkg_path.display(),
-                ver_str
-            )
+            let index = registry::default_index()?;
+            let version = registry::parse_base_version(ver_str)?;
+            Source::Registry(SourceRegistry { index, version })
         }
         Dependency::Detailed(ref det) => match (&det.path, &det.version, &det.git) {
             (Some(relative_path), _, _) => {
@@ -2080,6 +2615,14 @@ fn dep_to_source(
                 let source = SourceGit { repo, reference };
                 Source::Git(source)
             }
+            (None, Some(version), None) => {
+                let index = match det.registry {
+                    Some(ref location) => registry::parse_index_location(location, pkg_path)?,
+                    None => registry::default_index()?,
+                };
+                let version = registry::parse_base_version(version)?;
+                Source::Registry(SourceRegistry { index, version })
+            }
             _ => {
                 bail!("unsupported set of fields for dependency: {:?}", dep);
             }
@@ -2095,14 +2638,14 @@ fn dep_source_patch<'manifest>(
     dep_name: &str,
     dep_source: &Source,
 ) -> Option<&'manifest Dependency> {
-    if let Source::Git(git) = dep_source {
-        if let Some(patches) = manifest.patch(git.repo.as_str()) {
-            if let Some(patch) = patches.get(dep_name) {
-                return Some(patch);
-            }
-        }
-    }
-    None
+    let patch_name = match dep_source {
+        Source::Git(git) => git.repo.as_str(),
+        Source::Registry(reg) => reg.index.as_str(),
+        Source::Member(_) | Source::Path(_) => return None,
+    };
+    manifest
+        .patch(patch_name)
+        .and_then(|patches| patches.get(dep_name))
 }
 
 /// If a patch exists for the given dependency within the given manifest, this returns a new
@@ -2123,7 +2666,7 @@ fn apply_patch(
 
 /// Converts the `Dependency` to a `Source` with any relevant patches in the given manifest
 /// applied.
-fn dep_to_source_patched(
+pub(crate) fn dep_to_source_patched(
     manifest: &PackageManifestFile,
     dep_name: &str,
     dep: &Dependency,
@@ -2150,7 +2693,10 @@ pub fn sway_build_config(
     .print_finalized_asm(build_profile.print_finalized_asm)
     .print_intermediate_asm(build_profile.print_intermediate_asm)
     .print_ir(build_profile.print_ir)
-    .include_tests(build_profile.include_tests);
+    .include_tests(build_profile.include_tests)
+    .optimization(build_profile.opt_level)
+    .passes(build_profile.passes.clone())
+    .inline_threshold(build_profile.inline_threshold);
     Ok(build_config)
 }
 
@@ -2194,14 +2740,10 @@ pub fn dependency_namespace(
                 };
 
                 // Construct namespace with contract id
-                let contract_dep_constant_name = "CONTRACT_ID";
-                let contract_id_value = format!("0x{dep_contract_id}");
-                let contract_id_constant = ConfigTimeConstant {
-                    r#type: "b256".to_string(),
-                    value: contract_id_value,
-                    public: true,
-                };
-                constants.insert(contract_dep_constant_name.to_string(), contract_id_constant);
+                constants.insert(
+                    CONTRACT_ID_CONSTANT_NAME.to_string(),
+                    contract_id_constant(&dep_contract_id),
+                );
                 namespace::Module::default_with_constants(type_engine, constants)?
             }
         };
@@ -2237,6 +2779,50 @@ pub fn dependency_namespace(
     Ok(namespace)
 }
 
+/// The name of the constant holding the ID of a contract, provided both to the dependents of a
+/// contract dependency and to the tests of a contract.
+pub const CONTRACT_ID_CONSTANT_NAME: &str = "CONTRACT_ID";
+
+/// The `CONTRACT_ID` constant for the contract with the given ID.
+fn contract_id_constant(contract_id: &ContractId) -> ConfigTimeConstant {
+    ConfigTimeConstant {
+        r#type: "b256".to_string(),
+        value: format!("0x{contract_id}"),
+        public: true,
+    }
+}
+
+/// Returns an error if the given contract constants declare `CONTRACT_ID`, which is reserved for
+/// the ID of the contract within its tests.
+fn check_contract_id_not_declared(
+    constants: &BTreeMap<String, ConfigTimeConstant>,
+    pkg_name: &str,
+) -> Result<()> {
+    if constants.contains_key(CONTRACT_ID_CONSTANT_NAME) {
+        bail!(
+            "contract `{pkg_name}` declares the constant `{CONTRACT_ID_CONSTANT_NAME}`, which is \
+            reserved for the ID of the contract within its tests"
+        );
+    }
+    Ok(())
+}
+
+/// Provide a placeholder `CONTRACT_ID` to the tests of a contract that is not built with them.
+///
+/// Tests are type-checked even when they are not included, but the contract's own functions must
+/// not see the placeholder.
+fn insert_contract_id_placeholder(
+    namespace: &mut namespace::Module,
+    type_engine: &TypeEngine,
+) -> Result<(), vec1::Vec1<CompileError>> {
+    let mut constants = BTreeMap::new();
+    constants.insert(
+        CONTRACT_ID_CONSTANT_NAME.to_string(),
+        contract_id_constant(&ContractId::default()),
+    );
+    namespace.insert_test_constants(type_engine, constants)
+}
+
 /// Find the `std` dependency, if it is a direct one, of the given node.
 fn has_std_dep(graph: &Graph, node: NodeIx) -> bool {
     // If we are `std`, do nothing.
@@ -2378,6 +2964,9 @@ pub fn compile(
         "generate JSON ABI program",
         typed_program.generate_json_abi_program(type_engine, &mut types)
     );
+    let json_abi_tests = build_profile
+        .include_tests
+        .then(|| typed_program.generate_json_abi_tests(type_engine, &mut types));
 
     let storage_slots = typed_program.storage_slots.clone();
     let tree_type = typed_program.kind.tree_type();
@@ -2392,11 +2981,16 @@ pub fn compile(
         "compile ast to asm",
         sway_core::ast_to_asm(type_engine, &ast_res, &sway_build_config)
     );
-    let entries = asm_res
+    let (preamble_len, entries) = asm_res
         .value
         .as_ref()
-        .map(|asm| asm.0.entries.clone())
+        .map(|asm| (asm.0.preamble_len, asm.0.entries.clone()))
         .unwrap_or_default();
+    let test_decls = entries
+        .iter()
+        .filter(|entry| entry.is_test())
+        .map(TestDecl::from_entry)
+        .collect::<Result<_>>()?;
     let bc_res = time_expr!(
         "compile asm to bytecode",
         sway_core::asm_to_bytecode(asm_res, source_map)
@@ -2408,12 +3002,17 @@ pub fn compile(
             let bytecode = bytes;
             let built_package = BuiltPackage {
                 json_abi_program,
+                json_abi_tests,
                 storage_slots,
                 bytecode,
                 tree_type,
+                preamble_len,
                 entries,
                 source_map: source_map.to_owned(),
                 pkg_name: pkg.name.clone(),
+                warnings: bc_res.warnings,
+                build_info: None,
+                test_decls,
             };
             Ok((built_package, namespace))
         }
@@ -2430,7 +3029,7 @@ pub const SWAY_BIN_HASH_SUFFIX: &str = "-bin-hash";
 pub const SWAY_BIN_ROOT_SUFFIX: &str = "-bin-root";
 
 /// Selects the build profile from all available build profiles in the workspace using build_opts.
-fn build_profile_from_opts(
+pub fn build_profile_from_opts(
     build_profiles: &HashMap<String, BuildProfile>,
     build_options: &BuildOpts,
 ) -> Result<(String, BuildProfile)> {
@@ -2490,6 +3089,20 @@ fn build_profile_from_opts(
 
 /// Builds a project with given BuildOptions
 pub fn build_with_options(build_options: BuildOpts) -> Result<Built> {
+    let build_plan = BuildPlan::from_build_opts(&build_options)?;
+    let (built, _) = build_with_plan(&build_plan, build_options)?;
+    Ok(built)
+}
+
+/// Builds a project with given BuildOptions using an existing build plan, e.g. one constructed
+/// via `BuildPlan::from_build_opts`.
+///
+/// This allows for the build plan to be inspected without resolving it twice. Also returns every
+/// contract built along the way as described by `build_with_contracts`.
+pub fn build_with_plan(
+    build_plan: &BuildPlan,
+    build_options: BuildOpts,
+) -> Result<(Built, BuiltContracts)> {
     let path = &build_options.pkg.path;
 
     let this_dir = if let Some(ref path) = path {
@@ -2503,22 +3116,11 @@ pub fn build_with_options(build_options: BuildOpts) -> Result<Built> {
         binary_outfile,
         debug_outfile,
         pkg,
+        no_cache,
         ..
     } = &build_options;
 
     let manifest_file = ManifestFile::from_dir(&this_dir)?;
-    let member_manifests = manifest_file.member_manifests()?;
-    // Check if we have members to build so that we are not trying to build an empty workspace.
-    if member_manifests.is_empty() {
-        bail!("No member found to build")
-    }
-    let lock_path = manifest_file.lock_path()?;
-    let build_plan = BuildPlan::from_lock_and_manifests(
-        &lock_path,
-        &member_manifests,
-        build_options.pkg.locked,
-        build_options.pkg.offline,
-    )?;
     let graph = build_plan.graph();
     let manifest_map = build_plan.manifest_map();
     let build_profiles: HashMap<String, BuildProfile> = build_plan.build_profiles().collect();
@@ -2537,7 +3139,8 @@ pub fn build_with_options(build_options: BuildOpts) -> Result<Built> {
     };
     // Build it!
     let mut built_workspace = HashMap::new();
-    let built_packages = build(&build_plan, &build_profile, &outputs)?;
+    let (built_packages, built_contracts) =
+        build_with_contracts(build_plan, &build_profile, &outputs, *no_cache)?;
     let output_dir = pkg.output_directory.as_ref().map(PathBuf::from);
     for (node_ix, built_package) in built_packages.into_iter() {
         let pinned = &graph[node_ix];
@@ -2563,57 +3166,104 @@ pub fn build_with_options(build_options: BuildOpts) -> Result<Built> {
             let built_pkg = built_workspace
                 .remove(&pkg_manifest.project.name)
                 .expect("package didn't exist in workspace");
-            Ok(Built::Package(Box::new(built_pkg)))
+            Ok((Built::Package(Box::new(built_pkg)), built_contracts))
         }
-        ManifestFile::Workspace(_) => Ok(Built::Workspace(built_workspace)),
+        ManifestFile::Workspace(_) => Ok((Built::Workspace(built_workspace), built_contracts)),
     }
 }
 
 /// Returns the ContractId of a built_package contract with specified `salt`.
-fn contract_id(built_package: &BuiltPackage, salt: &fuel_tx::Salt) -> ContractId {
+pub fn contract_id(built_package: &BuiltPackage, salt: &fuel_tx::Salt) -> ContractId {
+    contract_id_with_storage(&built_package.bytecode, &built_package.storage_slots, salt)
+}
+
+/// Returns the ContractId of a contract with the given bytecode, initial `storage_slots` and
+/// `salt`.
+pub fn contract_id_with_storage(
+    bytecode: &[u8],
+    storage_slots: &[StorageSlot],
+    salt: &fuel_tx::Salt,
+) -> ContractId {
     // Construct the contract ID
-    let contract = Contract::from(built_package.bytecode.clone());
-    let mut storage_slots = built_package.storage_slots.clone();
+    let contract = Contract::from(bytecode.to_vec());
+    let mut storage_slots = storage_slots.to_vec();
     storage_slots.sort();
     let state_root = Contract::initial_state_root(storage_slots.iter());
     contract.id(salt, &contract.root(), &state_root)
 }
 
+/// Whether or not the package at the given node is declared as a contract dependency by any of
+/// the packages within the graph.
+fn is_contract_dependency(graph: &Graph, node: NodeIx) -> bool {
+    graph
+        .edges_directed(node, Direction::Incoming)
+        .any(|e| matches!(e.weight().kind, DepKind::Contract { .. }))
+}
+
 /// Checks if there are conficting `Salt` declarations for the contract dependencies in the graph.
 fn validate_contract_deps(graph: &Graph) -> Result<()> {
     // For each contract dependency node in the graph, check if there are conflicting salt
     // declarations.
     for node in graph.node_indices() {
-        let pkg = &graph[node];
-        let name = pkg.name.clone();
-        let salt_declarations: HashSet<fuel_tx::Salt> = graph
-            .edges_directed(node, Direction::Incoming)
-            .filter_map(|e| match e.weight().kind {
-                DepKind::Library => None,
-                DepKind::Contract { salt } => Some(salt),
-            })
-            .collect();
-        if salt_declarations.len() > 1 {
-            bail!(
-                "There are conflicting salt declarations for contract dependency named: {}\nDeclared salts: {:?}",
-                name,
-                salt_declarations,
-            )
-        }
+        contract_dep_salt(graph, node)?;
     }
     Ok(())
 }
 
+/// The salt declared for the contract at the given node under the `[contract-dependencies]` of
+/// its dependents, or `None` if it is not a contract dependency.
+///
+/// Returns an error if the contract's dependents declare conflicting salts.
+pub fn contract_dep_salt(graph: &Graph, node: NodeIx) -> Result<Option<fuel_tx::Salt>> {
+    let salt_declarations: BTreeSet<fuel_tx::Salt> = graph
+        .edges_directed(node, Direction::Incoming)
+        .filter_map(|e| match e.weight().kind {
+            DepKind::Library => None,
+            DepKind::Contract { salt } => Some(salt),
+        })
+        .collect();
+    if salt_declarations.len() > 1 {
+        bail!(
+            "There are conflicting salt declarations for contract dependency named: {}\nDeclared salts: {:?}",
+            graph[node].name,
+            salt_declarations,
+        )
+    }
+    Ok(salt_declarations.into_iter().next())
+}
+
 /// Build an entire forc package and return the built_package output.
 ///
 /// This compiles all packages (including dependencies) in the order specified by the `BuildPlan`.
 ///
 /// Also returns the resulting `sway_core::SourceMap` which may be useful for debugging purposes.
+///
+/// If `no_cache` is set, every package is compiled from source and the build cache is neither
+/// read from nor written to.
 pub fn build(
     plan: &BuildPlan,
     profile: &BuildProfile,
     outputs: &HashSet<NodeIx>,
+    no_cache: bool,
 ) -> anyhow::Result<Vec<(NodeIx, BuiltPackage)>> {
+    let (built_packages, _) = build_with_contracts(plan, profile, outputs, no_cache)?;
+    Ok(built_packages)
+}
+
+/// Every contract built as a part of a build, as it would be deployed (i.e. without tests).
+pub type BuiltContracts = HashMap<NodeIx, BuiltPackage>;
+
+/// Build an entire forc package in the same manner as `build`.
+///
+/// Also returns every contract that was built along the way, including both contract outputs and
+/// contract dependencies. Contracts are always provided as built without tests, allowing for them
+/// to be deployed without building them again.
+pub fn build_with_contracts(
+    plan: &BuildPlan,
+    profile: &BuildProfile,
+    outputs: &HashSet<NodeIx>,
+    no_cache: bool,
+) -> anyhow::Result<(Vec<(NodeIx, BuiltPackage)>, BuiltContracts)> {
     //TODO remove once type engine isn't global anymore.
     sway_core::clear_lazy_statics();
     let mut built_packages = Vec::new();
@@ -2623,9 +3273,142 @@ pub fn build(
         .flat_map(|output_node| plan.node_deps(*output_node))
         .collect();
 
+    // Tests are only ever included for the requested outputs and never for their
+    // dependencies. This ensures the bytecode of contract dependencies (and in turn the
+    // `CONTRACT_ID` provided to their dependents) matches that of a regular build.
+    let node_profile = |node: NodeIx| BuildProfile {
+        include_tests: profile.include_tests && outputs.contains(&node),
+        ..profile.clone()
+    };
+
+    // A contract built with tests is also built without tests, as it is deployed.
+    let without_tests = |profile: &BuildProfile| BuildProfile {
+        include_tests: false,
+        ..profile.clone()
+    };
+
+    // Load any packages that are unchanged since they were last built.
+    let mut cache_keys: HashMap<NodeIx, String> = HashMap::new();
+    let mut contract_cache_keys: HashMap<NodeIx, String> = HashMap::new();
+    let mut cached: HashMap<NodeIx, BuiltPackage> = HashMap::new();
+    let mut cached_contracts = BuiltContracts::new();
+    for &node in plan
+        .compilation_order
+        .iter()
+        .filter(|node| required.contains(node))
+    {
+        let node_profile = node_profile(node);
+        if no_cache || !cache::is_cacheable(&node_profile) {
+            continue;
+        }
+        let pkg = &plan.graph()[node];
+        let manifest = &plan.manifest_map()[&pkg.id()];
+        // A package may only be cached if all of its dependencies may be cached.
+        let dep_keys: Option<Vec<_>> = plan
+            .graph
+            .edges_directed(node, Direction::Outgoing)
+            .map(|edge| {
+                let dep_key = cache_keys.get(&edge.target())?;
+                Some((edge.weight(), &dep_key[..]))
+            })
+            .collect();
+        let dep_keys = match dep_keys {
+            Some(dep_keys) => dep_keys,
+            None => continue,
+        };
+        let key = cache::pkg_key(manifest, pkg, &node_profile, dep_keys.clone())?;
+        let built = cache::load(manifest, pkg, &node_profile, &key);
+        let is_contract = matches!(manifest.program_type(), Ok(TreeType::Contract));
+        if node_profile.include_tests && is_contract {
+            let contract_profile = without_tests(&node_profile);
+            let contract_key = cache::pkg_key(manifest, pkg, &contract_profile, dep_keys)?;
+            let contract = cache::load(manifest, pkg, &contract_profile, &contract_key);
+            if let (Some(built), Some(contract)) = (built, contract) {
+                cached.insert(node, built);
+                cached_contracts.insert(node, contract);
+            }
+            contract_cache_keys.insert(node, contract_key);
+        } else if let Some(built) = built {
+            cached.insert(node, built);
+        }
+        cache_keys.insert(node, key);
+    }
+
+    // The namespaces of libraries refer to the declarations and types within the engines of the
+    // build that produced them. Unless their namespaces can be restored along with the engines
+    // below, libraries must be recompiled for any dependents that are themselves being compiled.
+    let mut must_compile: HashSet<NodeIx> = HashSet::new();
+    for &node in plan
+        .compilation_order
+        .iter()
+        .rev()
+        .filter(|node| required.contains(node))
+    {
+        if !cached.contains_key(&node) {
+            must_compile.insert(node);
+            continue;
+        }
+        let required_by_dependent =
+            plan.graph
+                .edges_directed(node, Direction::Incoming)
+                .any(|edge| {
+                    edge.weight().kind == DepKind::Library && must_compile.contains(&edge.source())
+                });
+        if required_by_dependent {
+            must_compile.insert(node);
+        }
+    }
+    // Dependents implicitly require `core` whenever they're compiled.
+    let core_nodes: Vec<NodeIx> = must_compile
+        .iter()
+        .filter_map(|&node| find_core_dep(&plan.graph, node))
+        .collect();
+    must_compile.extend(core_nodes);
+
+    // The libraries at the start of the compilation order, typically `core` and `std`, are
+    // compiled with empty engines. Their namespaces are cached along with a snapshot of the engines
+    // once they are compiled, so that they need not be compiled again for their dependents.
+    let leading_libs: Vec<NodeIx> = plan
+        .compilation_order
+        .iter()
+        .filter(|node| required.contains(node))
+        .take_while(|node| {
+            let manifest = &plan.manifest_map()[&plan.graph()[**node].id()];
+            cache_keys.contains_key(node)
+                && matches!(manifest.program_type(), Ok(TreeType::Library { .. }))
+        })
+        .cloned()
+        .collect();
+    let leading_libs_entry: Vec<_> = leading_libs
+        .iter()
+        .map(|&node| {
+            let pkg = &plan.graph()[node];
+            (&plan.manifest_map()[&pkg.id()], pkg, node_profile(node))
+        })
+        .collect();
+    let leading_libs_key =
+        cache::namespaces_key(leading_libs.iter().map(|node| &cache_keys[node][..]));
+
     let type_engine = TypeEngine::default();
-    let mut lib_namespace_map = Default::default();
-    let mut compiled_contract_deps = HashMap::new();
+    let mut lib_namespace_map = HashMap::new();
+    let mut built_contracts = BuiltContracts::new();
+    // Their cached outputs are required too, as restoring their namespaces replaces compiling them.
+    let restore_leading_libs = leading_libs.iter().any(|node| must_compile.contains(node))
+        && leading_libs.iter().all(|node| cached.contains_key(node));
+    let restored_namespaces = if restore_leading_libs {
+        cache::load_namespaces(&leading_libs_entry, &leading_libs_key, &type_engine)
+    } else {
+        None
+    };
+    let restored_leading_libs = restored_namespaces.is_some();
+    for (node, namespace) in leading_libs
+        .iter()
+        .zip(restored_namespaces.into_iter().flatten())
+    {
+        must_compile.remove(node);
+        lib_namespace_map.insert(*node, namespace);
+    }
+    let mut leading_libs_compiled = 0;
     for &node in plan
         .compilation_order
         .iter()
@@ -2634,49 +3417,138 @@ pub fn build(
         let mut source_map = SourceMap::new();
         let pkg = &plan.graph()[node];
         let manifest = &plan.manifest_map()[&pkg.id()];
-        let constants = manifest.config_time_constants();
-        let dep_namespace = match dependency_namespace(
-            &lib_namespace_map,
-            &compiled_contract_deps,
-            &plan.graph,
-            node,
-            constants,
-            &type_engine,
-        ) {
-            Ok(o) => o,
-            Err(errs) => {
-                print_on_failure(profile.terse, &[], &errs);
-                bail!("Failed to compile {}", pkg.name);
+        if !must_compile.contains(&node) {
+            let mut built_package = cached.remove(&node).expect("no cached package");
+            print_on_fresh(profile.terse, &pkg.name, &built_package.tree_type);
+            if let Some(contract) = cached_contracts.remove(&node) {
+                built_contracts.insert(node, contract);
+            } else if built_package.tree_type == TreeType::Contract {
+                built_contracts.insert(node, built_package.clone());
+            }
+            if outputs.contains(&node) {
+                let build_info =
+                    BuildInfo::new(plan, node, &node_profile(node), &built_package.bytecode)?;
+                built_package.build_info = Some(build_info);
+                built_packages.push((node, built_package));
+            }
+            continue;
+        }
+        let node_profile = node_profile(node);
+        // A program whose type fails to parse is left for the compiler to report.
+        let is_contract = matches!(manifest.program_type(), Ok(TreeType::Contract));
+        let dep_namespace = |constants, contract_id_placeholder| {
+            let res = dependency_namespace(
+                &lib_namespace_map,
+                &built_contracts,
+                &plan.graph,
+                node,
+                constants,
+                &type_engine,
+            )
+            .and_then(|mut namespace| {
+                if contract_id_placeholder {
+                    insert_contract_id_placeholder(&mut namespace, &type_engine)?;
+                }
+                Ok(namespace)
+            });
+            match res {
+                Ok(o) => Ok(o),
+                Err(errs) => {
+                    print_on_failure(profile.terse, &[], &errs);
+                    bail!("Failed to compile {}", pkg.name);
+                }
             }
         };
+        let mut constants = manifest.config_time_constants();
+        if is_contract {
+            check_contract_id_not_declared(&constants, &pkg.name)?;
+        }
+        // Dependents must always refer to the ID of the contract as built without tests, which is
+        // also the contract that is deployed.
+        let contract_without_tests = if node_profile.include_tests && is_contract {
+            let profile = without_tests(&node_profile);
+            let (contract, _) = compile(
+                pkg,
+                manifest,
+                &profile,
+                dep_namespace(constants.clone(), true)?,
+                &type_engine,
+                &mut source_map,
+            )?;
+            // The contract's own tests may call into the deployed contract via `CONTRACT_ID`.
+            let salt = contract_dep_salt(&plan.graph, node)?.unwrap_or_else(fuel_tx::Salt::zeroed);
+            let id = contract_id(&contract, &salt);
+            constants.insert(
+                CONTRACT_ID_CONSTANT_NAME.to_string(),
+                contract_id_constant(&id),
+            );
+            Some(contract)
+        } else {
+            None
+        };
+        let dep_namespace = dep_namespace(constants, is_contract && !node_profile.include_tests)?;
         let res = compile(
             pkg,
             manifest,
-            profile,
+            &node_profile,
             dep_namespace,
             &type_engine,
             &mut source_map,
         )?;
         let (mut built_package, namespace) = res;
-        // If the current node is a contract dependency, collect the contract_id
-        if plan
-            .graph()
-            .edges_directed(node, Direction::Incoming)
-            .any(|e| matches!(e.weight().kind, DepKind::Contract { .. }))
+        // Only cache packages without warnings, so that warnings are reported on every build.
+        if let (Some(contract), Some(key)) =
+            (&contract_without_tests, contract_cache_keys.get(&node))
         {
-            compiled_contract_deps.insert(node, built_package.clone());
+            if contract.warnings.is_empty() {
+                cache::store(manifest, pkg, &without_tests(&node_profile), key, contract)?;
+            }
+        }
+        // Collect each contract, so that its dependents may refer to its contract ID.
+        if is_contract {
+            let contract = contract_without_tests.unwrap_or_else(|| built_package.clone());
+            built_contracts.insert(node, contract);
         }
         if let TreeType::Library { .. } = built_package.tree_type {
             lib_namespace_map.insert(node, namespace.into());
         }
         source_map.insert_dependency(manifest.dir());
         standardize_json_abi_types(&mut built_package.json_abi_program);
+        if let Some(json_abi_tests) = &mut built_package.json_abi_tests {
+            standardize_json_abi_types(json_abi_tests);
+        }
+        if let Some(key) = cache_keys.get(&node) {
+            if built_package.warnings.is_empty() {
+                cache::store(manifest, pkg, &node_profile, key, &built_package)?;
+                if leading_libs.contains(&node) {
+                    leading_libs_compiled += 1;
+                }
+            }
+        }
+        // The engines must be snapshotted before any other package is compiled.
+        if !restored_leading_libs
+            && leading_libs.last() == Some(&node)
+            && leading_libs_compiled == leading_libs.len()
+        {
+            let namespaces: Vec<_> = leading_libs
+                .iter()
+                .map(|node| &lib_namespace_map[node])
+                .collect();
+            cache::store_namespaces(
+                &leading_libs_entry,
+                &leading_libs_key,
+                &type_engine,
+                &namespaces,
+            )?;
+        }
         if outputs.contains(&node) {
+            let build_info = BuildInfo::new(plan, node, &node_profile, &built_package.bytecode)?;
+            built_package.build_info = Some(build_info);
             built_packages.push((node, built_package));
         }
     }
 
-    Ok(built_packages)
+    Ok((built_packages, built_contracts))
 }
 
 /// Standardize the JSON ABI data structure by eliminating duplicate types. This is an iterative
@@ -2827,7 +3699,11 @@ pub fn check(
         let pkg = &plan.graph[node];
         let manifest = &plan.manifest_map()[&pkg.id()];
         let constants = manifest.config_time_constants();
-        let dep_namespace = dependency_namespace(
+        let is_contract = matches!(manifest.program_type(), Ok(TreeType::Contract));
+        if is_contract {
+            check_contract_id_not_declared(&constants, &pkg.name)?;
+        }
+        let mut dep_namespace = dependency_namespace(
             &lib_namespace_map,
             &compiled_contract_deps,
             &plan.graph,
@@ -2836,6 +3712,12 @@ pub fn check(
             type_engine,
         )
         .expect("failed to create dependency namespace");
+        // The tests of a contract may refer to its own ID, for which we use a placeholder as we
+        // don't compile contracts during `check`.
+        if is_contract {
+            insert_contract_id_placeholder(&mut dep_namespace, type_engine)
+                .expect("failed to create dependency namespace");
+        }
         let CompileResult {
             value,
             mut warnings,
@@ -3001,6 +3883,353 @@ fn test_source_git_pinned_parsing() {
     }
 }
 
+#[test]
+fn test_source_registry_pinned_parsing() {
+    let strings = [
+        "registry+https://github.com/owner/index?0.1.0#0.1.3",
+        "registry+file:///home/user/index/?1.2.0-alpha#1.2.0",
+    ];
+
+    let expected = [
+        SourceRegistryPinned {
+            source: SourceRegistry {
+                index: Url::parse("https://github.com/owner/index").unwrap(),
+                version: semver::Version::new(0, 1, 0),
+            },
+            version: semver::Version::new(0, 1, 3),
+        },
+        SourceRegistryPinned {
+            source: SourceRegistry {
+                index: Url::parse("file:///home/user/index/").unwrap(),
+                version: semver::Version::parse("1.2.0-alpha").unwrap(),
+            },
+            version: semver::Version::new(1, 2, 0),
+        },
+    ];
+
+    for (&string, expected) in strings.iter().zip(&expected) {
+        let parsed = SourceRegistryPinned::from_str(string).unwrap();
+        assert_eq!(&parsed, expected);
+        let serialized = expected.to_string();
+        assert_eq!(&serialized, string);
+    }
+}
+
+#[test]
+fn test_validate_graph_transitive_path_deps() {
+    use crate::test_util;
+
+    let dir = test_util::temp_dir();
+    let (app_dir, a_dir, b_dir) = (
+        dir.path().join("app"),
+        dir.path().join("a"),
+        dir.path().join("b"),
+    );
+    test_util::write_lib(&b_dir, "b", "");
+    test_util::write_lib(&a_dir, "a", "[dependencies]\nb = { path = \"../b\" }\n");
+    test_util::write_lib(&app_dir, "app", "[dependencies]\na = { path = \"../a\" }\n");
+
+    let manifests = ManifestFile::from_dir(&app_dir)
+        .unwrap()
+        .member_manifests()
+        .unwrap();
+    let plan = BuildPlan::from_manifests(&manifests, true).unwrap();
+    assert_eq!(plan.graph.edge_count(), 2);
+
+    // The `a -> b` edge must be validated against `a`'s manifest, which is not a member.
+    let invalid = validate_graph(&plan.graph, &manifests, None).unwrap();
+    assert!(invalid.is_empty());
+}
+
+#[test]
+fn test_validate_graph_contract_dep_salt() {
+    use crate::test_util;
+
+    let dir = test_util::temp_dir();
+    let (app_dir, dep_dir) = (dir.path().join("app"), dir.path().join("dep_contract"));
+    let salt = |byte: &str| {
+        format!(
+            "[contract-dependencies]\n\
+            dep_contract = {{ path = \"../dep_contract\", salt = \"0x{}\" }}\n",
+            byte.repeat(32)
+        )
+    };
+    test_util::write_contract(&dep_dir, "dep_contract", "");
+    test_util::write_contract(&app_dir, "app", &salt("11"));
+
+    let manifests = ManifestFile::from_dir(&app_dir)
+        .unwrap()
+        .member_manifests()
+        .unwrap();
+    let plan = BuildPlan::from_manifests(&manifests, true).unwrap();
+    let invalid = validate_graph(&plan.graph, &manifests, None).unwrap();
+    assert!(invalid.is_empty());
+
+    // Changing the salt within the manifest invalidates the contract dependency edge.
+    test_util::write_contract(&app_dir, "app", &salt("22"));
+    let manifests = ManifestFile::from_dir(&app_dir)
+        .unwrap()
+        .member_manifests()
+        .unwrap();
+    let invalid = validate_graph(&plan.graph, &manifests, None).unwrap();
+    assert_eq!(invalid.len(), 1);
+    let edge = *invalid.iter().next().unwrap();
+    assert_eq!(
+        plan.graph[edge].kind,
+        DepKind::Contract {
+            salt: fuel_tx::Salt::new([0x11; 32])
+        }
+    );
+}
+
+#[test]
+fn test_missing_locked_registry_pkg_keeps_version() {
+    use crate::test_util;
+
+    test_util::with_forc_home(|_| {
+        let dir = test_util::temp_dir();
+        let (index_dir, app_dir) = (dir.path().join("index"), dir.path().join("app"));
+        test_util::publish(&index_dir, "foo", "1.0.0", "");
+        test_util::write_lib(
+            &app_dir,
+            "app",
+            "[dependencies]\nfoo = { version = \"1.0.0\", registry = \"../index\" }\n",
+        );
+        let lock_path = app_dir.join(constants::LOCK_FILE_NAME);
+        let manifests = ManifestFile::from_dir(&app_dir)
+            .unwrap()
+            .member_manifests()
+            .unwrap();
+        let foo_version = |plan: &BuildPlan| {
+            let foo = plan.graph.node_weights().find(|p| p.name == "foo").unwrap();
+            match &foo.source {
+                SourcePinned::Registry(reg) => reg.version.clone(),
+                source => panic!("unexpected source: {}", source),
+            }
+        };
+
+        let plan = BuildPlan::from_lock_and_manifests(&lock_path, &manifests, false, true).unwrap();
+        assert_eq!(foo_version(&plan), semver::Version::new(1, 0, 0));
+        let lock = fs::read_to_string(&lock_path).unwrap();
+
+        // A newer compatible version must not replace the locked version once it is removed from
+        // the forc directory.
+        test_util::publish(&index_dir, "foo", "1.1.0", "");
+        let index = registry::parse_index_location("../index", &app_dir).unwrap();
+        let pkg_path = registry::package_path(&index, "foo", &semver::Version::new(1, 0, 0));
+        fs::remove_dir_all(&pkg_path).unwrap();
+
+        let plan = BuildPlan::from_lock_and_manifests(&lock_path, &manifests, true, true).unwrap();
+        assert_eq!(foo_version(&plan), semver::Version::new(1, 0, 0));
+        assert!(pkg_path.exists());
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), lock);
+    })
+}
+
+#[test]
+fn test_locked_checksum_detects_edit_preserving_metadata() {
+    use crate::test_util;
+
+    test_util::with_forc_home(|_| {
+        let dir = test_util::temp_dir();
+        let (index_dir, app_dir) = (dir.path().join("index"), dir.path().join("app"));
+        test_util::publish(&index_dir, "foo", "1.0.0", "");
+        test_util::write_lib(
+            &app_dir,
+            "app",
+            "[dependencies]\nfoo = { version = \"1.0.0\", registry = \"../index\" }\n",
+        );
+        let lock_path = app_dir.join(constants::LOCK_FILE_NAME);
+        let manifests = ManifestFile::from_dir(&app_dir)
+            .unwrap()
+            .member_manifests()
+            .unwrap();
+        BuildPlan::from_lock_and_manifests(&lock_path, &manifests, false, true).unwrap();
+        BuildPlan::from_lock_and_manifests(&lock_path, &manifests, true, true).unwrap();
+
+        // Edit the fetched source without changing the size or modification time of the file.
+        let index = registry::parse_index_location("../index", &app_dir).unwrap();
+        let pkg_path = registry::package_path(&index, "foo", &semver::Version::new(1, 0, 0));
+        let src_path = pkg_path.join(constants::SRC_DIR).join("lib.sw");
+        let modified = fs::metadata(&src_path).unwrap().modified().unwrap();
+        let src = fs::read_to_string(&src_path).unwrap();
+        fs::write(&src_path, src.replace("foo", "bar")).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&src_path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let metadata = fs::metadata(&src_path).unwrap();
+        assert_eq!(metadata.len(), src.len() as u64);
+        assert_eq!(metadata.modified().unwrap(), modified);
+
+        let err = BuildPlan::from_lock_and_manifests(&lock_path, &manifests, true, true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("do not match the checksums"), "{err}");
+    })
+}
+
+#[test]
+fn test_check_reserved_contract_id_constant() {
+    use crate::test_util;
+
+    test_util::with_forc_home(|_| {
+        let dir = test_util::temp_dir();
+        test_util::write_contract(
+            dir.path(),
+            "app",
+            &format!(
+                "[constants]\nCONTRACT_ID = {{ type = \"b256\", value = \"0x{}\" }}\n",
+                "00".repeat(32)
+            ),
+        );
+        let manifests = ManifestFile::from_dir(dir.path())
+            .unwrap()
+            .member_manifests()
+            .unwrap();
+        let plan = BuildPlan::from_manifests(&manifests, true).unwrap();
+        let type_engine = TypeEngine::default();
+
+        let err = check(&plan, true, &type_engine).unwrap_err();
+        assert!(err.to_string().contains("reserved"), "{err}");
+    })
+}
+
+#[test]
+fn test_build_restores_cached_lib_namespaces() {
+    use crate::test_util;
+
+    test_util::with_forc_home(|_| {
+        let dir = test_util::temp_dir();
+        let root = dir.path();
+        let core_dep = test_util::core_dep();
+        test_util::write_lib(
+            &root.join("foo"),
+            "foo",
+            &format!("[dependencies]\n{core_dep}"),
+        );
+        fs::write(
+            root.join("foo/src/lib.sw"),
+            "library foo;\n\npub struct Counter {\n    value: u64,\n}\n\n\
+            pub fn double(c: Counter) -> u64 {\n    c.value * 2\n}\n",
+        )
+        .unwrap();
+        let app_dir = root.join("app");
+        test_util::write_contract(
+            &app_dir,
+            "app",
+            &format!("[dependencies]\n{core_dep}foo = {{ path = \"../foo\" }}\n"),
+        );
+        let write_app = |n: u64| {
+            let src = format!(
+                "contract;\n\nuse foo::{{double, Counter}};\n\nabi App {{\n    fn get() -> u64;\n}}\n\n\
+                impl App for Contract {{\n    fn get() -> u64 {{\n        \
+                double(Counter {{ value: {n} }})\n    }}\n}}\n"
+            );
+            fs::write(app_dir.join("src/main.sw"), src).unwrap();
+        };
+        let build_app = |no_cache| {
+            let manifests = ManifestFile::from_dir(&app_dir)
+                .unwrap()
+                .member_manifests()
+                .unwrap();
+            let plan = BuildPlan::from_manifests(&manifests, true).unwrap();
+            let outputs = plan.member_nodes().collect();
+            let built = build(&plan, &BuildProfile::debug(), &outputs, no_cache).unwrap();
+            built.into_iter().next().unwrap().1.bytecode
+        };
+
+        write_app(1);
+        build_app(false);
+        let namespaces_entries = || {
+            fs::read_dir(crate::cache::build_cache_directory())
+                .unwrap()
+                .filter(|entry| {
+                    let name = entry.as_ref().unwrap().file_name();
+                    name.to_string_lossy().starts_with("foo-namespaces-")
+                })
+                .count()
+        };
+        assert_eq!(namespaces_entries(), 1);
+
+        // Only the contract is compiled, against the restored namespaces of `core` and `foo`.
+        write_app(2);
+        let restored = build_app(false);
+        assert_eq!(restored, build_app(true));
+        assert_eq!(namespaces_entries(), 1);
+    })
+}
+
+#[test]
+fn test_cached_build_matches_fresh_build_across_core_edit() {
+    use crate::test_util;
+
+    test_util::with_forc_home(|_| {
+        let dir = test_util::temp_dir();
+        let root = dir.path();
+        let core_dir = root.join("core");
+        let core_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sway-lib-core");
+        crate::registry::copy_dir(&core_path, &core_dir).unwrap();
+        let core_dep = "core = { path = \"../core\" }\n";
+        test_util::write_lib(
+            &root.join("foo"),
+            "foo",
+            &format!("[dependencies]\n{core_dep}"),
+        );
+        fs::write(
+            root.join("foo/src/lib.sw"),
+            "library foo;\n\npub enum Shape {\n    Square: u64,\n    Rect: (u64, u64),\n}\n\n\
+            pub fn area(shape: Shape) -> u64 {\n    match shape {\n        \
+            Shape::Square(side) => side * side,\n        \
+            Shape::Rect((w, h)) => w * h,\n    }\n}\n",
+        )
+        .unwrap();
+        let app_dir = root.join("app");
+        test_util::write_contract(
+            &app_dir,
+            "app",
+            &format!("[dependencies]\n{core_dep}foo = {{ path = \"../foo\" }}\n"),
+        );
+        let write_app = |n: u64| {
+            let src = format!(
+                "contract;\n\nuse foo::{{area, Shape}};\n\nabi App {{\n    fn get(w: u64) -> u64;\n}}\n\n\
+                impl App for Contract {{\n    fn get(w: u64) -> u64 {{\n        \
+                let (a, b) = (area(Shape::Rect((w, {n}))), area(Shape::Square(w)));\n        \
+                match a {{\n            0 => b,\n            _ => a,\n        }}\n    }}\n}}\n"
+            );
+            fs::write(app_dir.join("src/main.sw"), src).unwrap();
+        };
+        let build_app = |no_cache| {
+            let manifests = ManifestFile::from_dir(&app_dir)
+                .unwrap()
+                .member_manifests()
+                .unwrap();
+            let plan = BuildPlan::from_manifests(&manifests, true).unwrap();
+            let outputs = plan.member_nodes().collect();
+            let built = build(&plan, &BuildProfile::debug(), &outputs, no_cache).unwrap();
+            let built = built.into_iter().next().unwrap().1;
+            let abi = serde_json::to_string(&built.json_abi_program).unwrap();
+            (built.bytecode, abi)
+        };
+
+        write_app(1);
+        assert_eq!(build_app(false), build_app(true));
+
+        // `core` and `foo` are recompiled after the edit, and their namespaces cached anew.
+        let core_lib = core_dir.join("src/lib.sw");
+        let mut core_src = fs::read_to_string(&core_lib).unwrap();
+        core_src.push_str("\npub fn edited() -> u64 {\n    match 1 {\n        _ => 1,\n    }\n}\n");
+        fs::write(&core_lib, core_src).unwrap();
+        assert_eq!(build_app(false), build_app(true));
+
+        // Only the contract is compiled, against the restored namespaces of the edited `core`.
+        write_app(2);
+        assert_eq!(build_app(false), build_app(true));
+    })
+}
+
 /// Format an error message for an absent `Forc.toml`.
 pub fn manifest_file_missing(dir: &Path) -> anyhow::Error {
     let message = format!(
@@ -0,0 +1,517 @@
+//! Resolving and fetching packages published to a registry index.
+//!
+//! A registry index is a directory containing one file per package, named after the package. Each
+//! non-empty line of a package's file is a JSON object describing a single published version:
+//!
+//! ```ignore
+//! {"name":"foo","version":"0.1.0","path":"packages/foo-0.1.0"}
+//! {"name":"foo","version":"0.2.0","git":"https://github.com/owner/foo","rev":"<commit-hash>"}
+//! {"name":"foo","version":"0.2.1","git":"https://github.com/owner/foo","rev":"<commit-hash>","yanked":true}
+//! ```
+//!
+//! `path` locations are relative to the root of the index.
+//!
+//! An index specified with a `file://` URL (or a plain path in a manifest) is read directly from
+//! the local directory. Any other URL is treated as a git repository mirroring such a directory
+//! and is checked out under `~/.forc/registry/index`. Fetched packages are copied to
+//! `~/.forc/registry/src`.
+
+use crate::pkg::{
+    checkout_git, fetch_git, find_dir_within, git_commit_path, path_lock, pin_git, GitReference,
+    GitSourceIndex, SourceGit, SourceGitPinned,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use forc_util::registry_directory;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::fuel_prelude::fuel_crypto;
+use tracing::info;
+use url::Url;
+
+/// The environment variable specifying the index used by dependencies without a `registry` field.
+pub const REGISTRY_INDEX_ENV_VAR: &str = "FORC_REGISTRY_INDEX";
+
+/// The name used for the temporary repository when fetching a git registry index.
+const INDEX_REPO_NAME: &str = "index";
+
+/// A single published version of a package within a registry index.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub version: semver::Version,
+    /// Where the source of this version of the package is located.
+    #[serde(flatten)]
+    pub location: PackageLocation,
+    /// Yanked versions are never selected when resolving a dependency.
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// The location of the source of a published package.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PackageLocation {
+    /// A directory within the index, relative to the root of the index.
+    Path { path: PathBuf },
+    /// A commit within a git repository. The package may be located anywhere within the repo.
+    Git { git: Url, rev: String },
+}
+
+/// Parse the location of a registry index.
+///
+/// Locations that are not absolute URLs are treated as paths to local directories relative to
+/// `base_dir`.
+pub fn parse_index_location(location: &str, base_dir: &Path) -> Result<Url> {
+    if let Ok(url) = Url::parse(location) {
+        return Ok(url);
+    }
+    let path = base_dir.join(location);
+    let canonical_path = path.canonicalize().map_err(|e| {
+        anyhow!(
+            "Failed to canonicalize registry index path {:?}: {}",
+            path,
+            e
+        )
+    })?;
+    Url::from_directory_path(&canonical_path)
+        .map_err(|_| anyhow!("invalid registry index path {:?}", canonical_path))
+}
+
+/// The registry index used by dependencies that do not specify one via the `registry` field.
+///
+/// This is specified by the `FORC_REGISTRY_INDEX` environment variable.
+pub fn default_index() -> Result<Url> {
+    let location = std::env::var(REGISTRY_INDEX_ENV_VAR).map_err(|_| {
+        anyhow!(
+            "no registry index specified: either set the `registry` field of the dependency \
+            or the `{}` environment variable",
+            REGISTRY_INDEX_ENV_VAR
+        )
+    })?;
+    parse_index_location(&location, &std::env::current_dir()?)
+}
+
+/// Parse the version of a registry dependency as declared within a manifest.
+///
+/// The version is treated as the base of a caret requirement, e.g. `"0.2"` and `"^0.2.0"` both
+/// refer to the latest version that is semver-compatible with `0.2.0`. Other kinds of version
+/// requirements are not supported.
+pub fn parse_base_version(version: &str) -> Result<semver::Version> {
+    let req = semver::VersionReq::parse(version)
+        .map_err(|e| anyhow!("invalid dependency version {:?}: {}", version, e))?;
+    match &req.comparators[..] {
+        [cmp] if cmp.op == semver::Op::Caret => Ok(semver::Version {
+            major: cmp.major,
+            minor: cmp.minor.unwrap_or(0),
+            patch: cmp.patch.unwrap_or(0),
+            pre: cmp.pre.clone(),
+            build: semver::BuildMetadata::EMPTY,
+        }),
+        _ => bail!(
+            "unsupported dependency version {:?}: only caret requirements (e.g. \"1.2.3\" or \
+            \"^1.2.3\") are currently supported",
+            version
+        ),
+    }
+}
+
+/// The requirement satisfied by all versions that are semver-compatible with `base`.
+pub fn compatible_req(base: &semver::Version) -> semver::VersionReq {
+    semver::VersionReq {
+        comparators: vec![semver::Comparator {
+            op: semver::Op::Caret,
+            major: base.major,
+            minor: Some(base.minor),
+            patch: Some(base.patch),
+            pre: base.pre.clone(),
+        }],
+    }
+}
+
+/// The name of the directory used for the given index under `~/.forc/registry`.
+///
+/// The URL is hashed with SHA-256 so that the name remains stable across Rust releases.
+fn index_dir_name(index: &Url) -> String {
+    let hash = fuel_crypto::Hasher::hash(index.as_str().as_bytes());
+    let host = index.host_str().unwrap_or("local");
+    format!("{}-{}", host, hex::encode(&hash[..8]))
+}
+
+/// The path at which a git registry index is checked out.
+///
+/// The resulting directory is:
+///
+/// ```ignore
+/// $HOME/.forc/registry/index/<host>-<index_url_hash>
+/// ```
+pub fn index_checkout_path(index: &Url) -> PathBuf {
+    registry_directory()
+        .join("index")
+        .join(index_dir_name(index))
+}
+
+/// The path to which a registry package is fetched.
+///
+/// The resulting directory is:
+///
+/// ```ignore
+/// $HOME/.forc/registry/src/<host>-<index_url_hash>/<name>-<version>
+/// ```
+pub fn package_path(index: &Url, name: &str, version: &semver::Version) -> PathBuf {
+    registry_directory()
+        .join("src")
+        .join(index_dir_name(index))
+        .join(format!("{}-{}", name, version))
+}
+
+/// Ensure the given registry index is available locally and return the path to its root.
+///
+/// Git indices are updated to the latest commit of their default branch, unless `offline` is
+/// specified in which case the existing checkout is used.
+pub fn fetch_index(fetch_id: u64, index: &Url, offline: bool) -> Result<PathBuf> {
+    if index.scheme() == "file" {
+        return index
+            .to_file_path()
+            .map_err(|_| anyhow!("invalid registry index path {}", index));
+    }
+    let path = index_checkout_path(index);
+    // Co-ordinate access to the index checkout directory using an advisory file lock.
+    let mut lock = path_lock(&path)?;
+    if offline {
+        let _guard = lock.read()?;
+        if !path.exists() {
+            bail!("Unable to fetch registry index {} in offline mode", index);
+        }
+        return Ok(path);
+    }
+    let _guard = lock.write()?;
+    let source = SourceGit {
+        repo: index.clone(),
+        reference: GitReference::DefaultBranch,
+    };
+    let pinned = pin_git(fetch_id, INDEX_REPO_NAME, source)?;
+    if checkout_commit(&path).as_deref() != Some(&pinned.commit_hash[..]) {
+        info!("  Updating registry index {}", index);
+        checkout_git(fetch_id, INDEX_REPO_NAME, &pinned, &path)?;
+    }
+    Ok(path)
+}
+
+/// The commit of an existing index checkout, if any.
+fn checkout_commit(path: &Path) -> Option<String> {
+    let index_file = fs::read_to_string(path.join(".forc_index")).ok()?;
+    let index: GitSourceIndex = serde_json::from_str(&index_file).ok()?;
+    Some(index.head_with_time.0)
+}
+
+/// Read all published versions of the named package from the index located at `index_path`.
+pub fn read_entries(index_path: &Path, name: &str) -> Result<Vec<IndexEntry>> {
+    let path = index_path.join(name);
+    let contents = fs::read_to_string(&path).map_err(|_| {
+        anyhow!(
+            "package `{}` not found in registry index {}",
+            name,
+            index_path.display()
+        )
+    })?;
+    let mut entries = vec![];
+    for (ix, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: IndexEntry = serde_json::from_str(line)
+            .with_context(|| format!("failed to parse line {} of {}", ix + 1, path.display()))?;
+        if entry.name != name {
+            bail!(
+                "line {} of {} describes package `{}`, expected `{}`",
+                ix + 1,
+                path.display(),
+                entry.name,
+                name
+            );
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Select the highest, non-yanked version of a package that is semver-compatible with `base`.
+pub fn select_version<'a>(
+    entries: &'a [IndexEntry],
+    base: &semver::Version,
+) -> Option<&'a IndexEntry> {
+    let req = compatible_req(base);
+    entries
+        .iter()
+        .filter(|entry| !entry.yanked && req.matches(&entry.version))
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+/// Resolve the version of the named package to use for a dependency with the given base version.
+pub fn resolve(index_path: &Path, name: &str, base: &semver::Version) -> Result<IndexEntry> {
+    let entries = read_entries(index_path, name)?;
+    select_version(&entries, base).cloned().ok_or_else(|| {
+        anyhow!(
+            "no version of `{}` compatible with `{}` found in registry index {}",
+            name,
+            compatible_req(base),
+            index_path.display()
+        )
+    })
+}
+
+/// Find the entry for an exact version of the named package.
+pub fn find_entry(index_path: &Path, name: &str, version: &semver::Version) -> Result<IndexEntry> {
+    read_entries(index_path, name)?
+        .into_iter()
+        .find(|entry| entry.version == *version)
+        .ok_or_else(|| {
+            anyhow!(
+                "version {} of `{}` not found in registry index {}",
+                version,
+                name,
+                index_path.display()
+            )
+        })
+}
+
+/// Join the `path` of an index entry onto the root of the index.
+///
+/// The path must be relative and must name a directory within the index, e.g. it may not contain
+/// `..` components.
+fn index_package_dir(index_path: &Path, name: &str, path: &Path) -> Result<PathBuf> {
+    use std::path::Component;
+    let is_within_index = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        && path
+            .components()
+            .any(|component| matches!(component, Component::Normal(_)));
+    if !is_within_index {
+        bail!(
+            "invalid path {:?} for package `{}` within index {}: \
+            paths must be relative to the root of the index and may not contain `..`",
+            path,
+            name,
+            index_path.display(),
+        );
+    }
+    Ok(index_path.join(path))
+}
+
+/// Fetch the source of the given entry to its [package_path] and return the path.
+///
+/// NOTE: This function assumes that the caller has acquired an advisory lock to co-ordinate access
+/// to the package path.
+pub fn fetch_pkg(
+    fetch_id: u64,
+    index: &Url,
+    index_path: &Path,
+    entry: &IndexEntry,
+    offline: bool,
+) -> Result<PathBuf> {
+    let src_path = match &entry.location {
+        PackageLocation::Path { path } => index_package_dir(index_path, &entry.name, path)?,
+        PackageLocation::Git { git, rev } => {
+            let pinned_git = SourceGitPinned {
+                source: SourceGit {
+                    repo: git.clone(),
+                    reference: GitReference::Rev(rev.clone()),
+                },
+                commit_hash: rev.clone(),
+            };
+            let repo_path = git_commit_path(&entry.name, git, rev);
+            let mut lock = path_lock(&repo_path)?;
+            {
+                let _guard = lock.write()?;
+                if !repo_path.exists() {
+                    if offline {
+                        bail!(
+                            "Unable to fetch pkg {:?} from {:?} in offline mode",
+                            entry.name,
+                            git
+                        );
+                    }
+                    fetch_git(fetch_id, &entry.name, &pinned_git)?;
+                }
+            }
+            let _guard = lock.read()?;
+            find_dir_within(&repo_path, &entry.name).ok_or_else(|| {
+                anyhow!("failed to find package `{}` in {}", entry.name, pinned_git)
+            })?
+        }
+    };
+    let path = package_path(index, &entry.name, &entry.version);
+    // Copy to a sibling directory before renaming, so that a failed copy never leaves a partially
+    // copied package at `path`.
+    let tmp_path = path.with_file_name(format!(
+        "{}-{}.{}.tmp",
+        entry.name,
+        entry.version,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&tmp_path);
+    if let Err(e) = copy_dir(&src_path, &tmp_path) {
+        let _ = fs::remove_dir_all(&tmp_path);
+        return Err(e).with_context(|| {
+            format!(
+                "failed to copy `{}` {} from {}",
+                entry.name,
+                entry.version,
+                src_path.display()
+            )
+        });
+    }
+    if path.exists() {
+        fs::remove_dir_all(&path)
+            .with_context(|| format!("failed to remove {}", path.display()))?;
+    }
+    fs::rename(&tmp_path, &path)
+        .with_context(|| format!("failed to move package into {}", path.display()))?;
+    Ok(path)
+}
+
+/// Recursively copy the contents of `src` to `dst`, skipping any `.git` directories.
+pub(crate) fn copy_dir(src: &Path, dst: &Path) -> Result<()> {
+    let walker = walkdir::WalkDir::new(src)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git");
+    for entry in walker {
+        let entry = entry?;
+        let rel_path = entry.path().strip_prefix(src)?;
+        let dst_path = dst.join(rel_path);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dst_path)?;
+        } else {
+            fs::copy(entry.path(), &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: &str, yanked: bool) -> IndexEntry {
+        IndexEntry {
+            name: "foo".to_string(),
+            version: semver::Version::parse(version).unwrap(),
+            location: PackageLocation::Path {
+                path: PathBuf::from(format!("foo-{}", version)),
+            },
+            yanked,
+        }
+    }
+
+    #[test]
+    fn test_parse_base_version() {
+        let cases = [
+            ("1", "1.0.0"),
+            ("0.2", "0.2.0"),
+            ("0.2.3", "0.2.3"),
+            ("^0.2.3", "0.2.3"),
+            ("1.0.0-alpha", "1.0.0-alpha"),
+        ];
+        for (s, expected) in cases {
+            let expected = semver::Version::parse(expected).unwrap();
+            assert_eq!(parse_base_version(s).unwrap(), expected);
+        }
+        assert!(parse_base_version("~0.2").is_err());
+        assert!(parse_base_version(">=0.2, <0.4").is_err());
+        assert!(parse_base_version("not a version").is_err());
+    }
+
+    #[test]
+    fn test_parse_index_entry() {
+        let path_line = r#"{"name":"foo","version":"0.1.0","path":"foo-0.1.0"}"#;
+        assert_eq!(
+            serde_json::from_str::<IndexEntry>(path_line).unwrap(),
+            entry("0.1.0", false)
+        );
+        let git_line = r#"{"name":"foo","version":"0.2.0","git":"https://github.com/owner/foo","rev":"64092602dd6158f3e41d775ed889389440a2cd86","yanked":true}"#;
+        let parsed = serde_json::from_str::<IndexEntry>(git_line).unwrap();
+        assert!(parsed.yanked);
+        assert_eq!(
+            parsed.location,
+            PackageLocation::Git {
+                git: Url::parse("https://github.com/owner/foo").unwrap(),
+                rev: "64092602dd6158f3e41d775ed889389440a2cd86".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_select_version() {
+        let entries = [
+            entry("0.1.0", false),
+            entry("0.1.4", false),
+            entry("0.2.0", false),
+            entry("0.2.3", false),
+            entry("0.2.4", true),
+            entry("1.0.0", false),
+            entry("1.3.0", false),
+        ];
+        let select = |base: &str| {
+            let base = semver::Version::parse(base).unwrap();
+            select_version(&entries, &base).map(|entry| entry.version.to_string())
+        };
+        assert_eq!(select("0.1.0").as_deref(), Some("0.1.4"));
+        assert_eq!(select("0.2.1").as_deref(), Some("0.2.3"));
+        assert_eq!(select("1.0.0").as_deref(), Some("1.3.0"));
+        assert_eq!(select("0.2.4"), None);
+        assert_eq!(select("2.0.0"), None);
+    }
+
+    #[test]
+    fn test_index_dir_name() {
+        let index = Url::parse("https://github.com/owner/index").unwrap();
+        assert_eq!(index_dir_name(&index), "github.com-a47ea3349cebb67a");
+        let local = Url::parse("file:///index").unwrap();
+        assert!(index_dir_name(&local).starts_with("local-"));
+        assert_ne!(index_dir_name(&local), index_dir_name(&index));
+    }
+
+    #[test]
+    fn test_fetch_pkg() {
+        use crate::test_util;
+
+        test_util::with_forc_home(|_| {
+            let index_dir = test_util::temp_dir();
+            let index_path = index_dir.path();
+            let index = Url::from_directory_path(index_path).unwrap();
+            test_util::publish(index_path, "foo", "0.1.0", "");
+            let fetched = entry("0.1.0", false);
+            let path = fetch_pkg(0, &index, index_path, &fetched, true).unwrap();
+            assert!(path.join("Forc.toml").exists());
+
+            // A failed copy leaves the previously fetched package in place.
+            let missing = IndexEntry {
+                location: PackageLocation::Path {
+                    path: PathBuf::from("missing"),
+                },
+                ..fetched
+            };
+            assert!(fetch_pkg(0, &index, index_path, &missing, true).is_err());
+            assert!(path.join("Forc.toml").exists());
+            let pkg_dirs = fs::read_dir(path.parent().unwrap()).unwrap();
+            assert_eq!(pkg_dirs.count(), 1);
+        })
+    }
+
+    #[test]
+    fn test_index_package_dir() {
+        let index = Path::new("/index");
+        assert_eq!(
+            index_package_dir(index, "foo", Path::new("packages/foo-0.1.0")).unwrap(),
+            index.join("packages/foo-0.1.0")
+        );
+        assert!(index_package_dir(index, "foo", Path::new("./foo-0.1.0")).is_ok());
+        for path in ["../foo-0.1.0", "packages/../../foo", "/tmp/foo", ".", ""] {
+            assert!(index_package_dir(index, "foo", Path::new(path)).is_err());
+        }
+    }
+}
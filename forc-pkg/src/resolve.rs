@@ -0,0 +1,537 @@
+//! Unifying semver-compatible versions of packages within the package graph and explaining
+//! conflicting requirements when this is not possible.
+//!
+//! Registry dependencies on the same package that are semver-compatible are unified into a single
+//! node, pinned to the latest version satisfying every dependent. All other sources are pinned
+//! exactly, so two semver-compatible instances of the same package from different git or registry
+//! sources are reported as a conflict, as are instances of which either declares no version.
+
+use crate::{
+    manifest::{Dependency, MemberManifestFiles, PackageManifestFile},
+    pkg::{
+        dep_to_source_patched, member_nodes, pin_pkg, DepKind, Edge, Graph, ManifestMap, NodeIx,
+        Pkg, Source, SourcePinned, SourceRegistry,
+    },
+    registry,
+    vendor::Vendor,
+};
+use anyhow::{bail, Result};
+use petgraph::{
+    visit::{Dfs, EdgeRef},
+    Direction,
+};
+use std::{collections::HashSet, fmt::Write};
+
+/// The maximum number of dependency paths shown for each package when explaining a conflict.
+const MAX_DEP_PATHS: usize = 8;
+
+/// Versions within the same class are semver-compatible with one another.
+///
+/// This is the left-most non-zero component of the version, e.g. `1.x.y`, `0.2.x` or `0.0.3`.
+fn compat_class(version: &semver::Version) -> (u64, u64, u64) {
+    match (version.major, version.minor) {
+        (0, 0) => (0, 0, version.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    }
+}
+
+/// Whether the two versions are semver-compatible with one another.
+pub fn is_compatible(a: &semver::Version, b: &semver::Version) -> bool {
+    compat_class(a) == compat_class(b)
+}
+
+/// Whether a pinned dependency satisfies the source declared by its dependent.
+///
+/// As registry dependencies are unified, their pinned source may have a greater base version than
+/// the one declared, provided the pinned version is semver-compatible with the declaration.
+pub(crate) fn satisfies(dep: &Pkg, dep_pinned: &SourcePinned, declared: &Source) -> bool {
+    match (dep_pinned, declared) {
+        (SourcePinned::Registry(pinned), Source::Registry(declared)) => {
+            pinned.source.index == declared.index
+                && registry::compatible_req(&declared.version).matches(&pinned.version)
+        }
+        _ => dep.source == *declared,
+    }
+}
+
+/// Retrieve or fetch the node for the registry dependency named `dep_name` of the `parent` node.
+///
+/// If the graph already contains a node for a semver-compatible version of the package from the
+/// same index, the dependency is unified with it. In the case that the existing node's pinned
+/// version does not satisfy the dependency, the node is re-pinned to the latest version that
+/// satisfies all of its dependents and its outgoing edges are removed so that its dependencies may
+/// be fetched again.
+///
+/// Returns the dependency's node along with whether or not it was newly pinned, either as a new
+/// node or by re-pinning an existing node.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn fetch_registry_dep(
+    fetch_id: u64,
+    parent: NodeIx,
+    dep_name: &str,
+    pkg_name: &str,
+    source: &SourceRegistry,
+    graph: &mut Graph,
+    manifest_map: &mut ManifestMap,
+    member_manifests: &MemberManifestFiles,
+    offline: bool,
+    vendor: Option<&Vendor>,
+) -> Result<(NodeIx, bool)> {
+    let existing = graph.node_indices().find(|&n| match &graph[n].source {
+        SourcePinned::Registry(reg) => {
+            graph[n].name == pkg_name
+                && reg.source.index == source.index
+                && is_compatible(&reg.version, &source.version)
+        }
+        _ => false,
+    });
+
+    // The registry path root is unused, as only `Path` dependencies require one.
+    let path_root = graph[parent].id();
+    let node = match existing {
+        None => {
+            let pkg = Pkg {
+                name: pkg_name.to_string(),
+                source: Source::Registry(source.clone()),
+            };
+            let pinned = pin_pkg(fetch_id, path_root, &pkg, manifest_map, offline, vendor)
+                .map_err(|e| {
+                    let requirers = vec![(parent, dep_name.to_string(), source.clone())];
+                    explain_registry_failure(graph, pkg_name, &requirers, e)
+                })?;
+            return Ok((graph.add_node(pinned), true));
+        }
+        Some(node) => node,
+    };
+
+    let existing_reg = match &graph[node].source {
+        SourcePinned::Registry(reg) => reg.clone(),
+        _ => unreachable!("only registry nodes are unified"),
+    };
+    if registry::compatible_req(&source.version).matches(&existing_reg.version) {
+        return Ok((node, false));
+    }
+
+    // The existing node is pinned to an older version than this dependency requires. As all
+    // requirements are semver-compatible, the greatest base version satisfies every dependent.
+    let base = std::cmp::max(&existing_reg.source.version, &source.version).clone();
+    let pkg = Pkg {
+        name: pkg_name.to_string(),
+        source: Source::Registry(SourceRegistry {
+            index: source.index.clone(),
+            version: base,
+        }),
+    };
+    let pinned =
+        pin_pkg(fetch_id, path_root, &pkg, manifest_map, offline, vendor).map_err(|e| {
+            let mut requirers = registry_requirers(graph, node, manifest_map, member_manifests);
+            requirers.push((parent, dep_name.to_string(), source.clone()));
+            explain_registry_failure(graph, pkg_name, &requirers, e)
+        })?;
+    let old_id = graph[node].id();
+    if pinned.id() != old_id {
+        manifest_map.remove(&old_id);
+    }
+    graph[node] = pinned;
+    let edges: Vec<_> = graph
+        .edges_directed(node, Direction::Outgoing)
+        .map(|edge| edge.id())
+        .collect();
+    for edge in edges {
+        graph.remove_edge(edge);
+    }
+    Ok((node, true))
+}
+
+/// The dependency declaration within the `parent` manifest associated with the given edge.
+fn declared_dep<'a>(parent: &'a PackageManifestFile, edge: &Edge) -> Option<&'a Dependency> {
+    match edge.kind {
+        DepKind::Library => parent.dep(&edge.name),
+        DepKind::Contract { .. } => parent
+            .contract_dep(&edge.name)
+            .map(|contract_dep| &contract_dep.dependency),
+    }
+}
+
+/// Collect the dependents of the given registry node along with the registry source each declares.
+fn registry_requirers(
+    graph: &Graph,
+    node: NodeIx,
+    manifest_map: &ManifestMap,
+    member_manifests: &MemberManifestFiles,
+) -> Vec<(NodeIx, String, SourceRegistry)> {
+    graph
+        .edges_directed(node, Direction::Incoming)
+        .filter_map(|edge| {
+            let parent = edge.source();
+            let parent_manifest = manifest_map.get(&graph[parent].id())?;
+            let dep = declared_dep(parent_manifest, edge.weight())?;
+            let source =
+                dep_to_source_patched(parent_manifest, &graph[node].name, dep, member_manifests)
+                    .ok()?;
+            match source {
+                Source::Registry(reg) => Some((parent, edge.weight().name.clone(), reg)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Add an explanation of which dependents required which versions to a registry resolution error.
+fn explain_registry_failure(
+    graph: &Graph,
+    pkg_name: &str,
+    requirers: &[(NodeIx, String, SourceRegistry)],
+    error: anyhow::Error,
+) -> anyhow::Error {
+    let mut explanation = format!("failed to select a version for `{}`", pkg_name);
+    let _ = write!(explanation, "\n\n`{}` is required by:", pkg_name);
+    for (parent, dep_name, source) in requirers {
+        let req = registry::compatible_req(&source.version);
+        for path in dep_paths(graph, *parent) {
+            let _ = write!(
+                explanation,
+                "\n  {} -> {} ({} from {})",
+                format_dep_path(graph, &path),
+                dep_name,
+                req,
+                source.index
+            );
+        }
+    }
+    error.context(explanation)
+}
+
+/// Ensure that the graph contains at most one instance of each semver-compatible version of a
+/// package with a git or registry source, producing a readable explanation of the conflict if not.
+///
+/// Instances with incompatible versions may co-exist. A package without a `version` in its
+/// manifest is considered compatible with all other instances of the same package. Path and member
+/// packages are not considered, as these are disambiguated by their location.
+pub(crate) fn validate_unique_pkgs(graph: &Graph, manifest_map: &ManifestMap) -> Result<()> {
+    let version = |n: NodeIx| -> Option<semver::Version> {
+        match &graph[n].source {
+            SourcePinned::Registry(reg) => Some(reg.version.clone()),
+            _ => manifest_map
+                .get(&graph[n].id())
+                .and_then(|manifest| manifest.project.version.clone()),
+        }
+    };
+    let nodes: Vec<NodeIx> = graph
+        .node_indices()
+        .filter(|&n| {
+            matches!(
+                graph[n].source,
+                SourcePinned::Git(_) | SourcePinned::Registry(_)
+            )
+        })
+        .collect();
+    let mut reported = HashSet::new();
+    for (ix, &a) in nodes.iter().enumerate() {
+        if reported.contains(&a) {
+            continue;
+        }
+        let conflicts: Vec<NodeIx> = nodes[ix + 1..]
+            .iter()
+            .copied()
+            .filter(|&b| {
+                graph[a].name == graph[b].name
+                    && match (version(a), version(b)) {
+                        (Some(va), Some(vb)) => is_compatible(&va, &vb),
+                        _ => true,
+                    }
+            })
+            .collect();
+        if conflicts.is_empty() {
+            continue;
+        }
+        reported.extend(conflicts.iter().copied());
+        let name = &graph[a].name;
+        let mut msg = format!("conflicting sources for package `{}`:", name);
+        let mut unversioned = false;
+        for n in std::iter::once(a).chain(conflicts) {
+            let _ = write!(msg, "\n  {}", graph[n].source);
+            match version(n) {
+                Some(version) => {
+                    let _ = write!(msg, " (version {})", version);
+                }
+                None => {
+                    unversioned = true;
+                    msg.push_str(" (unversioned)");
+                }
+            }
+            for path in dep_paths(graph, n) {
+                let _ = write!(msg, "\n    required by {}", format_dep_path(graph, &path));
+            }
+        }
+        if unversioned {
+            let _ = write!(
+                msg,
+                "\n\nonly one source of `{}` may be used unless each declares a `version` in its \
+                package manifest: update the dependency declarations above so that they agree on \
+                a single source",
+                name
+            );
+            bail!(msg);
+        }
+        let _ = write!(
+            msg,
+            "\n\nonly one semver-compatible version of `{}` may be used: update the dependency \
+            declarations above so that they agree on a single source",
+            name
+        );
+        bail!(msg);
+    }
+    Ok(())
+}
+
+/// Collect the paths from the workspace members to the given node.
+///
+/// Each path begins with a member and ends with `node`. At most `MAX_DEP_PATHS` paths are returned.
+fn dep_paths(graph: &Graph, node: NodeIx) -> Vec<Vec<NodeIx>> {
+    fn visit(graph: &Graph, path: &mut Vec<NodeIx>, paths: &mut Vec<Vec<NodeIx>>) {
+        if paths.len() >= MAX_DEP_PATHS {
+            return;
+        }
+        let node = *path.last().expect("path is never empty");
+        let mut parents = graph
+            .edges_directed(node, Direction::Incoming)
+            .map(|edge| edge.source())
+            .filter(|parent| !path.contains(parent))
+            .peekable();
+        if parents.peek().is_none() {
+            paths.push(path.iter().rev().copied().collect());
+            return;
+        }
+        for parent in parents.collect::<Vec<_>>() {
+            path.push(parent);
+            visit(graph, path, paths);
+            path.pop();
+        }
+    }
+    let mut paths = vec![];
+    visit(graph, &mut vec![node], &mut paths);
+    paths
+}
+
+/// Format a dependency path as `a -> b -> c`.
+fn format_dep_path(graph: &Graph, path: &[NodeIx]) -> String {
+    path.iter()
+        .map(|&n| &graph[n].name[..])
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Remove all nodes that are no longer reachable from a workspace member, along with their
+/// manifests.
+///
+/// Nodes may become unreachable when a registry package is re-pinned to a version with different
+/// dependencies.
+pub(crate) fn remove_unreachable(graph: &mut Graph, manifest_map: &mut ManifestMap) {
+    let mut reachable = HashSet::new();
+    for member in member_nodes(graph).collect::<Vec<_>>() {
+        let mut dfs = Dfs::new(&*graph, member);
+        while let Some(n) = dfs.next(&*graph) {
+            reachable.insert(n);
+        }
+    }
+    let unreachable: Vec<NodeIx> = graph
+        .node_indices()
+        .filter(|n| !reachable.contains(n))
+        .collect();
+    for n in unreachable {
+        manifest_map.remove(&graph[n].id());
+        graph.remove_node(n);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        manifest::ManifestFile,
+        pkg::Pinned,
+        test_util::{self, publish},
+        BuildPlan,
+    };
+    use std::{path::Path, str::FromStr};
+
+    fn pinned(name: &str, source: &str) -> Pinned {
+        Pinned {
+            name: name.to_string(),
+            source: SourcePinned::from_str(source).unwrap(),
+        }
+    }
+
+    fn member(name: &str) -> Pinned {
+        Pinned {
+            name: name.to_string(),
+            source: SourcePinned::Member,
+        }
+    }
+
+    fn add_dep(graph: &mut Graph, parent: NodeIx, dep: NodeIx) {
+        let name = graph[dep].name.clone();
+        graph.add_edge(parent, dep, Edge::new(name, DepKind::Library));
+    }
+
+    /// A graph in which `app` depends on `foo` and `bar` and `bar` depends on another `foo`.
+    fn graph_with_two_foos(foo_a: Pinned, foo_b: Pinned) -> Graph {
+        let mut graph = Graph::default();
+        let app = graph.add_node(member("app"));
+        let bar = graph.add_node(pinned("bar", "registry+file:///index/?0.1.0#0.1.0"));
+        let (foo_a, foo_b) = (graph.add_node(foo_a), graph.add_node(foo_b));
+        add_dep(&mut graph, app, foo_a);
+        add_dep(&mut graph, app, bar);
+        add_dep(&mut graph, bar, foo_b);
+        graph
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        let v = |s| semver::Version::parse(s).unwrap();
+        assert!(is_compatible(&v("1.2.3"), &v("1.0.0")));
+        assert!(is_compatible(&v("0.2.3"), &v("0.2.0")));
+        assert!(is_compatible(&v("0.0.3"), &v("0.0.3")));
+        assert!(!is_compatible(&v("1.2.3"), &v("2.0.0")));
+        assert!(!is_compatible(&v("0.2.3"), &v("0.3.0")));
+        assert!(!is_compatible(&v("0.0.3"), &v("0.0.4")));
+    }
+
+    #[test]
+    fn test_unify_registry_versions_across_graph() {
+        test_util::with_forc_home(|_| {
+            let dir = test_util::temp_dir();
+            let index = dir.path().join("index");
+            for version in ["1.0.0", "1.2.0", "1.3.0", "2.0.0", "2.1.0"] {
+                publish(&index, "foo", version, "");
+            }
+            // The index location may only be parsed once the index exists.
+            let registry =
+                registry::parse_index_location(index.to_str().unwrap(), dir.path()).unwrap();
+            let dep = |version: &str| {
+                format!("foo = {{ version = \"{version}\", registry = \"{registry}\" }}\n")
+            };
+            // `bar` requires `foo` 1.2, while `baz` requires the incompatible `foo` 2.0.
+            publish(&index, "bar", "1.0.0", &dep("1.2"));
+            publish(&index, "baz", "1.0.0", &dep("2.0"));
+
+            let app_dir = dir.path().join("app");
+            let deps = format!(
+                "[dependencies]\n{}bar = {{ version = \"1.0\", registry = \"{registry}\" }}\n\
+                baz = {{ version = \"1.0\", registry = \"{registry}\" }}\n",
+                dep("1.0"),
+            );
+            test_util::write_lib(&app_dir, "app", &deps);
+            let manifests = ManifestFile::from_dir(&app_dir)
+                .unwrap()
+                .member_manifests()
+                .unwrap();
+            let lock_path = app_dir.join("Forc.lock");
+            let plan =
+                BuildPlan::from_lock_and_manifests(&lock_path, &manifests, false, true).unwrap();
+
+            // `app` and `bar` share the latest 1.x version of `foo`, while `baz` uses the latest
+            // 2.x.
+            let graph = plan.graph();
+            let mut foos: Vec<(String, Vec<String>)> = graph
+                .node_indices()
+                .filter(|&n| graph[n].name == "foo")
+                .map(|n| {
+                    let version = match &graph[n].source {
+                        SourcePinned::Registry(reg) => reg.version.to_string(),
+                        _ => panic!("`foo` is a registry package"),
+                    };
+                    let mut dependents: Vec<String> = graph
+                        .edges_directed(n, Direction::Incoming)
+                        .map(|edge| graph[edge.source()].name.clone())
+                        .collect();
+                    dependents.sort();
+                    (version, dependents)
+                })
+                .collect();
+            foos.sort();
+            let expected = vec![
+                (
+                    "1.3.0".to_string(),
+                    vec!["app".to_string(), "bar".to_string()],
+                ),
+                ("2.1.0".to_string(), vec!["baz".to_string()]),
+            ];
+            assert_eq!(foos, expected);
+        })
+    }
+
+    #[test]
+    fn test_satisfies_unified_registry_version() {
+        let pinned = pinned("foo", "registry+file:///index/?1.0.0#1.3.0");
+        let declared = |version: &str| {
+            Source::Registry(SourceRegistry {
+                index: url::Url::parse("file:///index/").unwrap(),
+                version: semver::Version::parse(version).unwrap(),
+            })
+        };
+        let pkg = pinned.unpinned(Path::new("/"));
+        assert!(satisfies(&pkg, &pinned.source, &declared("1.0.0")));
+        assert!(satisfies(&pkg, &pinned.source, &declared("1.3.0")));
+        assert!(!satisfies(&pkg, &pinned.source, &declared("1.4.0")));
+        assert!(!satisfies(&pkg, &pinned.source, &declared("2.0.0")));
+    }
+
+    #[test]
+    fn test_compatible_registry_sources_conflict() {
+        let graph = graph_with_two_foos(
+            pinned("foo", "registry+file:///index/?1.0.0#1.0.0"),
+            pinned("foo", "registry+file:///other_index/?1.1.0#1.1.0"),
+        );
+        let err = validate_unique_pkgs(&graph, &ManifestMap::default())
+            .unwrap_err()
+            .to_string();
+        let expected = "conflicting sources for package `foo`:\n  \
+            registry+file:///index/?1.0.0#1.0.0 (version 1.0.0)\n    \
+            required by app -> foo\n  \
+            registry+file:///other_index/?1.1.0#1.1.0 (version 1.1.0)\n    \
+            required by app -> bar -> foo\n\n\
+            only one semver-compatible version of `foo` may be used: update the dependency \
+            declarations above so that they agree on a single source";
+        assert_eq!(err, expected);
+    }
+
+    #[test]
+    fn test_incompatible_registry_sources_coexist() {
+        let graph = graph_with_two_foos(
+            pinned("foo", "registry+file:///index/?1.0.0#1.0.0"),
+            pinned("foo", "registry+file:///other_index/?2.0.0#2.0.0"),
+        );
+        assert!(validate_unique_pkgs(&graph, &ManifestMap::default()).is_ok());
+    }
+
+    #[test]
+    fn test_unversioned_git_sources_conflict() {
+        let graph = graph_with_two_foos(
+            pinned(
+                "foo",
+                "git+https://github.com/owner/foo?tag=v0.1.0#87f80bdf323e2d64e213895d0a639ad468f4deff",
+            ),
+            pinned(
+                "foo",
+                "git+https://github.com/owner/foo?branch=master#5a9d5b5ee2a75e3f6a1a2d7b1e0e2d38b4e3a1c0",
+            ),
+        );
+        let err = validate_unique_pkgs(&graph, &ManifestMap::default())
+            .unwrap_err()
+            .to_string();
+        let expected = "conflicting sources for package `foo`:\n  \
+            git+https://github.com/owner/foo?tag=v0.1.0#87f80bdf323e2d64e213895d0a639ad468f4deff \
+            (unversioned)\n    \
+            required by app -> foo\n  \
+            git+https://github.com/owner/foo?branch=master#5a9d5b5ee2a75e3f6a1a2d7b1e0e2d38b4e3a1c0 \
+            (unversioned)\n    \
+            required by app -> bar -> foo\n\n\
+            only one source of `foo` may be used unless each declares a `version` in its package \
+            manifest: update the dependency declarations above so that they agree on a single \
+            source";
+        assert_eq!(err, expected);
+    }
+}
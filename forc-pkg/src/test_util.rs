@@ -0,0 +1,93 @@
+//! Fixtures shared by the tests of forc's crates.
+//!
+//! All files are written to temporary directories that are removed once the returned `TempDir`
+//! is dropped, including when a test panics.
+
+use forc_util::FORC_HOME_ENV_VAR;
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::Mutex,
+};
+use sway_utils::constants;
+pub use tempfile::TempDir;
+
+/// Held by `with_forc_home` while the user's forc directory is overridden, as the override
+/// applies to the whole process.
+static FORC_HOME_LOCK: Mutex<()> = Mutex::new(());
+
+/// Create a new, empty temporary directory.
+pub fn temp_dir() -> TempDir {
+    tempfile::tempdir().expect("failed to create a temporary directory")
+}
+
+/// Write a library package named `name` to `dir`, without an implicit `std` dependency.
+///
+/// The `tables` are appended to the manifest as is, e.g. `"[dependencies]\nfoo = ..."`.
+pub fn write_lib(dir: &Path, name: &str, tables: &str) {
+    write_pkg(dir, name, "lib.sw", &format!("library {name};\n"), tables);
+}
+
+/// Write a contract package named `name` with no ABI to `dir`, without an implicit `std`
+/// dependency.
+///
+/// The `tables` are appended to the manifest as is, e.g. `"[contract-dependencies]\nfoo = ..."`.
+pub fn write_contract(dir: &Path, name: &str, tables: &str) {
+    write_pkg(dir, name, "main.sw", "contract;\n", tables);
+}
+
+/// A `[dependencies]` entry for the `core` library of this repository, with which the packages
+/// written by these fixtures may be compiled.
+pub fn core_dep() -> String {
+    let core_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../sway-lib-core");
+    format!("core = {{ path = {:?} }}\n", core_path)
+}
+
+fn write_pkg(dir: &Path, name: &str, entry: &str, src: &str, tables: &str) {
+    let src_dir = dir.join(constants::SRC_DIR);
+    fs::create_dir_all(&src_dir).unwrap();
+    let manifest = format!(
+        "[project]\nauthors = [\"Fuel Labs <contact@fuel.sh>\"]\nentry = \"{entry}\"\n\
+        license = \"Apache-2.0\"\nname = \"{name}\"\nimplicit-std = false\n\n{tables}"
+    );
+    fs::write(dir.join(constants::MANIFEST_FILE_NAME), manifest).unwrap();
+    fs::write(src_dir.join(entry), src).unwrap();
+}
+
+/// Publish version `version` of the library `name` with the given `[dependencies]` to the
+/// file-based registry index at `index`.
+pub fn publish(index: &Path, name: &str, version: &str, deps: &str) {
+    let pkg_dir_name = format!("{name}-{version}");
+    write_lib(
+        &index.join(&pkg_dir_name),
+        name,
+        &format!("[dependencies]\n{deps}"),
+    );
+    let mut entries = fs::read_to_string(index.join(name)).unwrap_or_default();
+    entries.push_str(&format!(
+        "{{\"name\":\"{name}\",\"version\":\"{version}\",\"path\":\"{pkg_dir_name}\"}}\n"
+    ));
+    fs::write(index.join(name), entries).unwrap();
+}
+
+/// Run `f` with the user's forc directory overridden by a new temporary directory, so that any
+/// fetched sources or cached builds are isolated from the real directory and from other tests.
+///
+/// As the override applies to the whole process, calls are serialized. Every test that may read
+/// from or write to the user's forc directory, e.g. by building or fetching packages, must run
+/// within `with_forc_home` so that it never observes the directory of another test.
+pub fn with_forc_home<T>(f: impl FnOnce(&Path) -> T) -> T {
+    let _guard = FORC_HOME_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let forc_home = temp_dir();
+    let prev = std::env::var_os(FORC_HOME_ENV_VAR);
+    std::env::set_var(FORC_HOME_ENV_VAR, forc_home.path());
+    let res = panic::catch_unwind(AssertUnwindSafe(|| f(forc_home.path())));
+    match prev {
+        Some(prev) => std::env::set_var(FORC_HOME_ENV_VAR, prev),
+        None => std::env::remove_var(FORC_HOME_ENV_VAR),
+    }
+    res.unwrap_or_else(|panic| panic::resume_unwind(panic))
+}
@@ -0,0 +1,395 @@
+//! Vendoring the git and registry sources of a project's dependencies.
+//!
+//! Vendored sources are copied to a `vendor` directory alongside the project's `Forc.lock`. Each
+//! source is described within `vendor/vendor.toml`:
+//!
+//! ```toml
+//! [[source]]
+//! name = 'std'
+//! source = 'git+https://github.com/fuellabs/sway?tag=v0.32.2#b9996f1c8b2a8b1e9b0e3f6d0c5d5b0e2b6fa6d4'
+//! path = 'std-b9996f1c8b2a8b1e9b0e3f6d0c5d5b0e2b6fa6d4'
+//! dirs = ['sway-lib-core', 'sway-lib-std']
+//! checksum = '<sha256>'
+//! ```
+//!
+//! The `path` of each source is relative to the vendor directory, while `dirs` lists the package
+//! directories copied from the root of the source, e.g. a git package along with the path
+//! dependencies that reside within the same repository.
+//!
+//! While `vendor/vendor.toml` exists, git and registry dependencies are only ever resolved from
+//! the vendor directory, never from the network or the user's forc directory.
+
+use crate::{
+    checksum::{dirs_checksum, is_checksummed, source_dirs},
+    lock::Lock,
+    manifest::MemberManifestFiles,
+    pkg::{
+        fetch_id, fetch_source, find_dir_within, source_root, BuildPlan, Pinned, SourceGit,
+        SourceGitPinned, SourcePinned, SourceRegistry, SourceRegistryPinned,
+    },
+    registry,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use tracing::info;
+
+/// The name of the directory, alongside `Forc.lock`, to which sources are vendored.
+pub const VENDOR_DIR_NAME: &str = "vendor";
+
+/// The name of the file describing the vendored sources within the vendor directory.
+pub const VENDOR_CONFIG_FILE_NAME: &str = "vendor.toml";
+
+/// The contents of `vendor/vendor.toml`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct VendorConfig {
+    // Named `source` so that each entry serializes under `[[source]]`.
+    #[serde(default, rename = "source")]
+    pub sources: Vec<VendoredSource>,
+}
+
+/// A git or registry source that has been copied to the vendor directory.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VendoredSource {
+    /// The name of the package pinned to this source.
+    pub name: String,
+    /// The pinned source, as it appears within `Forc.lock`.
+    pub source: String,
+    /// The location of the vendored copy of the source's root, relative to the vendor directory.
+    pub path: PathBuf,
+    /// The package directories that were copied, relative to the root of the source.
+    pub dirs: Vec<PathBuf>,
+    /// The checksum of the contents of `dirs` at the time they were vendored.
+    pub checksum: String,
+}
+
+/// The vendored sources of a project, as loaded from its vendor directory.
+#[derive(Debug)]
+pub struct Vendor {
+    dir: PathBuf,
+    sources: Vec<(Pinned, VendoredSource)>,
+}
+
+impl Vendor {
+    /// Load the vendored sources of the project whose lock file is at `lock_path`.
+    ///
+    /// Returns `None` in the case that the project has not been vendored.
+    pub fn from_lock_path(lock_path: &Path) -> Result<Option<Self>> {
+        let dir = vendor_dir(lock_path);
+        let config_path = dir.join(VENDOR_CONFIG_FILE_NAME);
+        if !config_path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&config_path)?;
+        let config: VendorConfig = toml::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", config_path.display()))?;
+        let sources = config
+            .sources
+            .into_iter()
+            .map(|vendored| {
+                let source = SourcePinned::from_str(&vendored.source).map_err(|e| {
+                    anyhow!("invalid vendored source {:?}: {:?}", vendored.source, e)
+                })?;
+                let pinned = Pinned {
+                    name: vendored.name.clone(),
+                    source,
+                };
+                Ok((pinned, vendored))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Some(Self { dir, sources }))
+    }
+
+    /// Load the vendored sources of the project to which the given members belong.
+    pub fn from_manifests(manifests: &MemberManifestFiles) -> Result<Option<Self>> {
+        match manifests.values().next() {
+            Some(manifest) => Self::from_lock_path(&manifest.lock_path()?),
+            None => Ok(None),
+        }
+    }
+
+    /// The root of the vendored copy of the given pinned package's source.
+    pub fn root(&self, pinned: &Pinned) -> Result<PathBuf> {
+        self.sources
+            .iter()
+            .find(|(vendored, _)| vendored == pinned)
+            .map(|(_, vendored)| self.dir.join(&vendored.path))
+            .ok_or_else(|| not_vendored(&pinned.name, &pinned.source.to_string()))
+    }
+
+    /// Pin the git source to the vendored commit, returning the root of the vendored copy.
+    pub(crate) fn pin_git(
+        &self,
+        name: &str,
+        source: &SourceGit,
+    ) -> Result<(SourceGitPinned, PathBuf)> {
+        self.sources
+            .iter()
+            .find_map(|(pinned, vendored)| match &pinned.source {
+                SourcePinned::Git(git) if pinned.name == name && git.source == *source => {
+                    Some((git.clone(), self.dir.join(&vendored.path)))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| not_vendored(name, &format!("{}?{}", source.repo, source.reference)))
+    }
+
+    /// Pin the registry source to the latest compatible vendored version, returning the root of
+    /// the vendored copy.
+    pub(crate) fn pin_registry(
+        &self,
+        name: &str,
+        source: &SourceRegistry,
+    ) -> Result<(SourceRegistryPinned, PathBuf)> {
+        let req = registry::compatible_req(&source.version);
+        self.sources
+            .iter()
+            .filter_map(|(pinned, vendored)| match &pinned.source {
+                SourcePinned::Registry(reg)
+                    if pinned.name == name
+                        && reg.source.index == source.index
+                        && req.matches(&reg.version) =>
+                {
+                    let pinned_reg = SourceRegistryPinned {
+                        source: source.clone(),
+                        version: reg.version.clone(),
+                    };
+                    Some((pinned_reg, self.dir.join(&vendored.path)))
+                }
+                _ => None,
+            })
+            .max_by(|(a, _), (b, _)| a.version.cmp(&b.version))
+            .ok_or_else(|| not_vendored(name, &format!("{} {}", source.index, req)))
+    }
+}
+
+/// The vendor directory of the project whose lock file is at `lock_path`.
+pub fn vendor_dir(lock_path: &Path) -> PathBuf {
+    lock_path
+        .parent()
+        .expect("lock file has no parent directory")
+        .join(VENDOR_DIR_NAME)
+}
+
+fn not_vendored(name: &str, source: &str) -> anyhow::Error {
+    anyhow!(
+        "`{}` ({}) has not been vendored, run `forc vendor` to update the vendor directory",
+        name,
+        source
+    )
+}
+
+/// Copy the source of every git and registry package within the `plan` to the vendor directory of
+/// the project whose lock file is at `lock_path`, replacing any existing vendor directory.
+///
+/// The `plan` should be constructed without regard for any existing vendored sources, i.e. via
+/// [BuildPlan::from_lock_and_manifests_unvendored].
+pub fn vendor(plan: &BuildPlan, lock_path: &Path) -> Result<VendorConfig> {
+    let dir = vendor_dir(lock_path);
+    let tmp_dir = dir.with_extension("tmp");
+    if tmp_dir.exists() {
+        fs::remove_dir_all(&tmp_dir)?;
+    }
+    fs::create_dir_all(&tmp_dir)?;
+
+    let graph = plan.graph();
+    let mut nodes: Vec<_> = graph
+        .node_indices()
+        .filter(|&n| is_checksummed(&graph[n].source))
+        .collect();
+    nodes.sort_by_key(|&n| (graph[n].name.clone(), graph[n].source.to_string()));
+
+    let mut config = VendorConfig::default();
+    for node in nodes {
+        let pinned = &graph[node];
+        let root = source_root(pinned)?;
+        let dirs = source_dirs(graph, plan.manifest_map(), pinned, &root)?;
+        let path = PathBuf::from(vendored_dir_name(pinned));
+        if config.sources.iter().any(|vendored| vendored.path == path) {
+            bail!("multiple sources would be vendored to {}", path.display());
+        }
+        info!("   Vendoring {} ({})", pinned.name, pinned.source);
+        for rel_dir in &dirs {
+            registry::copy_dir(&root.join(rel_dir), &tmp_dir.join(&path).join(rel_dir))
+                .with_context(|| format!("failed to vendor `{}`", pinned.name))?;
+        }
+        let checksum = dirs_checksum(&root, &dirs)?;
+        config.sources.push(VendoredSource {
+            name: pinned.name.clone(),
+            source: pinned.source.to_string(),
+            path,
+            dirs,
+            checksum,
+        });
+    }
+
+    let string = toml::ser::to_string_pretty(&config)
+        .map_err(|e| anyhow!("failed to serialize vendor config: {}", e))?;
+    fs::write(tmp_dir.join(VENDOR_CONFIG_FILE_NAME), string)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::rename(&tmp_dir, &dir)?;
+    Ok(config)
+}
+
+/// Verify that the vendored sources of the project whose lock file is at `lock_path` match the
+/// sources pinned within its `Forc.lock`.
+///
+/// This checks that every git and registry package within the lock file has been vendored, that
+/// the vendored contents are unmodified since they were vendored, and that they match the
+/// contents of the pinned commit or version. Pinned sources that are not yet available locally
+/// are fetched unless `offline` is specified.
+///
+/// Upon failure, all discrepancies are reported within the error.
+pub fn verify(lock_path: &Path, offline: bool) -> Result<()> {
+    let vendor = Vendor::from_lock_path(lock_path)?.ok_or_else(|| {
+        anyhow!(
+            "no vendored sources found at {}",
+            vendor_dir(lock_path).display()
+        )
+    })?;
+    let lock = Lock::from_path(lock_path)?;
+    let graph = lock.to_graph()?;
+    let locked: Vec<&Pinned> = graph
+        .node_indices()
+        .map(|n| &graph[n])
+        .filter(|pinned| is_checksummed(&pinned.source))
+        .collect();
+
+    let mut errors = vec![];
+    for pinned in &locked {
+        if vendor.root(pinned).is_err() {
+            errors.push(format!(
+                "`{}` ({}) is pinned within Forc.lock but has not been vendored",
+                pinned.name, pinned.source
+            ));
+        }
+    }
+
+    let fetch_id = fetch_id(&vendor.dir, std::time::Instant::now());
+    for (pinned, vendored) in &vendor.sources {
+        let desc = format!("`{}` ({})", pinned.name, pinned.source);
+        if !locked.contains(&pinned) {
+            errors.push(format!(
+                "{desc} is vendored but is not pinned within Forc.lock"
+            ));
+            continue;
+        }
+        if matches!(lock.checksum(pinned), Some(checksum) if checksum != vendored.checksum) {
+            errors.push(format!(
+                "{desc} does not match the checksum recorded within Forc.lock"
+            ));
+            continue;
+        }
+        let checksum = dirs_checksum(&vendor.dir.join(&vendored.path), &vendored.dirs)?;
+        if checksum != vendored.checksum {
+            errors.push(format!("{desc} has been modified since it was vendored"));
+            continue;
+        }
+        let root = fetch_source(fetch_id, pinned, offline, false)
+            .with_context(|| format!("failed to fetch {desc} for verification"))?;
+        if dirs_checksum(&root, &vendored.dirs)? != vendored.checksum {
+            errors.push(format!("{desc} does not match its pinned source"));
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!(
+            "vendored sources failed verification:\n  {}",
+            errors.join("\n  ")
+        );
+    }
+    Ok(())
+}
+
+/// The name of the directory to which the given source is vendored.
+fn vendored_dir_name(pinned: &Pinned) -> String {
+    match &pinned.source {
+        SourcePinned::Git(git) => format!("{}-{}", pinned.name, git.commit_hash),
+        SourcePinned::Registry(reg) => format!("{}-{}", pinned.name, reg.version),
+        _ => unreachable!("only git and registry sources are vendored"),
+    }
+}
+
+/// Find the directory of the named package within a vendored source root.
+pub(crate) fn find_pkg_dir(root: &Path, pinned_name: &str, source: &str) -> Result<PathBuf> {
+    find_dir_within(root, pinned_name).ok_or_else(|| {
+        anyhow!(
+            "failed to find package `{}` in vendored source {}",
+            pinned_name,
+            source
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vendored(name: &str, source: &str) -> VendoredSource {
+        VendoredSource {
+            name: name.to_string(),
+            source: source.to_string(),
+            path: PathBuf::from(name),
+            dirs: vec![PathBuf::new()],
+            checksum: String::new(),
+        }
+    }
+
+    fn vendor(sources: &[VendoredSource]) -> Vendor {
+        let sources = sources
+            .iter()
+            .map(|vendored| {
+                let pinned = Pinned {
+                    name: vendored.name.clone(),
+                    source: SourcePinned::from_str(&vendored.source).unwrap(),
+                };
+                (pinned, vendored.clone())
+            })
+            .collect();
+        Vendor {
+            dir: PathBuf::from("vendor"),
+            sources,
+        }
+    }
+
+    #[test]
+    fn test_pin_registry_selects_latest_compatible() {
+        let vendor = vendor(&[
+            vendored("foo", "registry+file:///index/?0.1.0#0.1.2"),
+            vendored("foo", "registry+file:///index/?0.1.0#0.1.5"),
+            vendored("foo", "registry+file:///index/?0.2.0#0.2.0"),
+        ]);
+        let source = SourceRegistry {
+            index: url::Url::parse("file:///index/").unwrap(),
+            version: semver::Version::parse("0.1.3").unwrap(),
+        };
+        let (pinned, _) = vendor.pin_registry("foo", &source).unwrap();
+        assert_eq!(pinned.version, semver::Version::parse("0.1.5").unwrap());
+        assert!(vendor.pin_registry("bar", &source).is_err());
+    }
+
+    #[test]
+    fn test_pin_git_requires_matching_reference() {
+        let commit = "436d5f28863b51caf84a4aaebf905bb4f1e35d8e";
+        let vendor = vendor(&[vendored(
+            "foo",
+            &format!("git+https://github.com/owner/foo?tag=v0.1.0#{commit}"),
+        )]);
+        let source = |reference| SourceGit {
+            repo: url::Url::parse("https://github.com/owner/foo").unwrap(),
+            reference,
+        };
+        let tag = source(crate::GitReference::Tag("v0.1.0".to_string()));
+        let (pinned, root) = vendor.pin_git("foo", &tag).unwrap();
+        assert_eq!(pinned.commit_hash, commit);
+        assert_eq!(root, PathBuf::from("vendor").join("foo"));
+        let branch = source(crate::GitReference::Branch("master".to_string()));
+        assert!(vendor.pin_git("foo", &branch).is_err());
+    }
+}
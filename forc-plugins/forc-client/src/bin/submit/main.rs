@@ -0,0 +1,15 @@
+use forc_client::ops::submit::{cmd::SubmitCommand, op::submit};
+use forc_tracing::init_tracing_subscriber;
+use std::process;
+
+use clap::Parser;
+
+#[tokio::main]
+async fn main() {
+    init_tracing_subscriber(Default::default());
+    let command = SubmitCommand::parse();
+    if let Err(err) = submit(command).await {
+        tracing::error!("Error: {:?}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,510 @@
+//! Encoding the arguments of a program's ABI functions and decoding their return values, using
+//! the types described by the program's JSON ABI.
+
+use anyhow::{anyhow, bail, Context, Result};
+use forc_pkg as pkg;
+use fuel_tx::{ContractId, Receipt};
+use fuels_core::{
+    abi_decoder::ABIDecoder, abi_encoder::ABIEncoder,
+    code_gen::function_selector::resolve_fn_selector, ByteArray, StringToken, Token,
+};
+use fuels_types::{
+    param_types::{ParamType, ReturnLocation},
+    ProgramABI, TypeApplication, TypeDeclaration,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The name, parameters and return type of a function declared within a program's ABI.
+pub(crate) struct AbiFunction {
+    name: String,
+    /// The ABI type application and type of each parameter, in order.
+    params: Vec<(TypeApplication, ParamType)>,
+    output: ParamType,
+    /// All types of the program ABI by type ID, used to look up the names of struct fields and
+    /// enum variants.
+    type_lookup: HashMap<usize, TypeDeclaration>,
+}
+
+impl AbiFunction {
+    /// Resolve the types of the function with the given name declared within the program ABI.
+    pub(crate) fn from_abi(abi: &ProgramABI, name: &str) -> Result<Self> {
+        let type_lookup = pkg::abi::type_lookup(abi);
+        let param_type = |application: &TypeApplication| {
+            ParamType::try_from_type_application(application, &type_lookup).map_err(|e| {
+                anyhow!(
+                    "failed to resolve the type of `{}`: {}",
+                    application.name,
+                    e
+                )
+            })
+        };
+        let function = abi
+            .functions
+            .iter()
+            .find(|function| function.name == name)
+            .ok_or_else(|| anyhow!("the ABI does not declare a function named `{name}`"))?;
+        let params = function
+            .inputs
+            .iter()
+            .map(|input| Ok((input.clone(), param_type(input)?)))
+            .collect::<Result<_>>()?;
+        let output = param_type(&function.output)?;
+        Ok(Self {
+            name: name.to_string(),
+            params,
+            output,
+            type_lookup,
+        })
+    }
+
+    /// The selector by which a contract identifies the function when it is called.
+    pub(crate) fn selector(&self) -> ByteArray {
+        let param_types: Vec<_> = self.params.iter().map(|(_, ty)| ty.clone()).collect();
+        resolve_fn_selector(&self.name, &param_types)
+    }
+
+    /// Whether the arguments are passed to the function by a pointer to their encoding, rather
+    /// than by value within the call's single argument word.
+    pub(crate) fn takes_args_by_reference(&self) -> bool {
+        self.params.len() > 1
+            || self.params.iter().any(|(_, ty)| {
+                !matches!(
+                    ty,
+                    ParamType::Unit
+                        | ParamType::U8
+                        | ParamType::U16
+                        | ParamType::U32
+                        | ParamType::U64
+                        | ParamType::Bool
+                )
+            })
+    }
+
+    /// Convert arguments given as a JSON object keyed by parameter name, or as a JSON array of
+    /// values in the order of the parameters.
+    pub(crate) fn tokenize_json(&self, args: &str) -> Result<Vec<Token>> {
+        let args: Value = serde_json::from_str(args).context("the arguments must be valid JSON")?;
+        let values: Vec<&Value> = match &args {
+            Value::Object(map) => {
+                if let Some(name) = map
+                    .keys()
+                    .find(|name| !self.params.iter().any(|(param, _)| param.name == **name))
+                {
+                    bail!("`{}` has no parameter named `{name}`", self.name);
+                }
+                self.params
+                    .iter()
+                    .map(|(param, _)| {
+                        map.get(&param.name).ok_or_else(|| {
+                            anyhow!("missing argument for parameter `{}`", param.name)
+                        })
+                    })
+                    .collect::<Result<_>>()?
+            }
+            Value::Array(values) => values.iter().collect(),
+            _ => {
+                bail!("the arguments must be a JSON object keyed by parameter name or a JSON array")
+            }
+        };
+        self.tokenize(values)
+    }
+
+    /// Convert arguments given one per parameter. Each argument is parsed as JSON, or otherwise
+    /// taken to be a string so that e.g. `b256` values need not be quoted.
+    pub(crate) fn tokenize_literals(&self, args: &[String]) -> Result<Vec<Token>> {
+        let values: Vec<Value> = args
+            .iter()
+            .map(|arg| serde_json::from_str(arg).unwrap_or_else(|_| Value::String(arg.clone())))
+            .collect();
+        self.tokenize(values.iter().collect())
+    }
+
+    fn tokenize(&self, values: Vec<&Value>) -> Result<Vec<Token>> {
+        if values.len() != self.params.len() {
+            bail!(
+                "`{}` expects {} argument(s) but {} were given",
+                self.name,
+                self.params.len(),
+                values.len()
+            );
+        }
+        self.params
+            .iter()
+            .zip(values)
+            .map(|((param, param_type), value)| {
+                json_to_token(param_type, Some(param), &self.type_lookup, value)
+                    .with_context(|| format!("invalid argument for parameter `{}`", param.name))
+            })
+            .collect()
+    }
+
+    /// Decode the value returned by the function from the receipts of a transaction, given the ID
+    /// of the contract that declares it, or the zeroed ID in the case of a script's `main`.
+    ///
+    /// Returns `None` in the case that the function did not return, e.g. because it reverted.
+    pub(crate) fn decode_return(
+        &self,
+        receipts: &[Receipt],
+        id: &ContractId,
+    ) -> Result<Option<Token>> {
+        let location = self.output.get_return_location();
+        let bytes = receipts
+            .iter()
+            .find_map(|receipt| match (receipt, &location) {
+                (
+                    Receipt::Return {
+                        id: ret_id, val, ..
+                    },
+                    ReturnLocation::Return,
+                ) if ret_id == id => Some(val.to_be_bytes().to_vec()),
+                (
+                    Receipt::ReturnData {
+                        id: ret_id, data, ..
+                    },
+                    ReturnLocation::ReturnData,
+                ) if ret_id == id => Some(data.clone()),
+                _ => None,
+            });
+        bytes
+            .map(|bytes| ABIDecoder::decode_single(&self.output, &bytes))
+            .transpose()
+            .map_err(|e| {
+                anyhow!(
+                    "failed to decode the value returned by `{}`: {}",
+                    self.name,
+                    e
+                )
+            })
+    }
+}
+
+/// ABI-encode the given arguments as the script data of a script with the given bytecode.
+pub(crate) fn encode_script_data(args: &[Token], bytecode: &[u8]) -> Result<Vec<u8>> {
+    // Heap types, e.g. vectors, are encoded as pointers into the script data, so the encoding
+    // depends on the VM memory address at which the script data begins.
+    let encoded = ABIEncoder::encode(args)
+        .map_err(|e| anyhow!("failed to encode the arguments of `main`: {}", e))?;
+    Ok(encoded.resolve(pkg::abi::script_data_addr(bytecode)))
+}
+
+/// Convert a JSON value to a token of the given type, using its ABI type application (where
+/// known) to look up the names of struct fields and enum variants.
+///
+/// Structs are given as objects keyed by field name, or as arrays of field values in order. Enums
+/// are given as an object with a single key naming the variant, or as the name alone for variants
+/// of type `()`. Tuples, arrays and vectors are given as arrays. `b256` values are given as hex
+/// strings, and integers may be given as strings for values that JSON cannot represent exactly.
+fn json_to_token(
+    param_type: &ParamType,
+    application: Option<&TypeApplication>,
+    type_lookup: &HashMap<usize, TypeDeclaration>,
+    value: &Value,
+) -> Result<Token> {
+    let components = pkg::abi::type_components(application, type_lookup);
+    let type_name = pkg::abi::type_name(application, type_lookup);
+    let token = match param_type {
+        ParamType::Unit => match value {
+            Value::Null => Token::Unit,
+            _ => bail!("expected `null` for a value of type `()`, found {value}"),
+        },
+        ParamType::U8 => Token::U8(json_uint(value, "u8")?),
+        ParamType::U16 => Token::U16(json_uint(value, "u16")?),
+        ParamType::U32 => Token::U32(json_uint(value, "u32")?),
+        ParamType::U64 => Token::U64(json_uint(value, "u64")?),
+        ParamType::Byte => Token::Byte(json_uint(value, "byte")?),
+        ParamType::Bool => Token::Bool(
+            value
+                .as_bool()
+                .ok_or_else(|| anyhow!("expected a bool, found {value}"))?,
+        ),
+        ParamType::B256 => {
+            let s = json_str(value)?;
+            let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s))?;
+            let bytes = <[u8; 32]>::try_from(bytes)
+                .map_err(|_| anyhow!("expected 32 bytes of hex, found `{s}`"))?;
+            Token::B256(bytes)
+        }
+        ParamType::String(len) => {
+            let s = json_str(value)?;
+            if s.len() != *len {
+                bail!("expected a string of length {len}, found `{s}`");
+            }
+            Token::String(StringToken::new(s.to_string(), *len))
+        }
+        ParamType::Array(elem_type, len) => {
+            let values = json_array(value)?;
+            if values.len() != *len {
+                bail!("expected an array of length {len}, found {value}");
+            }
+            let elems = std::iter::repeat((&**elem_type, components.first()));
+            Token::Array(json_to_tokens(elems, type_lookup, values)?)
+        }
+        ParamType::Vector(elem_type) => {
+            let elem_application = pkg::abi::vec_elem_application(application);
+            let elems = std::iter::repeat((&**elem_type, elem_application));
+            Token::Vector(json_to_tokens(elems, type_lookup, json_array(value)?)?)
+        }
+        ParamType::Tuple(elem_types) => {
+            let values = json_array(value)?;
+            if values.len() != elem_types.len() {
+                bail!("expected {} values, found {value}", elem_types.len());
+            }
+            let elems = elem_types
+                .iter()
+                .enumerate()
+                .map(|(ix, elem_type)| (elem_type, components.get(ix)));
+            Token::Tuple(json_to_tokens(elems, type_lookup, values)?)
+        }
+        ParamType::Struct { fields, .. } => {
+            let values = match value {
+                Value::Object(map) if components.len() == fields.len() => components
+                    .iter()
+                    .map(|component| {
+                        map.get(&component.name).cloned().ok_or_else(|| {
+                            anyhow!("missing field `{}` of `{type_name}`", component.name)
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+                Value::Array(values) if values.len() == fields.len() => values.clone(),
+                _ => bail!(
+                    "expected the {} fields of `{type_name}`, found {value}",
+                    fields.len()
+                ),
+            };
+            let elems = fields
+                .iter()
+                .enumerate()
+                .map(|(ix, (_, field_type))| (field_type, components.get(ix)));
+            Token::Struct(json_to_tokens(elems, type_lookup, &values)?)
+        }
+        ParamType::Enum { variants, .. } => {
+            let (variant_name, value) = match value {
+                Value::String(variant_name) => (variant_name, &Value::Null),
+                Value::Object(map) if map.len() == 1 => map.iter().next().expect("one entry"),
+                _ => bail!("expected a variant of `{type_name}`, found {value}"),
+            };
+            let discriminant = components
+                .iter()
+                .position(|variant| variant.name == *variant_name)
+                .filter(|discriminant| *discriminant < variants.variants().len())
+                .ok_or_else(|| anyhow!("`{type_name}` has no variant `{variant_name}`"))?;
+            let (_, variant_type) = &variants.variants()[discriminant];
+            let variant = components.get(discriminant);
+            let token = json_to_token(variant_type, variant, type_lookup, value)
+                .with_context(|| format!("invalid value for `{type_name}::{variant_name}`"))?;
+            Token::Enum(Box::new((discriminant as u8, token, variants.clone())))
+        }
+    };
+    Ok(token)
+}
+
+/// Convert a sequence of JSON values, given the type and ABI type application of each.
+fn json_to_tokens<'a>(
+    elems: impl Iterator<Item = (&'a ParamType, Option<&'a TypeApplication>)>,
+    type_lookup: &HashMap<usize, TypeDeclaration>,
+    values: &[Value],
+) -> Result<Vec<Token>> {
+    elems
+        .zip(values)
+        .map(|((elem_type, application), value)| {
+            json_to_token(elem_type, application, type_lookup, value)
+        })
+        .collect()
+}
+
+fn json_uint<T: TryFrom<u64>>(value: &Value, type_name: &str) -> Result<T> {
+    let n: u64 = match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| anyhow!("expected an unsigned integer, found {value}"))?;
+    T::try_from(n).map_err(|_| anyhow!("{n} is out of range for `{type_name}`"))
+}
+
+fn json_str(value: &Value) -> Result<&str> {
+    value
+        .as_str()
+        .ok_or_else(|| anyhow!("expected a string, found {value}"))
+}
+
+fn json_array(value: &Value) -> Result<&Vec<Value>> {
+    value
+        .as_array()
+        .ok_or_else(|| anyhow!("expected an array, found {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AbiFunction;
+    use fuel_tx::{ContractId, Receipt};
+    use fuels_core::Token;
+    use fuels_types::{ABIFunction, ProgramABI, TypeApplication, TypeDeclaration};
+
+    const UNIT: usize = 0;
+    const U64: usize = 1;
+    const B256: usize = 2;
+    const POINT: usize = 3;
+    const DIRECTION: usize = 4;
+
+    fn type_application(name: &str, type_id: usize) -> TypeApplication {
+        TypeApplication {
+            name: name.to_string(),
+            type_id,
+            type_arguments: None,
+        }
+    }
+
+    fn type_declaration(
+        type_id: usize,
+        type_field: &str,
+        components: Option<Vec<TypeApplication>>,
+    ) -> TypeDeclaration {
+        TypeDeclaration {
+            type_id,
+            type_field: type_field.to_string(),
+            components,
+            type_parameters: None,
+        }
+    }
+
+    /// The ABI of a program declaring:
+    ///
+    /// - `fn add(a: u64, b: u64) -> u64`
+    /// - `fn move_point(p: Point, d: Direction) -> ()`
+    /// - `fn owner(id: b256) -> ()`
+    ///
+    /// where `Point { x: u64, y: u64 }` and `Direction { Left: (), Right: (), By: u64 }`.
+    fn program_abi() -> ProgramABI {
+        let types = vec![
+            type_declaration(UNIT, "()", None),
+            type_declaration(U64, "u64", None),
+            type_declaration(B256, "b256", None),
+            type_declaration(
+                POINT,
+                "struct Point",
+                Some(vec![type_application("x", U64), type_application("y", U64)]),
+            ),
+            type_declaration(
+                DIRECTION,
+                "enum Direction",
+                Some(vec![
+                    type_application("Left", UNIT),
+                    type_application("Right", UNIT),
+                    type_application("By", U64),
+                ]),
+            ),
+        ];
+        let function = |name: &str, inputs: Vec<TypeApplication>, output: usize| ABIFunction {
+            name: name.to_string(),
+            inputs,
+            output: type_application("", output),
+        };
+        let functions = vec![
+            function(
+                "add",
+                vec![type_application("a", U64), type_application("b", U64)],
+                U64,
+            ),
+            function(
+                "move_point",
+                vec![
+                    type_application("p", POINT),
+                    type_application("d", DIRECTION),
+                ],
+                UNIT,
+            ),
+            function("owner", vec![type_application("id", B256)], UNIT),
+        ];
+        ProgramABI {
+            types,
+            functions,
+            logged_types: None,
+            messages_types: None,
+        }
+    }
+
+    fn abi_function(name: &str) -> AbiFunction {
+        AbiFunction::from_abi(&program_abi(), name).unwrap()
+    }
+
+    #[test]
+    fn unknown_function_is_rejected() {
+        assert!(AbiFunction::from_abi(&program_abi(), "sub").is_err());
+    }
+
+    #[test]
+    fn json_args_by_name_or_position() {
+        let add = abi_function("add");
+        let expected = vec![Token::U64(1), Token::U64(2)];
+        assert_eq!(add.tokenize_json(r#"{"b": 2, "a": 1}"#).unwrap(), expected);
+        assert_eq!(add.tokenize_json("[1, 2]").unwrap(), expected);
+        assert_eq!(add.tokenize_json(r#"["1", "2"]"#).unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_json_args_are_rejected() {
+        let add = abi_function("add");
+        // Missing, unknown and surplus arguments.
+        assert!(add.tokenize_json(r#"{"a": 1}"#).is_err());
+        assert!(add.tokenize_json(r#"{"a": 1, "b": 2, "c": 3}"#).is_err());
+        assert!(add.tokenize_json("[1, 2, 3]").is_err());
+        // Values of the wrong type.
+        assert!(add.tokenize_json(r#"[1, true]"#).is_err());
+        assert!(add.tokenize_json("[1, -2]").is_err());
+        assert!(add.tokenize_json("1").is_err());
+    }
+
+    #[test]
+    fn structs_and_enums() {
+        let move_point = abi_function("move_point");
+        let point = Token::Struct(vec![Token::U64(3), Token::U64(4)]);
+        let tokens = move_point
+            .tokenize_json(r#"{"p": {"y": 4, "x": 3}, "d": "Right"}"#)
+            .unwrap();
+        assert_eq!(tokens[0], point);
+        assert!(matches!(&tokens[1], Token::Enum(e) if e.0 == 1 && e.1 == Token::Unit));
+
+        let tokens = move_point.tokenize_json(r#"[[3, 4], {"By": 7}]"#).unwrap();
+        assert_eq!(tokens[0], point);
+        assert!(matches!(&tokens[1], Token::Enum(e) if e.0 == 2 && e.1 == Token::U64(7)));
+
+        assert!(move_point.tokenize_json(r#"[{"x": 3}, "Left"]"#).is_err());
+        assert!(move_point.tokenize_json(r#"[[3, 4], "Up"]"#).is_err());
+    }
+
+    #[test]
+    fn literal_args_need_not_quote_strings() {
+        let owner = abi_function("owner");
+        let id = format!("0x{}", "ab".repeat(32));
+        let tokens = owner.tokenize_literals(&[id]).unwrap();
+        assert_eq!(tokens, vec![Token::B256([0xab; 32])]);
+        assert!(owner.tokenize_literals(&["0xabcd".to_string()]).is_err());
+
+        let add = abi_function("add");
+        let tokens = add
+            .tokenize_literals(&["1".to_string(), "2".to_string()])
+            .unwrap();
+        assert_eq!(tokens, vec![Token::U64(1), Token::U64(2)]);
+    }
+
+    #[test]
+    fn args_by_reference() {
+        assert!(abi_function("add").takes_args_by_reference());
+        assert!(abi_function("move_point").takes_args_by_reference());
+        assert!(abi_function("owner").takes_args_by_reference());
+    }
+
+    #[test]
+    fn decode_return_of_the_given_contract() {
+        let add = abi_function("add");
+        let id = ContractId::from([1; 32]);
+        let other_id = ContractId::from([2; 32]);
+        let receipts = vec![Receipt::ret(other_id, 5, 0, 0), Receipt::ret(id, 3, 0, 0)];
+        let ret = add.decode_return(&receipts, &id).unwrap();
+        assert_eq!(ret, Some(Token::U64(3)));
+        let ret = add.decode_return(&receipts[..1], &id).unwrap();
+        assert_eq!(ret, None);
+    }
+}
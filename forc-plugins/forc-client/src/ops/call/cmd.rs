@@ -0,0 +1,65 @@
+use clap::Parser;
+use fuel_gql_client::fuel_crypto::SecretKey;
+use fuel_tx::ContractId;
+use std::path::PathBuf;
+
+/// Call a method of a deployed contract.
+/// Crafts a script transaction that calls the method with the given arguments, then sends it to a
+/// running node.
+#[derive(Debug, Default, Parser)]
+#[clap(bin_name = "forc call", version)]
+pub struct CallCommand {
+    /// The ID of the contract to call.
+    pub contract_id: ContractId,
+
+    /// The name of the ABI method to call.
+    pub method: String,
+
+    /// The arguments to the method, one for each parameter in order, encoded using the types
+    /// described by the contract's ABI.
+    ///
+    /// Each argument is given as a JSON value, or otherwise as an unquoted string, e.g. `10` or
+    /// `0x...`. Structs are given as objects keyed by field name, enums as an object with a single
+    /// key naming the variant, e.g. `{"Some": 10}`, and tuples, arrays and vectors as arrays.
+    pub args: Vec<String>,
+
+    /// Path to the JSON ABI of the contract.
+    #[clap(long)]
+    pub abi: PathBuf,
+
+    /// Submit the transaction so that the call is included in a block and any changes that it
+    /// makes to the contract's state persist.
+    ///
+    /// By default, the call is only executed by the node as a dry run.
+    #[clap(long)]
+    pub submit: bool,
+
+    /// 32-byte ID of an additional contract that will be called during the transaction, e.g. by
+    /// the called method.
+    #[clap(long = "contract")]
+    pub contracts: Vec<ContractId>,
+
+    /// The URL of the node to send the transaction to. Defaults to a node running locally.
+    #[clap(long, short, env = "FUEL_NODE_URL")]
+    pub url: Option<String>,
+
+    /// Pretty-print the outputs from the node.
+    #[clap(long = "pretty-print", short = 'r')]
+    pub pretty_print: bool,
+
+    /// Set the transaction gas limit. Defaults to the maximum gas limit.
+    #[clap(long)]
+    pub gas_limit: Option<u64>,
+
+    /// Set the transaction gas price. Defaults to 0.
+    #[clap(long)]
+    pub gas_price: Option<u64>,
+
+    /// Do not sign the transaction. A transaction that is not submitted is never signed.
+    #[clap(long)]
+    pub unsigned: bool,
+
+    /// Set the key to be used for signing.
+    #[clap(long)]
+    pub signing_key: Option<SecretKey>,
+}
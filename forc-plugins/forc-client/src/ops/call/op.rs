@@ -0,0 +1,231 @@
+use anyhow::{anyhow, Context, Result};
+use forc_pkg as pkg;
+use fuel_gql_client::{
+    client::FuelClient,
+    fuel_vm::{
+        consts::{REG_CGAS, REG_ONE, REG_ZERO},
+        prelude::{Immediate18, Opcode},
+    },
+};
+use fuel_tx::{ContractId, Receipt, TransactionBuilder};
+use fuels_core::{abi_encoder::ABIEncoder, Token};
+use fuels_types::{constants::WORD_SIZE, ProgramABI};
+use std::fs;
+use sway_utils::constants::DEFAULT_NODE_URL;
+use tracing::info;
+
+use crate::ops::abi::AbiFunction;
+use crate::ops::run::op::try_send_tx;
+use crate::ops::tx_util::{TransactionBuilderExt, TxParameters};
+
+use super::cmd::CallCommand;
+
+pub struct CalledContract {
+    pub receipts: Vec<Receipt>,
+}
+
+/// Calls a method of a deployed contract with the arguments given on the command line.
+///
+/// Upon success, returns the receipts of the transaction that made the call.
+pub async fn call(command: CallCommand) -> Result<CalledContract> {
+    let abi_str = fs::read_to_string(&command.abi)
+        .with_context(|| format!("failed to read ABI from {}", command.abi.display()))?;
+    let program_abi: ProgramABI = serde_json::from_str(&abi_str)
+        .map_err(|e| anyhow!("failed to parse ABI {}: {}", command.abi.display(), e))?;
+    let method = AbiFunction::from_abi(&program_abi, &command.method)?;
+    let args = method.tokenize_literals(&command.args)?;
+    let (script, script_data) = call_script(&method, command.contract_id, &args)?;
+
+    let node_url = command.url.as_deref().unwrap_or(DEFAULT_NODE_URL);
+    let client = FuelClient::new(node_url)?;
+    let contract_ids = std::iter::once(command.contract_id)
+        .chain(command.contracts.iter().copied())
+        .collect();
+    // A dry run does not require a signature, so the user is only prompted to sign the
+    // transaction when it is submitted.
+    let unsigned = command.unsigned || !command.submit;
+    let tx = TransactionBuilder::script(script, script_data)
+        .params(TxParameters::new(command.gas_limit, command.gas_price))
+        .add_contracts(contract_ids)
+        .finalize_signed(client, unsigned, command.signing_key)
+        .await?;
+    let receipts = try_send_tx(node_url, &tx.into(), command.pretty_print, !command.submit).await?;
+
+    for log in pkg::logs::decode_logs(&receipts, &program_abi, &command.contract_id) {
+        info!("{}", log);
+    }
+    if let Some(token) = method.decode_return(&receipts, &command.contract_id)? {
+        info!("Return value: {}", token);
+    }
+    Ok(CalledContract { receipts })
+}
+
+/// Create the bytecode and script data of a script that calls the given method of the contract
+/// with the given arguments.
+///
+/// The script data holds the call frame parameters read by the `CALL` instruction: the ID of the
+/// asset to forward (none is forwarded, so this is zeroed), followed by the contract ID, the
+/// method's selector, and either its single argument or a pointer to the encoded arguments.
+fn call_script(
+    method: &AbiFunction,
+    contract_id: ContractId,
+    args: &[Token],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let instructions = |asset_id_addr: u64| -> Vec<u8> {
+        let call_params_addr = asset_id_addr + ContractId::LEN as u64;
+        vec![
+            Opcode::MOVI(0x10, call_params_addr as Immediate18),
+            Opcode::MOVI(0x11, asset_id_addr as Immediate18),
+            Opcode::CALL(0x10, REG_ZERO, 0x11, REG_CGAS),
+            Opcode::RET(REG_ONE),
+        ]
+        .into_iter()
+        .collect()
+    };
+    // The length of the script does not depend on the addresses that it refers to.
+    let script_data_addr = pkg::abi::script_data_addr(&instructions(0));
+    let script = instructions(script_data_addr);
+
+    let mut script_data = vec![0u8; ContractId::LEN];
+    script_data.extend_from_slice(contract_id.as_ref());
+    script_data.extend_from_slice(&method.selector());
+    let encoded = ABIEncoder::encode(args)
+        .map_err(|e| anyhow!("failed to encode the arguments of the call: {}", e))?;
+    if method.takes_args_by_reference() {
+        let args_addr = script_data_addr + (script_data.len() + WORD_SIZE) as u64;
+        script_data.extend_from_slice(&args_addr.to_be_bytes());
+        script_data.extend(encoded.resolve(args_addr));
+    } else {
+        // No argument, or a single argument that fits within a word, is passed by value.
+        let mut arg = encoded.resolve(0);
+        arg.resize(WORD_SIZE, 0);
+        script_data.extend(arg);
+    }
+    Ok((script, script_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::call_script;
+    use crate::ops::abi::AbiFunction;
+    use crate::ops::tx_util::TransactionBuilderExt;
+    use forc_pkg::{self as pkg, test_util};
+    use fuel_gql_client::fuel_vm::{interpreter::Interpreter, storage::MemoryStorage};
+    use fuel_tx::{
+        ConsensusParameters, Contract, ContractId, IntoChecked, Output, Salt, TransactionBuilder,
+    };
+    use fuels_core::Token;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::{fs, path::Path};
+    use sway_utils::constants;
+
+    const CALLEE_SRC: &str = r#"contract;
+
+struct Pair {
+    a: u64,
+    b: u64,
+}
+
+abi Callee {
+    fn answer() -> u64;
+    fn double(x: u64) -> u64;
+    fn sum(pair: Pair) -> u64;
+}
+
+impl Callee for Contract {
+    fn answer() -> u64 {
+        42
+    }
+
+    fn double(x: u64) -> u64 {
+        x * 2
+    }
+
+    fn sum(pair: Pair) -> u64 {
+        pair.a + pair.b
+    }
+}
+"#;
+
+    /// Build the callee contract and deploy it to a new in-memory storage, as `forc test` deploys
+    /// the contracts that its tests call.
+    fn deploy_callee(dir: &Path) -> (pkg::BuiltPackage, ContractId, MemoryStorage) {
+        let deps = format!("[dependencies]\n{}", test_util::core_dep());
+        test_util::write_contract(dir, "callee", &deps);
+        fs::write(dir.join(constants::SRC_DIR).join("main.sw"), CALLEE_SRC).unwrap();
+        let opts = pkg::BuildOpts {
+            pkg: pkg::PkgOpts {
+                path: Some(dir.display().to_string()),
+                offline: true,
+                ..Default::default()
+            },
+            no_cache: true,
+            ..Default::default()
+        };
+        let built = match pkg::build_with_options(opts).unwrap() {
+            pkg::Built::Package(built) => *built,
+            pkg::Built::Workspace(_) => unreachable!("the callee is a package"),
+        };
+
+        let salt = Salt::zeroed();
+        let contract_id = pkg::contract_id(&built, &salt);
+        let state_root = Contract::initial_state_root(built.storage_slots.iter());
+        let mut rng = StdRng::seed_from_u64(0);
+        let params = ConsensusParameters::default();
+        let tx = TransactionBuilder::create(
+            built.bytecode.clone().into(),
+            salt,
+            built.storage_slots.clone(),
+        )
+        .add_unsigned_coin_input(rng.gen(), rng.gen(), 1, Default::default(), rng.gen(), 0)
+        .add_output(Output::contract_created(contract_id, state_root))
+        .finalize()
+        .into_checked(0, &params)
+        .unwrap();
+        let mut interpreter = Interpreter::with_storage(MemoryStorage::default(), params);
+        interpreter.transact(tx).unwrap();
+        let storage = interpreter.as_ref().clone();
+        (built, contract_id, storage)
+    }
+
+    /// Call the given method of a freshly deployed callee with the script created by
+    /// `call_script`, returning the decoded return value.
+    fn call(method: &str, args: &[&str]) -> Token {
+        let dir = test_util::temp_dir();
+        let (built, contract_id, storage) =
+            test_util::with_forc_home(|_| deploy_callee(dir.path()));
+        let method = AbiFunction::from_abi(&built.json_abi_program, method).unwrap();
+        let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+        let args = method.tokenize_literals(&args).unwrap();
+        let (script, script_data) = call_script(&method, contract_id, &args).unwrap();
+
+        let params = ConsensusParameters::default();
+        let tx = TransactionBuilder::script(script, script_data)
+            .gas_limit(params.max_gas_per_tx)
+            .add_contract(contract_id)
+            .finalize()
+            .into_checked(0, &params)
+            .unwrap();
+        let mut interpreter = Interpreter::with_storage(storage, params);
+        let receipts = interpreter.transact(tx).unwrap().receipts().to_vec();
+        method
+            .decode_return(&receipts, &contract_id)
+            .unwrap()
+            .expect("the called method returns a value")
+    }
+
+    #[test]
+    fn test_call_without_args() {
+        assert_eq!(call("answer", &[]), Token::U64(42));
+    }
+
+    #[test]
+    fn test_call_with_arg_by_value() {
+        assert_eq!(call("double", &["21"]), Token::U64(42));
+    }
+
+    #[test]
+    fn test_call_with_args_by_reference() {
+        assert_eq!(call("sum", &[r#"{"a": 40, "b": 2}"#]), Token::U64(42));
+    }
+}
@@ -1,5 +1,6 @@
 use clap::Parser;
-use fuel_gql_client::fuel_crypto::SecretKey;
+use fuel_gql_client::{fuel_crypto::SecretKey, fuel_tx::Salt};
+use std::path::PathBuf;
 
 #[derive(Debug, Default, Parser)]
 #[clap(bin_name = "forc deploy", version)]
@@ -82,6 +83,34 @@ pub struct DeployCommand {
     /// Set the transaction gas price. Defaults to 0.
     #[clap(long)]
     pub gas_price: Option<u64>,
+    /// The salt with which to deploy the contract, altering its contract ID. Defaults to zero.
+    ///
+    /// Contract dependencies are always deployed with the salt declared by their dependents.
+    #[clap(long, conflicts_with = "random-salt")]
+    pub salt: Option<Salt>,
+    /// Deploy the contract with a randomly generated salt, so that it is assigned a new contract
+    /// ID each time that it is deployed.
+    #[clap(long)]
+    pub random_salt: bool,
+    /// Path to a JSON file of storage slots in the format of `<name>-storage_slots.json`, used to
+    /// override the initial storage of the contract.
+    ///
+    /// Each slot replaces the compiled slot with the same key, or is added to the contract's
+    /// initial storage if there is no such slot. Contract dependencies are always deployed with
+    /// their compiled storage.
+    #[clap(long)]
+    pub storage_slots: Option<PathBuf>,
+    /// Print the ID of the contract, computed from its bytecode, salt and initial storage,
+    /// without contacting a node to deploy it.
+    #[clap(long)]
+    pub print_contract_id: bool,
+    /// Export the transaction to the given file instead of signing and submitting it, so that it
+    /// may be signed elsewhere and submitted with `forc submit`.
+    ///
+    /// The file holds the transaction's bytes along with the transaction ID to be signed. The
+    /// transaction is funded from the address of the wallet that will sign it, which is prompted.
+    #[clap(long, value_name = "FILE", conflicts_with_all = &["unsigned", "signing-key", "print-contract-id"])]
+    pub export_tx: Option<PathBuf>,
     /// Set the key to be used for signing.
     pub signing_key: Option<SecretKey>,
 }
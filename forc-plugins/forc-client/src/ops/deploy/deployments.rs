@@ -0,0 +1,132 @@
+//! The record of the contracts deployed by `forc deploy`.
+//!
+//! Upon deploying, `forc deploy` writes a `deployments.json` to the directory of the package or
+//! workspace, recording the most recent deployment of each contract by package name.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The name of the file to which deployments are recorded.
+pub const DEPLOYMENTS_FILE_NAME: &str = "deployments.json";
+
+/// The most recent deployment of each contract, keyed by package name.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Deployments {
+    pub contracts: BTreeMap<String, Deployment>,
+}
+
+/// The deployment of a single contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Deployment {
+    /// The URL of the node to which the contract was deployed.
+    pub network_url: String,
+    pub contract_id: String,
+    pub salt: String,
+    /// The ID of the transaction that deployed the contract, if known.
+    ///
+    /// This is unknown in the case that the contract was found to be deployed already and was
+    /// not previously recorded.
+    pub tx_id: Option<String>,
+    /// The hex-encoded SHA-256 hash of the contract's bytecode.
+    pub bytecode_hash: String,
+}
+
+impl Deployments {
+    /// Read the deployments recorded at the given path, or an empty record if there is no file.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read deployments from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("failed to parse deployments {}: {}", path.display(), e))
+    }
+
+    /// Record the given deployment of the contract named `name`, replacing any previous record.
+    ///
+    /// A deployment that was skipped as the contract was already deployed keeps the ID of the
+    /// recorded transaction that deployed the same contract to the same network.
+    pub fn record(&mut self, name: &str, mut deployment: Deployment) {
+        if let Some(recorded) = self.contracts.get(name) {
+            if deployment.tx_id.is_none()
+                && recorded.contract_id == deployment.contract_id
+                && recorded.network_url == deployment.network_url
+            {
+                deployment.tx_id = recorded.tx_id.clone();
+            }
+        }
+        self.contracts.insert(name.to_string(), deployment);
+    }
+
+    /// Write the deployments to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(&file, self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Deployment, Deployments};
+    use forc_pkg::test_util;
+
+    fn deployment(network_url: &str, contract_id: &str, tx_id: Option<&str>) -> Deployment {
+        Deployment {
+            network_url: network_url.to_string(),
+            contract_id: contract_id.to_string(),
+            salt: "0x00".to_string(),
+            tx_id: tx_id.map(str::to_string),
+            bytecode_hash: "00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_record_keeps_tx_of_skipped_deployment() {
+        let mut deployments = Deployments::default();
+        deployments.record("foo", deployment("a", "0x01", Some("0x0a")));
+        deployments.record("foo", deployment("a", "0x01", None));
+        assert_eq!(
+            deployments.contracts["foo"],
+            deployment("a", "0x01", Some("0x0a"))
+        );
+    }
+
+    #[test]
+    fn test_record_replaces_other_deployments() {
+        let mut deployments = Deployments::default();
+        deployments.record("foo", deployment("a", "0x01", Some("0x0a")));
+        deployments.record("bar", deployment("a", "0x02", Some("0x0b")));
+
+        // A different contract, or the same contract on another network, is recorded as is.
+        deployments.record("foo", deployment("a", "0x03", None));
+        assert_eq!(deployments.contracts["foo"], deployment("a", "0x03", None));
+        deployments.record("bar", deployment("b", "0x02", None));
+        assert_eq!(deployments.contracts["bar"], deployment("b", "0x02", None));
+
+        // A new deployment replaces the transaction of a recorded one.
+        deployments.record("bar", deployment("b", "0x02", Some("0x0c")));
+        assert_eq!(
+            deployments.contracts["bar"],
+            deployment("b", "0x02", Some("0x0c"))
+        );
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = test_util::temp_dir();
+        let path = dir.path().join(super::DEPLOYMENTS_FILE_NAME);
+        assert!(Deployments::from_path(&path).unwrap().contracts.is_empty());
+
+        let mut deployments = Deployments::default();
+        deployments.record("foo", deployment("a", "0x01", Some("0x0a")));
+        deployments.record("bar", deployment("a", "0x02", None));
+        deployments.write(&path).unwrap();
+        let read = Deployments::from_path(&path).unwrap();
+        assert_eq!(read.contracts, deployments.contracts);
+    }
+}
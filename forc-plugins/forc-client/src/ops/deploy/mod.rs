@@ -1,2 +1,3 @@
 pub mod cmd;
+pub mod deployments;
 pub mod op;
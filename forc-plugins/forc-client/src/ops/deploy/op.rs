@@ -1,5 +1,5 @@
-use anyhow::{bail, Context, Result};
-use forc_pkg::{self as pkg, PackageManifestFile};
+use anyhow::{anyhow, bail, Context, Result};
+use forc_pkg::{self as pkg, fuel_core_not_running, manifest::ManifestFile, PackageManifestFile};
 use fuel_gql_client::client::types::TransactionStatus;
 use fuel_gql_client::{
     client::FuelClient,
@@ -8,54 +8,139 @@ use fuel_gql_client::{
 };
 use futures::FutureExt;
 use pkg::BuiltPackage;
-use std::path::PathBuf;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
-use sway_core::language::parsed::TreeType;
 use sway_utils::constants::DEFAULT_NODE_URL;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::ops::pkg_util::built_pkgs_with_manifest;
-use crate::ops::tx_util::{TransactionBuilderExt, TxParameters, TX_SUBMIT_TIMEOUT_MS};
+use crate::ops::pkg_util::built_contracts_in_deployment_order;
+use crate::ops::tx_util::{export_tx, TransactionBuilderExt, TxParameters, TX_SUBMIT_TIMEOUT_MS};
 
 use super::cmd::DeployCommand;
+use super::deployments::{Deployment, Deployments, DEPLOYMENTS_FILE_NAME};
 
 pub struct DeployedContract {
     pub id: fuel_tx::ContractId,
+    /// The record of the contract's deployment, or `None` if its ID was only computed.
+    pub deployment: Option<Deployment>,
 }
 
 /// Builds and deploys contract(s). If the given path corresponds to a workspace, all deployable members
 /// will be built and deployed.
 ///
-/// Upon success, returns the ID of each deployed contract in order of deployment.
+/// The contract dependencies of the deployed packages are deployed first, in the order in which
+/// they are compiled, and contracts that are already deployed are skipped. Each deployment is
+/// recorded within the `deployments.json` of the package or workspace.
 ///
-/// When deploying a single contract, only that contract's ID is returned.
+/// Upon success, returns the ID of each deployed contract in order of deployment.
 pub async fn deploy(command: DeployCommand) -> Result<Vec<DeployedContract>> {
-    let mut contract_ids = Vec::new();
+    let mut deployed_contracts = Vec::new();
     let curr_dir = if let Some(ref path) = command.path {
         PathBuf::from(path)
     } else {
         std::env::current_dir()?
     };
     let build_opts = build_opts_from_cmd(&command);
-    let built_pkgs_with_manifest = built_pkgs_with_manifest(&curr_dir, build_opts)?;
-    for (member_manifest, built_pkg) in built_pkgs_with_manifest {
-        if member_manifest
-            .check_program_type(vec![TreeType::Contract])
-            .is_ok()
-        {
-            let contract_id = deploy_pkg(&command, &member_manifest, &built_pkg).await?;
-            contract_ids.push(contract_id);
+    let contracts = built_contracts_in_deployment_order(&curr_dir, build_opts)?;
+    let deployments_path = ManifestFile::from_dir(&curr_dir)?
+        .dir()
+        .join(DEPLOYMENTS_FILE_NAME);
+    let mut deployments = Deployments::from_path(&deployments_path)?;
+    for contract in &contracts {
+        let deployed_contract = deploy_pkg(
+            &command,
+            &contract.manifest,
+            &contract.built,
+            contract.dep_salt,
+        )
+        .await?;
+        // Record each deployment as it completes, so that none are lost if a later one fails.
+        if let Some(deployment) = deployed_contract.deployment.clone() {
+            deployments.record(&contract.manifest.project.name, deployment);
+            deployments.write(&deployments_path)?;
+        }
+        let exported = deployed_contract.deployment.is_none() && !command.print_contract_id;
+        deployed_contracts.push(deployed_contract);
+        // Contracts that follow may depend on the exported contract, and so may only be deployed
+        // once its transaction is submitted.
+        if exported && deployed_contracts.len() < contracts.len() {
+            let remaining: Vec<_> = contracts[deployed_contracts.len()..]
+                .iter()
+                .map(|contract| contract.manifest.project.name.as_str())
+                .collect();
+            warn!(
+                "Not deploying {} until the exported transaction is submitted",
+                remaining.join(", ")
+            );
+            break;
         }
     }
-    Ok(contract_ids)
+    if deployed_contracts
+        .iter()
+        .any(|deployed_contract| deployed_contract.deployment.is_some())
+    {
+        info!("Recorded deployments in {}", deployments_path.display());
+    }
+    Ok(deployed_contracts)
 }
 
-/// Deploy a single pkg given deploy command and the manifest file
+/// Deploy a single pkg given deploy command and the manifest file.
+///
+/// A contract dependency of another package is deployed with the salt declared by its dependents,
+/// given as `dep_salt`, and with its compiled storage so that its ID matches the ID with which
+/// its dependents were compiled.
 pub async fn deploy_pkg(
     command: &DeployCommand,
     manifest: &PackageManifestFile,
     compiled: &BuiltPackage,
+    dep_salt: Option<Salt>,
 ) -> Result<DeployedContract> {
+    if dep_salt.is_some() {
+        let ignored: Vec<_> = [
+            (command.salt.is_some(), "--salt"),
+            (command.random_salt, "--random-salt"),
+            (command.storage_slots.is_some(), "--storage-slots"),
+        ]
+        .into_iter()
+        .filter_map(|(given, flag)| given.then_some(flag))
+        .collect();
+        if !ignored.is_empty() {
+            warn!(
+                "Ignoring {} for `{}`, as it is deployed with the salt and storage declared by \
+                its dependents",
+                ignored.join(", "),
+                manifest.project.name
+            );
+        }
+    }
+    let bytecode = compiled.bytecode.clone().into();
+    let salt = match (dep_salt, command.salt, command.random_salt) {
+        (Some(salt), _, _) | (None, Some(salt), _) => salt,
+        (None, None, true) => rand::thread_rng().gen(),
+        (None, None, false) => Salt::new([0; 32]),
+    };
+    let mut storage_slots = match (&command.storage_slots, dep_salt) {
+        (Some(path), None) => override_storage_slots(&compiled.storage_slots, path)?,
+        _ => compiled.storage_slots.clone(),
+    };
+    storage_slots.sort();
+
+    let state_root = Contract::initial_state_root(storage_slots.iter());
+    let contract_id = pkg::contract_id_with_storage(&compiled.bytecode, &storage_slots, &salt);
+    info!("Contract id: 0x{}", hex::encode(contract_id));
+    if command.random_salt && dep_salt.is_none() {
+        info!("Salt: 0x{}", hex::encode(salt));
+    }
+    if command.print_contract_id {
+        return Ok(DeployedContract {
+            id: contract_id,
+            deployment: None,
+        });
+    }
+
     let node_url = match &manifest.network {
         Some(network) => &network.url,
         _ => DEFAULT_NODE_URL,
@@ -63,25 +148,55 @@ pub async fn deploy_pkg(
 
     let node_url = command.url.as_deref().unwrap_or(node_url);
     let client = FuelClient::new(node_url)?;
+    if client.health().await.is_err() {
+        return Err(fuel_core_not_running(node_url));
+    }
 
-    let bytecode = compiled.bytecode.clone().into();
-    let salt = Salt::new([0; 32]);
-    let mut storage_slots = compiled.storage_slots.clone();
-    storage_slots.sort();
+    let deployment = |tx_id: Option<Bytes32>| Deployment {
+        network_url: node_url.to_string(),
+        contract_id: format!("0x{}", hex::encode(contract_id)),
+        salt: format!("0x{}", hex::encode(salt)),
+        tx_id: tx_id.map(|tx_id| format!("0x{}", hex::encode(tx_id))),
+        bytecode_hash: format!("{:x}", Hasher::hash(&compiled.bytecode)),
+    };
 
-    let contract = Contract::from(compiled.bytecode.clone());
-    let root = contract.root();
-    let state_root = Contract::initial_state_root(storage_slots.iter());
-    let contract_id = contract.id(&salt, &root, &state_root);
-    info!("Contract id: 0x{}", hex::encode(contract_id));
+    // Skip contracts that are already deployed.
+    let deployed = client
+        .contract(&format!("0x{}", hex::encode(contract_id)))
+        .await?;
+    if let Some(deployed) = deployed {
+        if deployed.bytecode.0 .0 != compiled.bytecode {
+            bail!(
+                "contract {} is already deployed with different bytecode",
+                &contract_id
+            );
+        }
+        info!("contract {} is already deployed, skipping", &contract_id);
+        return Ok(DeployedContract {
+            id: contract_id,
+            deployment: Some(deployment(None)),
+        });
+    }
 
-    let tx = TransactionBuilder::create(bytecode, salt, storage_slots.clone())
+    let mut tx_builder = TransactionBuilder::create(bytecode, salt, storage_slots.clone());
+    tx_builder
         .params(TxParameters::new(command.gas_limit, command.gas_price))
-        .add_output(Output::contract_created(contract_id, state_root))
+        .add_output(Output::contract_created(contract_id, state_root));
+    if let Some(path) = &command.export_tx {
+        let (tx, signature_witness_index) =
+            tx_builder.finalize_funded(client.clone(), None).await?;
+        export_tx(path, tx.into(), signature_witness_index)?;
+        return Ok(DeployedContract {
+            id: contract_id,
+            deployment: None,
+        });
+    }
+    let tx = tx_builder
         .finalize_signed(client.clone(), command.unsigned, command.signing_key)
         .await?;
 
     let tx = Transaction::from(tx);
+    let tx_id = tx.id();
 
     let deployment_request = client.submit_and_await_commit(&tx).map(|res| match res {
         Ok(logs) => match logs {
@@ -115,7 +230,28 @@ pub async fn deploy_pkg(
             &contract_id
         )
     })??;
-    Ok(DeployedContract { id: contract_id })
+    Ok(DeployedContract {
+        id: contract_id,
+        deployment: Some(deployment(Some(tx_id))),
+    })
+}
+
+/// Apply the storage slots declared within the JSON file at the given path to the compiled storage
+/// slots of a contract, replacing any slots with the same keys.
+fn override_storage_slots(slots: &[StorageSlot], path: &Path) -> Result<Vec<StorageSlot>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read storage slots from {}", path.display()))?;
+    let overrides: Vec<StorageSlot> = serde_json::from_str(&contents)
+        .map_err(|e| anyhow!("failed to parse storage slots {}: {}", path.display(), e))?;
+    let slots: BTreeMap<Bytes32, Bytes32> = slots
+        .iter()
+        .chain(&overrides)
+        .map(|slot| (*slot.key(), *slot.value()))
+        .collect();
+    Ok(slots
+        .into_iter()
+        .map(|(key, value)| StorageSlot::new(key, value))
+        .collect())
 }
 
 fn build_opts_from_cmd(cmd: &DeployCommand) -> pkg::BuildOpts {
@@ -144,5 +280,38 @@ fn build_opts_from_cmd(cmd: &DeployCommand) -> pkg::BuildOpts {
         binary_outfile: cmd.binary_outfile.clone(),
         debug_outfile: cmd.debug_outfile.clone(),
         tests: false,
+        no_cache: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::override_storage_slots;
+    use forc_pkg::test_util;
+    use fuel_gql_client::fuel_vm::prelude::*;
+    use std::fs;
+
+    fn slot(key: u8, value: u8) -> StorageSlot {
+        StorageSlot::new(Bytes32::new([key; 32]), Bytes32::new([value; 32]))
+    }
+
+    #[test]
+    fn test_override_storage_slots() {
+        let dir = test_util::temp_dir();
+        let path = dir.path().join("storage_slots.json");
+        let overrides = vec![slot(3, 30), slot(1, 11)];
+        fs::write(&path, serde_json::to_string(&overrides).unwrap()).unwrap();
+        let compiled = vec![slot(2, 20), slot(1, 10)];
+        let slots = override_storage_slots(&compiled, &path).unwrap();
+        assert_eq!(slots, vec![slot(1, 11), slot(2, 20), slot(3, 30)]);
+    }
+
+    #[test]
+    fn test_override_storage_slots_invalid_file() {
+        let dir = test_util::temp_dir();
+        let path = dir.path().join("storage_slots.json");
+        assert!(override_storage_slots(&[], &path).is_err());
+        fs::write(&path, "{}").unwrap();
+        assert!(override_storage_slots(&[slot(1, 10)], &path).is_err());
     }
 }
@@ -1,4 +1,7 @@
+mod abi;
+pub mod call;
 pub mod deploy;
 pub mod pkg_util;
 pub mod run;
+pub mod submit;
 pub mod tx_util;
@@ -1,8 +1,11 @@
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use anyhow::Result;
 use forc_pkg::{self as pkg, manifest::ManifestFile, BuildOpts, BuildPlan};
+use fuel_tx::Salt;
 use pkg::{build_with_options, BuiltPackage, PackageManifestFile};
+use sway_core::language::parsed::TreeType;
 
 pub(crate) fn built_pkgs_with_manifest(
     path: &Path,
@@ -35,3 +38,192 @@ pub(crate) fn built_pkgs_with_manifest(
     }
     Ok(pkgs_with_manifest)
 }
+
+/// A built contract that is to be deployed.
+pub(crate) struct ContractToDeploy {
+    pub(crate) manifest: PackageManifestFile,
+    pub(crate) built: BuiltPackage,
+    /// The salt declared for the contract under the `[contract-dependencies]` of its dependents,
+    /// if it is a contract dependency of another package.
+    pub(crate) dep_salt: Option<Salt>,
+}
+
+/// Builds the package or workspace at the given path, producing the contracts to deploy in the
+/// order in which they must be deployed.
+///
+/// This includes every built member that is a contract along with the contract dependencies of
+/// every built member, following the compilation order of the build plan so that each contract
+/// is deployed after its own contract dependencies.
+pub(crate) fn built_contracts_in_deployment_order(
+    path: &Path,
+    build_opts: BuildOpts,
+) -> Result<Vec<ContractToDeploy>> {
+    let build_plan = BuildPlan::from_build_opts(&build_opts)?;
+    let graph = build_plan.graph();
+    let build_profiles: HashMap<String, pkg::BuildProfile> = build_plan.build_profiles().collect();
+    let (_, profile) = pkg::build_profile_from_opts(&build_profiles, &build_opts)?;
+    let no_cache = build_opts.no_cache;
+
+    let mut built_pkgs: HashMap<pkg::NodeIx, BuiltPackage> =
+        built_pkgs_with_manifest(path, build_opts)?
+            .into_iter()
+            .filter_map(|(manifest, built)| {
+                let member = build_plan
+                    .member_nodes()
+                    .find(|&member| graph[member].name == manifest.project.name)?;
+                Some((member, built))
+            })
+            .collect();
+
+    // Contract dependencies that are not themselves built members must be built separately.
+    let deps: HashSet<pkg::NodeIx> = built_pkgs
+        .keys()
+        .flat_map(|&member| build_plan.contract_deps(member))
+        .filter(|dep| !built_pkgs.contains_key(dep))
+        .collect();
+    if !deps.is_empty() {
+        // Only the members' build should print any of the requested compiler output.
+        let profile = pkg::BuildProfile {
+            print_ast: false,
+            print_dca_graph: false,
+            print_ir: false,
+            print_finalized_asm: false,
+            print_intermediate_asm: false,
+            time_phases: false,
+            include_tests: false,
+            ..profile
+        };
+        built_pkgs.extend(pkg::build(&build_plan, &profile, &deps, no_cache)?);
+    }
+
+    let mut contracts = Vec::new();
+    for node in build_plan.compilation_order() {
+        let built = match built_pkgs.remove(node) {
+            Some(built) => built,
+            None => continue,
+        };
+        let manifest = &build_plan.manifest_map()[&graph[*node].id()];
+        if manifest
+            .check_program_type(vec![TreeType::Contract])
+            .is_err()
+        {
+            continue;
+        }
+        let dep_salt = pkg::contract_dep_salt(graph, *node)?;
+        contracts.push(ContractToDeploy {
+            manifest: manifest.clone(),
+            built,
+            dep_salt,
+        });
+    }
+    Ok(contracts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::built_contracts_in_deployment_order;
+    use forc_pkg::{self as pkg, test_util};
+    use fuel_tx::Salt;
+    use std::{fs, path::Path, str::FromStr};
+    use sway_utils::constants;
+
+    const SALT_B: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    const SALT_C: &str = "0x0000000000000000000000000000000000000000000000000000000000000002";
+
+    fn write_workspace(root: &Path, members: &[&str]) {
+        let members: Vec<_> = members.iter().map(|member| format!("{member:?}")).collect();
+        let manifest = format!("[workspace]\nmembers = [{}]\n", members.join(", "));
+        fs::write(root.join(constants::MANIFEST_FILE_NAME), manifest).unwrap();
+    }
+
+    /// Write a contract named `name` with the given `[contract-dependencies]` to `root/name`.
+    fn write_contract(root: &Path, name: &str, contract_deps: &str) {
+        let tables = format!(
+            "[dependencies]\n{}\n[contract-dependencies]\n{contract_deps}",
+            test_util::core_dep()
+        );
+        test_util::write_contract(&root.join(name), name, &tables);
+    }
+
+    fn contracts_in_deployment_order(root: &Path) -> anyhow::Result<Vec<(String, Option<Salt>)>> {
+        let build_opts = pkg::BuildOpts {
+            pkg: pkg::PkgOpts {
+                path: Some(root.display().to_string()),
+                offline: true,
+                ..Default::default()
+            },
+            no_cache: true,
+            ..Default::default()
+        };
+        let contracts =
+            test_util::with_forc_home(|_| built_contracts_in_deployment_order(root, build_opts))?
+                .into_iter()
+                .map(|contract| (contract.manifest.project.name.clone(), contract.dep_salt))
+                .collect();
+        Ok(contracts)
+    }
+
+    #[test]
+    fn test_deployment_order() {
+        let dir = test_util::temp_dir();
+        let root = dir.path();
+        write_workspace(root, &["a", "b", "lib"]);
+        write_contract(
+            root,
+            "a",
+            &format!("b = {{ path = \"../b\", salt = \"{SALT_B}\" }}\n"),
+        );
+        write_contract(
+            root,
+            "b",
+            &format!("c = {{ path = \"../c\", salt = \"{SALT_C}\" }}\n"),
+        );
+        write_contract(root, "c", "");
+        let deps = format!("[dependencies]\n{}", test_util::core_dep());
+        test_util::write_lib(&root.join("lib"), "lib", &deps);
+
+        // Contract dependencies are deployed first, including those that aren't members, while
+        // libraries are skipped.
+        assert_eq!(
+            contracts_in_deployment_order(root).unwrap(),
+            vec![
+                ("c".to_string(), Some(Salt::from_str(SALT_C).unwrap())),
+                ("b".to_string(), Some(Salt::from_str(SALT_B).unwrap())),
+                ("a".to_string(), None),
+            ]
+        );
+
+        // Only the contracts required by the selected member are deployed, still with the salts
+        // declared throughout the workspace so that their IDs match those its dependents use.
+        assert_eq!(
+            contracts_in_deployment_order(&root.join("b")).unwrap(),
+            vec![
+                ("c".to_string(), Some(Salt::from_str(SALT_C).unwrap())),
+                ("b".to_string(), Some(Salt::from_str(SALT_B).unwrap())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deployment_with_conflicting_salts() {
+        let dir = test_util::temp_dir();
+        let root = dir.path();
+        write_workspace(root, &["a", "b"]);
+        write_contract(
+            root,
+            "a",
+            &format!("c = {{ path = \"../c\", salt = \"{SALT_B}\" }}\n"),
+        );
+        write_contract(
+            root,
+            "b",
+            &format!("c = {{ path = \"../c\", salt = \"{SALT_C}\" }}\n"),
+        );
+        write_contract(root, "c", "");
+
+        let err = contracts_in_deployment_order(root).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("conflicting salt declarations for contract dependency named: c"));
+    }
+}
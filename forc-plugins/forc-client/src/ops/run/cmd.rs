@@ -1,5 +1,6 @@
 use clap::Parser;
 use fuel_gql_client::fuel_crypto::SecretKey;
+use std::path::PathBuf;
 
 /// Run script project.
 /// Crafts a script transaction then sends it to a running node.
@@ -7,9 +8,26 @@ use fuel_gql_client::fuel_crypto::SecretKey;
 #[clap(bin_name = "forc run", version)]
 pub struct RunCommand {
     /// Hex string of data to input to script.
-    #[clap(short, long)]
+    #[clap(short, long, conflicts_with_all = &["args", "arg"])]
     pub data: Option<String>,
 
+    /// The arguments to the script's `main` function as JSON, encoded using the types described
+    /// by the script's ABI.
+    ///
+    /// Either an object keyed by parameter name, e.g. `{"amount": 10, "to": "0x..."}`, or an array
+    /// of values in the order of the parameters. Structs are given as objects keyed by field name,
+    /// enums as an object with a single key naming the variant, e.g. `{"Some": 10}`, and tuples,
+    /// arrays and vectors as arrays.
+    #[clap(long, conflicts_with = "arg")]
+    pub args: Option<String>,
+
+    /// An argument to the script's `main` function, given once for each parameter in order.
+    ///
+    /// Each argument is given as a JSON value in the same form as `--args`, or otherwise as an
+    /// unquoted string, e.g. `--arg 10 --arg 0x...`.
+    #[clap(long)]
+    pub arg: Vec<String>,
+
     /// Path to the project, if not specified, current working directory will be used.
     #[clap(short, long)]
     pub path: Option<String>,
@@ -130,6 +148,14 @@ pub struct RunCommand {
     #[clap(long)]
     pub unsigned: bool,
 
+    /// Export the transaction to the given file instead of signing and submitting it, so that it
+    /// may be signed elsewhere and submitted with `forc submit`.
+    ///
+    /// The file holds the transaction's bytes along with the transaction ID to be signed. The
+    /// transaction is funded from the address of the wallet that will sign it, which is prompted.
+    #[clap(long, value_name = "FILE", conflicts_with_all = &["unsigned", "signing-key", "dry-run", "simulate"])]
+    pub export_tx: Option<PathBuf>,
+
     /// Set the key to be used for signing.
     pub signing_key: Option<SecretKey>,
 }
@@ -2,16 +2,18 @@ use anyhow::{anyhow, bail, Context, Result};
 use forc_pkg::{self as pkg, fuel_core_not_running, PackageManifestFile};
 use fuel_gql_client::client::FuelClient;
 use fuel_tx::{ContractId, Transaction, TransactionBuilder, UniqueIdentifier};
+use fuels_types::ProgramABI;
 use futures::TryFutureExt;
 use pkg::BuiltPackage;
 use std::time::Duration;
 use std::{path::PathBuf, str::FromStr};
 use sway_core::language::parsed::TreeType;
 use tokio::time::timeout;
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::ops::abi::{self, AbiFunction};
 use crate::ops::pkg_util::built_pkgs_with_manifest;
-use crate::ops::tx_util::{TransactionBuilderExt, TxParameters, TX_SUBMIT_TIMEOUT_MS};
+use crate::ops::tx_util::{export_tx, TransactionBuilderExt, TxParameters, TX_SUBMIT_TIMEOUT_MS};
 
 use super::cmd::RunCommand;
 
@@ -35,15 +37,20 @@ pub async fn run(command: RunCommand) -> Result<Vec<RanScript>> {
         std::env::current_dir().map_err(|e| anyhow!("{:?}", e))?
     };
     let build_opts = build_opts_from_cmd(&command);
-    let built_pkgs_with_manifest = built_pkgs_with_manifest(&curr_dir, build_opts)?;
-    for (member_manifest, built_pkg) in built_pkgs_with_manifest {
-        if member_manifest
-            .check_program_type(vec![TreeType::Script])
-            .is_ok()
-        {
-            let pkg_receipts = run_pkg(&command, &member_manifest, &built_pkg).await?;
-            receipts.push(pkg_receipts);
-        }
+    let built_scripts: Vec<_> = built_pkgs_with_manifest(&curr_dir, build_opts)?
+        .into_iter()
+        .filter(|(member_manifest, _)| {
+            member_manifest
+                .check_program_type(vec![TreeType::Script])
+                .is_ok()
+        })
+        .collect();
+    if command.export_tx.is_some() && built_scripts.len() > 1 {
+        bail!("`--export-tx` requires a single script to run, specify one with `--path`");
+    }
+    for (member_manifest, built_pkg) in built_scripts {
+        let pkg_receipts = run_pkg(&command, &member_manifest, &built_pkg).await?;
+        receipts.push(pkg_receipts);
     }
 
     Ok(receipts)
@@ -54,9 +61,19 @@ pub async fn run_pkg(
     manifest: &PackageManifestFile,
     compiled: &BuiltPackage,
 ) -> Result<RanScript> {
-    let input_data = command.data.as_deref().unwrap_or("");
-    let data = format_hex_data(input_data);
-    let script_data = hex::decode(data).expect("Invalid hex");
+    // The types of `main` are only resolved when needed, so that scripts whose ABI cannot be
+    // resolved may still be run with `--data`.
+    let script_main = || AbiFunction::from_abi(&compiled.json_abi_program, "main");
+    let script_data = if let Some(args) = &command.args {
+        abi::encode_script_data(&script_main()?.tokenize_json(args)?, &compiled.bytecode)?
+    } else if !command.arg.is_empty() {
+        let args = script_main()?.tokenize_literals(&command.arg)?;
+        abi::encode_script_data(&args, &compiled.bytecode)?
+    } else {
+        let input_data = command.data.as_deref().unwrap_or("");
+        let data = format_hex_data(input_data);
+        hex::decode(data).expect("Invalid hex")
+    };
 
     let node_url = command
         .node_url
@@ -74,9 +91,16 @@ pub async fn run_pkg(
                 .map_err(|e| anyhow!("Failed to parse contract id: {}", e))
         })
         .collect::<Result<Vec<ContractId>>>()?;
-    let tx = TransactionBuilder::script(compiled.bytecode.clone(), script_data)
+    let mut tx_builder = TransactionBuilder::script(compiled.bytecode.clone(), script_data);
+    tx_builder
         .params(TxParameters::new(command.gas_limit, command.gas_price))
-        .add_contracts(contract_ids)
+        .add_contracts(contract_ids);
+    if let Some(path) = &command.export_tx {
+        let (tx, signature_witness_index) = tx_builder.finalize_funded(client, None).await?;
+        export_tx(path, tx.into(), signature_witness_index)?;
+        return Ok(RanScript { receipts: vec![] });
+    }
+    let tx = tx_builder
         .finalize_signed(client.clone(), command.unsigned, command.signing_key)
         .await?;
     if command.dry_run {
@@ -85,11 +109,15 @@ pub async fn run_pkg(
     } else {
         let receipts =
             try_send_tx(node_url, &tx.into(), command.pretty_print, command.simulate).await?;
+        // The script has already been executed, so its receipts are returned regardless.
+        if let Err(e) = print_decoded_output(&compiled.json_abi_program, &receipts) {
+            warn!("Failed to decode the output of the script: {e}");
+        }
         Ok(RanScript { receipts })
     }
 }
 
-async fn try_send_tx(
+pub(crate) async fn try_send_tx(
     node_url: &str,
     tx: &Transaction,
     pretty_print: bool,
@@ -174,6 +202,20 @@ fn print_receipt_output(receipts: &Vec<fuel_tx::Receipt>, pretty_print: bool) ->
     Ok(())
 }
 
+/// Print the logs and return value of the script, decoded using the types described by its ABI.
+fn print_decoded_output(program_abi: &ProgramABI, receipts: &[fuel_tx::Receipt]) -> Result<()> {
+    for log in pkg::logs::decode_logs(receipts, program_abi, &ContractId::zeroed()) {
+        info!("{}", log);
+    }
+    // The return value is only decoded if the type of `main` can be resolved from the ABI.
+    if let Ok(script_main) = AbiFunction::from_abi(program_abi, "main") {
+        if let Some(token) = script_main.decode_return(receipts, &ContractId::zeroed())? {
+            info!("Return value: {}", token);
+        }
+    }
+    Ok(())
+}
+
 fn build_opts_from_cmd(cmd: &RunCommand) -> pkg::BuildOpts {
     pkg::BuildOpts {
         pkg: pkg::PkgOpts {
@@ -200,5 +242,6 @@ fn build_opts_from_cmd(cmd: &RunCommand) -> pkg::BuildOpts {
         binary_outfile: cmd.binary_outfile.clone(),
         debug_outfile: cmd.debug_outfile.clone(),
         tests: false,
+        no_cache: false,
     }
 }
@@ -0,0 +1,27 @@
+use clap::Parser;
+use fuel_gql_client::fuel_crypto::Signature;
+use std::path::PathBuf;
+
+/// Submit a transaction exported with `--export-tx`.
+/// Attaches the given signatures as the transaction's witnesses, then sends it to a running node.
+#[derive(Debug, Default, Parser)]
+#[clap(bin_name = "forc submit", version)]
+pub struct SubmitCommand {
+    /// Path to the transaction file written by `forc deploy --export-tx` or `forc run --export-tx`.
+    pub tx_path: PathBuf,
+
+    /// A signature of the transaction ID.
+    ///
+    /// The first signature replaces the transaction's placeholder witness. Any further signatures
+    /// are attached as the witnesses that follow it, in order.
+    #[clap(long = "signature", required = true)]
+    pub signatures: Vec<Signature>,
+
+    /// The URL of the node to send the transaction to. Defaults to a node running locally.
+    #[clap(long, short, env = "FUEL_NODE_URL")]
+    pub url: Option<String>,
+
+    /// Pretty-print the outputs from the node.
+    #[clap(long = "pretty-print", short = 'r')]
+    pub pretty_print: bool,
+}
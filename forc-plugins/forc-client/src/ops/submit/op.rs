@@ -0,0 +1,136 @@
+use anyhow::{anyhow, bail, Result};
+use fuel_gql_client::{
+    fuel_crypto::{Message, Signature},
+    fuel_tx::{Input, Witness},
+};
+use fuel_tx::{field, Cacheable, Transaction, UniqueIdentifier};
+use sway_utils::constants::DEFAULT_NODE_URL;
+use tracing::info;
+
+use crate::ops::run::op::try_send_tx;
+use crate::ops::tx_util::ExportedTx;
+
+use super::cmd::SubmitCommand;
+
+pub struct SubmittedTx {
+    pub receipts: Vec<fuel_tx::Receipt>,
+}
+
+/// Attaches the given signatures to a transaction exported with `--export-tx` and sends it to
+/// the node.
+///
+/// Upon success, returns the receipts of the submitted transaction.
+pub async fn submit(command: SubmitCommand) -> Result<SubmittedTx> {
+    let exported = ExportedTx::from_path(&command.tx_path)?;
+    let mut tx = exported.transaction()?;
+    let witness_index = exported.signature_witness_index;
+    match &mut tx {
+        Transaction::Script(script) => {
+            attach_signatures(script, witness_index, &command.signatures)?
+        }
+        Transaction::Create(create) => {
+            attach_signatures(create, witness_index, &command.signatures)?
+        }
+        Transaction::Mint(_) => bail!("mint transactions cannot be submitted"),
+    }
+    tx.precompute();
+
+    let node_url = command.url.as_deref().unwrap_or(DEFAULT_NODE_URL);
+    let receipts = try_send_tx(node_url, &tx, command.pretty_print, false).await?;
+    info!("Submitted transaction 0x{}", tx.id());
+    Ok(SubmittedTx { receipts })
+}
+
+/// Attach the signatures as the witnesses starting at the given index, checking that each
+/// signature was made by the owner of the coins that the witness unlocks.
+fn attach_signatures<T>(tx: &mut T, witness_index: u8, signatures: &[Signature]) -> Result<()>
+where
+    T: field::Inputs + field::Witnesses + UniqueIdentifier,
+{
+    // Safety: `Message::from_bytes_unchecked` is unsafe because
+    // it can't guarantee that the provided bytes will be the product
+    // of a cryptographically secure hash. However, the bytes are
+    // coming from `tx.id()`, which already uses `Hasher::hash()`
+    // to hash it using a secure hash mechanism.
+    let message = unsafe { Message::from_bytes_unchecked(*tx.id()) };
+    // Each signature either replaces an existing witness or is appended as the next one.
+    let num_witnesses = tx.witnesses().len();
+    if witness_index as usize > num_witnesses {
+        bail!(
+            "witness index {} must not exceed the number of the transaction's witnesses ({})",
+            witness_index,
+            num_witnesses
+        );
+    }
+    for (signature, index) in signatures.iter().zip(witness_index..) {
+        let public_key = signature
+            .recover(&message)
+            .map_err(|e| anyhow!("invalid signature {}: {}", signature, e))?;
+        let signer = Input::owner(&public_key);
+        let owners = tx
+            .inputs()
+            .iter()
+            .filter(|input| input.witness_index() == Some(index))
+            .filter_map(Input::input_owner);
+        for owner in owners {
+            if *owner != signer {
+                bail!(
+                    "signature {} was made by {}, but witness {} unlocks coins owned by {}",
+                    signature,
+                    signer,
+                    index,
+                    owner
+                );
+            }
+        }
+
+        let witness = Witness::from(signature.as_ref());
+        let witnesses = tx.witnesses_mut();
+        match witnesses.get_mut(index as usize) {
+            Some(existing) => *existing = witness,
+            None => witnesses.push(witness),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::attach_signatures;
+    use fuel_gql_client::fuel_crypto::{Message, SecretKey, Signature};
+    use fuel_tx::{field::Witnesses, Script, Transaction, UniqueIdentifier, Witness};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn script_tx(witnesses: Vec<Witness>) -> Script {
+        Transaction::script(0, 1_000, 0, vec![], vec![], vec![], vec![], witnesses)
+    }
+
+    fn sign(tx: &Script, seed: u64) -> Signature {
+        let secret_key = SecretKey::random(&mut StdRng::seed_from_u64(seed));
+        let message = unsafe { Message::from_bytes_unchecked(*tx.id()) };
+        Signature::sign(&secret_key, &message)
+    }
+
+    #[test]
+    fn test_attach_signatures() {
+        let mut tx = script_tx(vec![Witness::default()]);
+        let signatures = [sign(&tx, 0), sign(&tx, 1)];
+        attach_signatures(&mut tx, 0, &signatures).unwrap();
+        let expected: Vec<Witness> = signatures
+            .iter()
+            .map(|signature| Witness::from(signature.as_ref()))
+            .collect();
+        assert_eq!(tx.witnesses(), &expected);
+    }
+
+    #[test]
+    fn test_attach_signatures_beyond_witnesses() {
+        let mut tx = script_tx(vec![Witness::default()]);
+        let signature = sign(&tx, 0);
+        let err = attach_signatures(&mut tx, 2, &[signature]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("must not exceed the number of the transaction's witnesses (1)"));
+        assert_eq!(tx.witnesses(), &[Witness::default()]);
+    }
+}
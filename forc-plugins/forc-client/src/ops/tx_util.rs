@@ -1,17 +1,19 @@
-use std::{io::Write, str::FromStr};
+use std::{fs, io::Write, path::Path, str::FromStr};
 
-use anyhow::{Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
 use async_trait::async_trait;
 use fuel_gql_client::{
     client::FuelClient,
     fuel_crypto::{Message, SecretKey, Signature},
     fuel_tx::{Address, ContractId, Input, Output, TransactionBuilder, Witness},
-    prelude::SerializableVec,
+    prelude::{Deserializable, SerializableVec},
 };
-use fuel_tx::{field, Buildable};
+use fuel_tx::{field, Buildable, Transaction, UniqueIdentifier};
 use fuels_core::constants::BASE_ASSET_ID;
 use fuels_signers::{provider::Provider, Wallet};
 use fuels_types::bech32::Bech32Address;
+use serde::{Deserialize, Serialize};
+use tracing::info;
 
 /// The maximum time to wait for a transaction to be included in a block by the node
 pub const TX_SUBMIT_TIMEOUT_MS: u64 = 30_000u64;
@@ -71,6 +73,15 @@ pub trait TransactionBuilderExt<Tx> {
         provider: Provider,
         signature_witness_index: u8,
     ) -> Result<&mut Self>;
+    /// Fund the transaction from the address of the signer and finalize it without a signature,
+    /// leaving a placeholder witness for the signature.
+    ///
+    /// Returns the transaction along with the index of the placeholder witness.
+    async fn finalize_funded(
+        &mut self,
+        client: FuelClient,
+        signing_key: Option<SecretKey>,
+    ) -> Result<(Tx, u8)>;
     async fn finalize_signed(
         &mut self,
         client: FuelClient,
@@ -136,48 +147,55 @@ impl<Tx: Buildable + SerializableVec + field::Witnesses + Send> TransactionBuild
 
         Ok(self)
     }
+    async fn finalize_funded(
+        &mut self,
+        client: FuelClient,
+        signing_key: Option<SecretKey>,
+    ) -> Result<(Tx, u8)> {
+        // Get the address
+        let address = if let Some(signing_key) = signing_key {
+            Address::from(*signing_key.public_key().hash())
+        } else {
+            Address::from(prompt_address()?)
+        };
+
+        // Insert dummy witness for signature
+        let signature_witness_index = self.witnesses().len().try_into()?;
+        self.add_witness(Witness::default());
+
+        // Add input coin and output change
+        self.fund(address, Provider::new(client), signature_witness_index)
+            .await?;
+
+        Ok((self._finalize_without_signature(), signature_witness_index))
+    }
     async fn finalize_signed(
         &mut self,
         client: FuelClient,
         unsigned: bool,
         signing_key: Option<SecretKey>,
     ) -> Result<Tx> {
-        let mut signature_witness_index = 0u8;
-        if !unsigned {
-            // Get the address
-            let address = if let Some(signing_key) = signing_key {
-                Address::from(*signing_key.public_key().hash())
-            } else {
-                Address::from(prompt_address()?)
-            };
-
-            // Insert dummy witness for signature
-            signature_witness_index = self.witnesses().len().try_into()?;
-            self.add_witness(Witness::default());
-
-            // Add input coin and output change
-            self.fund(address, Provider::new(client), signature_witness_index)
-                .await?;
+        if unsigned {
+            let mut tx = self._finalize_without_signature();
+            tx.precompute();
+            return Ok(tx);
         }
 
-        let mut tx = self._finalize_without_signature();
-
-        if !unsigned {
-            let signature = if let Some(signing_key) = signing_key {
-                // Safety: `Message::from_bytes_unchecked` is unsafe because
-                // it can't guarantee that the provided bytes will be the product
-                // of a cryptographically secure hash. However, the bytes are
-                // coming from `tx.id()`, which already uses `Hasher::hash()`
-                // to hash it using a secure hash mechanism.
-                let message = unsafe { Message::from_bytes_unchecked(*tx.id()) };
-                Signature::sign(&signing_key, &message)
-            } else {
-                prompt_signature(tx.id())?
-            };
-
-            let witness = Witness::from(signature.as_ref());
-            tx.replace_witness(signature_witness_index, witness);
-        }
+        let (mut tx, signature_witness_index) = self.finalize_funded(client, signing_key).await?;
+        let signature = if let Some(signing_key) = signing_key {
+            // Safety: `Message::from_bytes_unchecked` is unsafe because
+            // it can't guarantee that the provided bytes will be the product
+            // of a cryptographically secure hash. However, the bytes are
+            // coming from `tx.id()`, which already uses `Hasher::hash()`
+            // to hash it using a secure hash mechanism.
+            let message = unsafe { Message::from_bytes_unchecked(*tx.id()) };
+            Signature::sign(&signing_key, &message)
+        } else {
+            prompt_signature(tx.id())?
+        };
+
+        let witness = Witness::from(signature.as_ref());
+        tx.replace_witness(signature_witness_index, witness);
         tx.precompute();
 
         Ok(tx)
@@ -194,3 +212,103 @@ impl<T: field::Witnesses> TransactionExt for T {
         self
     }
 }
+
+/// A transaction exported via `--export-tx` in order to be signed elsewhere, e.g. on an
+/// air-gapped machine, and later submitted with `forc submit`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExportedTx {
+    /// The ID of the transaction, to be signed by the owner of its coin inputs.
+    pub tx_id: String,
+    /// The hex-encoded bytes of the transaction.
+    pub tx: String,
+    /// The index of the placeholder witness to be replaced with the signature.
+    pub signature_witness_index: u8,
+}
+
+impl ExportedTx {
+    pub fn new(mut tx: Transaction, signature_witness_index: u8) -> Self {
+        Self {
+            tx_id: format!("0x{}", hex::encode(tx.id())),
+            tx: format!("0x{}", hex::encode(tx.to_bytes())),
+            signature_witness_index,
+        }
+    }
+
+    /// Read the exported transaction from the given path.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read transaction from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("failed to parse transaction {}: {}", path.display(), e))
+    }
+
+    /// Write the exported transaction to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(&file, self)?;
+        Ok(())
+    }
+
+    /// Decode the transaction, checking that it matches the recorded transaction ID.
+    pub fn transaction(&self) -> Result<Transaction> {
+        let bytes = hex::decode(self.tx.strip_prefix("0x").unwrap_or(&self.tx))?;
+        let tx = Transaction::from_bytes(&bytes)
+            .map_err(|e| anyhow!("failed to decode transaction: {}", e))?;
+        let tx_id = format!("0x{}", hex::encode(tx.id()));
+        if tx_id != self.tx_id {
+            bail!(
+                "transaction ID {} does not match the recorded ID {}",
+                tx_id,
+                self.tx_id
+            );
+        }
+        Ok(tx)
+    }
+}
+
+/// Export the given transaction to a file at the given path, to be signed and then submitted
+/// with `forc submit`.
+pub fn export_tx(path: &Path, tx: Transaction, signature_witness_index: u8) -> Result<()> {
+    let exported = ExportedTx::new(tx, signature_witness_index);
+    exported.write(path)?;
+    info!(
+        "Exported transaction {} to {}",
+        exported.tx_id,
+        path.display()
+    );
+    info!(
+        "Sign the transaction ID, then submit it with `forc submit {} --signature <signature>`",
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExportedTx;
+    use fuel_tx::{Transaction, UniqueIdentifier};
+
+    fn script_tx(script: Vec<u8>) -> Transaction {
+        Transaction::script(0, 1_000, 0, script, vec![], vec![], vec![], vec![]).into()
+    }
+
+    #[test]
+    fn test_exported_tx_round_trip() {
+        let tx = script_tx(vec![1, 2, 3, 4]);
+        let tx_id = tx.id();
+        let exported = ExportedTx::new(tx, 0);
+        assert_eq!(exported.tx_id, format!("0x{}", hex::encode(tx_id)));
+        let json = serde_json::to_string(&exported).unwrap();
+        let imported: ExportedTx = serde_json::from_str(&json).unwrap();
+        assert_eq!(imported.transaction().unwrap().id(), tx_id);
+    }
+
+    #[test]
+    fn test_exported_tx_id_mismatch() {
+        let mut exported = ExportedTx::new(script_tx(vec![1, 2, 3, 4]), 0);
+        let other = ExportedTx::new(script_tx(vec![5, 6, 7, 8]), 0);
+        exported.tx = other.tx;
+        assert!(exported.transaction().is_err());
+    }
+}
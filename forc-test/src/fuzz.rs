@@ -0,0 +1,717 @@
+//! Property-based testing of tests that accept arguments.
+//!
+//! A test declared with parameters is executed many times, each time with arguments generated at
+//! random from the types of its parameters as described by the package's test ABI. The arguments
+//! are ABI-encoded and provided to the test as its script data. If the test fails for any set of
+//! arguments, those arguments are repeatedly simplified for as long as the test continues to fail,
+//! and the simplest failing arguments are reported as a counterexample.
+
+use crate::{exec_test, script_gas_used, TestPassCondition, TestSetup};
+use anyhow::anyhow;
+use forc_pkg::{self as pkg, abi};
+use fuel_tx as tx;
+use fuel_vm as vm;
+use fuels_core::{abi_encoder::ABIEncoder, StringToken, Token};
+use fuels_types::{param_types::ParamType, ProgramABI, TypeApplication, TypeDeclaration};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{collections::HashMap, fmt, time::Duration};
+use sway_core::fuel_prelude::fuel_crypto;
+
+/// The maximum number of elements generated for a vector argument.
+const MAX_VEC_LEN: usize = 8;
+/// The maximum number of times a failing test is re-executed while shrinking its arguments.
+const MAX_SHRINK_RUNS: u64 = 1024;
+
+/// Configures the execution of tests that accept arguments.
+#[derive(Clone, Copy, Debug)]
+pub struct FuzzConfig {
+    /// The number of sets of arguments with which each test is executed.
+    pub runs: u64,
+    /// The seed from which the arguments of each test are generated. A given seed always produces
+    /// the same arguments for a given test.
+    pub seed: u64,
+}
+
+/// The result of executing a test with generated arguments.
+#[derive(Clone, Debug)]
+pub struct FuzzResult {
+    /// The number of sets of generated arguments with which the test was executed, not including
+    /// the executions required to shrink a counterexample.
+    pub runs: u64,
+    /// The simplest arguments found for which the test fails, if any.
+    pub counterexample: Option<Counterexample>,
+}
+
+/// A set of arguments for which a test fails.
+#[derive(Clone, Debug)]
+pub struct Counterexample {
+    pub args: Vec<CounterexampleArg>,
+}
+
+/// A single argument of a counterexample.
+#[derive(Clone, Debug)]
+pub struct CounterexampleArg {
+    /// The name of the test parameter.
+    pub name: String,
+    /// The argument as it was encoded for the test.
+    pub token: Token,
+    /// The argument formatted in Sway syntax.
+    pub value: String,
+}
+
+/// The parameters of a test, as described by the test ABI.
+pub(crate) struct TestParams {
+    /// The name, type and ABI type application of each parameter.
+    params: Vec<(String, ParamType, TypeApplication)>,
+    /// All types of the test ABI by type ID, used to format counterexamples.
+    type_lookup: HashMap<usize, TypeDeclaration>,
+}
+
+/// A test that has been executed with generated arguments.
+pub(crate) struct FuzzedTest {
+    pub(crate) result: FuzzResult,
+    /// The final state of the counterexample, or of the last run if the test passed.
+    pub(crate) state: vm::state::ProgramState,
+    /// The receipts of the counterexample, or of the last run if the test passed.
+    pub(crate) receipts: Vec<tx::Receipt>,
+    /// The total time taken across all runs, including those required for shrinking.
+    pub(crate) duration: Duration,
+    /// The mean gas used across all runs with generated arguments.
+    pub(crate) gas_used: u64,
+}
+
+impl FuzzConfig {
+    /// The number of runs used when none is specified.
+    pub const DEFAULT_RUNS: u64 = 256;
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            runs: Self::DEFAULT_RUNS,
+            seed: 0,
+        }
+    }
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (ix, arg) in self.args.iter().enumerate() {
+            if ix > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} = {}", arg.name, arg.value)?;
+        }
+        Ok(())
+    }
+}
+
+impl TestParams {
+    /// The parameters of the given test, as described by the test ABI.
+    pub(crate) fn from_abi(test_abi: &ProgramABI, test_name: &str) -> anyhow::Result<Self> {
+        let function = test_abi
+            .functions
+            .iter()
+            .find(|function| function.name == test_name)
+            .ok_or_else(|| anyhow!("test `{test_name}` is missing from the test ABI"))?;
+        let type_lookup = abi::type_lookup(test_abi);
+        let params = function
+            .inputs
+            .iter()
+            .map(|input| {
+                let param_type = ParamType::try_from_type_application(input, &type_lookup)
+                    .map_err(|e| {
+                        anyhow!(
+                            "unsupported type for parameter `{}` of test `{test_name}`: {e}",
+                            input.name
+                        )
+                    })?;
+                Ok((input.name.clone(), param_type, input.clone()))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            params,
+            type_lookup,
+        })
+    }
+
+    /// Whether or not the test accepts any arguments.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+/// Execute the test with arguments generated for the given parameters until either the configured
+/// number of runs is reached or the test fails, in which case the failing arguments are shrunk.
+///
+/// The test is always executed at least once.
+pub(crate) fn fuzz_test(
+    test_name: &str,
+    params: &TestParams,
+    bytecode: &[u8],
+    test_setup: &TestSetup,
+    test_config: &pkg::TestConfig,
+    condition: &TestPassCondition,
+    fuzz_config: &FuzzConfig,
+) -> anyhow::Result<FuzzedTest> {
+    let script_data_addr = abi::script_data_addr(bytecode);
+    let run = |args: &[Token]| {
+        let script_data = ABIEncoder::encode(args)
+            .map_err(|e| anyhow!("failed to encode arguments of test `{test_name}`: {e}"))?
+            .resolve(script_data_addr);
+        exec_test(bytecode, script_data, test_setup, test_config)
+    };
+
+    // Execute the test with generated arguments until it fails.
+    let mut rng = StdRng::seed_from_u64(test_seed(fuzz_config.seed, test_name));
+    let mut runs = 0;
+    let mut duration = Duration::ZERO;
+    let mut total_gas_used: u128 = 0;
+    let (args, mut state, mut receipts) = loop {
+        let args: Vec<Token> = params
+            .params
+            .iter()
+            .map(|(_, ty, _)| generate(ty, &mut rng))
+            .collect();
+        let (state, run_duration, receipts) = run(&args)?;
+        runs += 1;
+        duration += run_duration;
+        total_gas_used += u128::from(script_gas_used(&receipts));
+        if !condition.is_met(&state) || runs >= fuzz_config.runs {
+            break (args, state, receipts);
+        }
+    };
+    let gas_used = u64::try_from(total_gas_used / u128::from(runs))?;
+
+    // Simplify the failing arguments for as long as the test continues to fail.
+    let counterexample = match condition.is_met(&state) {
+        true => None,
+        false => {
+            let (shrunk_args, shrunk_run) = shrink_failure(args, |candidate| {
+                let (candidate_state, run_duration, candidate_receipts) = run(candidate)?;
+                duration += run_duration;
+                let fails = !condition.is_met(&candidate_state);
+                Ok(fails.then_some((candidate_state, candidate_receipts)))
+            })?;
+            if let Some((shrunk_state, shrunk_receipts)) = shrunk_run {
+                state = shrunk_state;
+                receipts = shrunk_receipts;
+            }
+            let args = params
+                .params
+                .iter()
+                .zip(shrunk_args)
+                .map(|((name, _, application), token)| CounterexampleArg {
+                    name: name.clone(),
+                    value: format_value(&token, Some(application), &params.type_lookup),
+                    token,
+                })
+                .collect();
+            Some(Counterexample { args })
+        }
+    };
+
+    Ok(FuzzedTest {
+        result: FuzzResult {
+            runs,
+            counterexample,
+        },
+        state,
+        receipts,
+        duration,
+        gas_used,
+    })
+}
+
+/// Greedily simplify the given failing arguments for as long as they continue to fail.
+///
+/// `run` executes the test with a candidate set of arguments, returning the outcome of the run if
+/// the test fails. Returns the simplest failing arguments found, along with the outcome of their
+/// run if they differ from the given arguments.
+fn shrink_failure<T>(
+    mut args: Vec<Token>,
+    mut run: impl FnMut(&[Token]) -> anyhow::Result<Option<T>>,
+) -> anyhow::Result<(Vec<Token>, Option<T>)> {
+    let mut failure = None;
+    let mut shrink_runs = 0;
+    'shrink: while shrink_runs < MAX_SHRINK_RUNS {
+        for candidate in shrink_args(&args) {
+            if shrink_runs >= MAX_SHRINK_RUNS {
+                break 'shrink;
+            }
+            shrink_runs += 1;
+            if let Some(outcome) = run(&candidate)? {
+                args = candidate;
+                failure = Some(outcome);
+                continue 'shrink;
+            }
+        }
+        break;
+    }
+    Ok((args, failure))
+}
+
+/// Derive the seed for a single test so that each test receives its own sequence of arguments.
+///
+/// The seed is derived with SHA-256 so that it remains the same across platforms and releases.
+fn test_seed(seed: u64, test_name: &str) -> u64 {
+    let mut hasher = fuel_crypto::Hasher::default();
+    hasher.input(seed.to_be_bytes());
+    hasher.input(test_name.as_bytes());
+    let digest = hasher.digest();
+    let (bytes, _) = digest.split_at(std::mem::size_of::<u64>());
+    u64::from_be_bytes(bytes.try_into().expect("digest is at least 8 bytes"))
+}
+
+/// Generate an arbitrary value of the given type.
+fn generate(param_type: &ParamType, rng: &mut StdRng) -> Token {
+    match param_type {
+        ParamType::U8 => Token::U8(generate_uint(rng, u8::MAX.into()) as u8),
+        ParamType::U16 => Token::U16(generate_uint(rng, u16::MAX.into()) as u16),
+        ParamType::U32 => Token::U32(generate_uint(rng, u32::MAX.into()) as u32),
+        ParamType::U64 => Token::U64(generate_uint(rng, u64::MAX)),
+        ParamType::Byte => Token::Byte(generate_uint(rng, u8::MAX.into()) as u8),
+        ParamType::Bool => Token::Bool(rng.gen()),
+        ParamType::B256 => Token::B256(match rng.gen_range(0..4) {
+            0 => [0; 32],
+            1 => [0xff; 32],
+            _ => rng.gen(),
+        }),
+        ParamType::Unit => Token::Unit,
+        ParamType::Array(ty, len) => Token::Array((0..*len).map(|_| generate(ty, rng)).collect()),
+        ParamType::Vector(ty) => {
+            let len = rng.gen_range(0..=MAX_VEC_LEN);
+            Token::Vector((0..len).map(|_| generate(ty, rng)).collect())
+        }
+        ParamType::String(len) => {
+            let data = (0..*len)
+                .map(|_| char::from(rng.gen_range(b' '..=b'~')))
+                .collect();
+            Token::String(StringToken::new(data, *len))
+        }
+        ParamType::Struct { fields, .. } => {
+            Token::Struct(fields.iter().map(|(_, ty)| generate(ty, rng)).collect())
+        }
+        ParamType::Enum { variants, .. } => {
+            let ix = rng.gen_range(0..variants.variants().len());
+            let (_, ty) = &variants.variants()[ix];
+            Token::Enum(Box::new((ix as u8, generate(ty, rng), variants.clone())))
+        }
+        ParamType::Tuple(types) => Token::Tuple(types.iter().map(|ty| generate(ty, rng)).collect()),
+    }
+}
+
+/// Generate an unsigned integer no greater than `max`, biased towards small values and the edge
+/// cases that most often expose overflow and off-by-one bugs.
+fn generate_uint(rng: &mut StdRng, max: u64) -> u64 {
+    match rng.gen_range(0..4) {
+        0 => *[0, 1, 2, max / 2, max - 1, max]
+            .choose(rng)
+            .expect("edge cases are non-empty"),
+        1 => rng.gen_range(0..=max.min(u8::MAX.into())),
+        _ => rng.gen_range(0..=max),
+    }
+}
+
+/// The simplest value of the given type, towards which all values of the type are shrunk.
+fn minimal(param_type: &ParamType) -> Token {
+    match param_type {
+        ParamType::U8 => Token::U8(0),
+        ParamType::U16 => Token::U16(0),
+        ParamType::U32 => Token::U32(0),
+        ParamType::U64 => Token::U64(0),
+        ParamType::Byte => Token::Byte(0),
+        ParamType::Bool => Token::Bool(false),
+        ParamType::B256 => Token::B256([0; 32]),
+        ParamType::Unit => Token::Unit,
+        ParamType::Array(ty, len) => Token::Array(vec![minimal(ty); *len]),
+        ParamType::Vector(_) => Token::Vector(vec![]),
+        ParamType::String(len) => Token::String(StringToken::new("a".repeat(*len), *len)),
+        ParamType::Struct { fields, .. } => {
+            Token::Struct(fields.iter().map(|(_, ty)| minimal(ty)).collect())
+        }
+        ParamType::Enum { variants, .. } => {
+            let (_, ty) = &variants.variants()[0];
+            Token::Enum(Box::new((0, minimal(ty), variants.clone())))
+        }
+        ParamType::Tuple(types) => Token::Tuple(types.iter().map(minimal).collect()),
+    }
+}
+
+/// All candidate simplifications of the given sequence of values (e.g. a test's arguments or the
+/// fields of a struct), each differing in exactly one value, ordered from the most to the least
+/// aggressive.
+fn shrink_args(args: &[Token]) -> Vec<Vec<Token>> {
+    args.iter()
+        .enumerate()
+        .flat_map(|(ix, arg)| {
+            shrink(arg).into_iter().map(move |candidate| {
+                let mut args = args.to_vec();
+                args[ix] = candidate;
+                args
+            })
+        })
+        .collect()
+}
+
+/// All candidate simplifications of the given value, ordered from the most to the least
+/// aggressive.
+fn shrink(token: &Token) -> Vec<Token> {
+    match token {
+        Token::U8(n) => shrink_uint((*n).into())
+            .map(|n| Token::U8(n as u8))
+            .collect(),
+        Token::U16(n) => shrink_uint((*n).into())
+            .map(|n| Token::U16(n as u16))
+            .collect(),
+        Token::U32(n) => shrink_uint((*n).into())
+            .map(|n| Token::U32(n as u32))
+            .collect(),
+        Token::U64(n) => shrink_uint(*n).map(Token::U64).collect(),
+        Token::Byte(n) => shrink_uint((*n).into())
+            .map(|n| Token::Byte(n as u8))
+            .collect(),
+        Token::Bool(true) => vec![Token::Bool(false)],
+        Token::B256(bytes) if *bytes != [0; 32] => vec![Token::B256([0; 32])],
+        Token::String(string) => match string.get_encodable_str() {
+            Ok(data) => shrink_str(data)
+                .into_iter()
+                .map(|shrunk| Token::String(StringToken::new(shrunk, data.len())))
+                .collect(),
+            Err(_) => vec![],
+        },
+        Token::Array(elems) => shrink_args(elems).into_iter().map(Token::Array).collect(),
+        Token::Struct(fields) => shrink_args(fields).into_iter().map(Token::Struct).collect(),
+        Token::Tuple(elems) => shrink_args(elems).into_iter().map(Token::Tuple).collect(),
+        Token::Vector(elems) => {
+            let mut candidates = vec![];
+            if !elems.is_empty() {
+                candidates.push(vec![]);
+            }
+            if elems.len() > 2 {
+                candidates.push(elems[..elems.len() / 2].to_vec());
+            }
+            if elems.len() > 1 {
+                candidates.extend((0..elems.len()).map(|ix| {
+                    let mut elems = elems.clone();
+                    elems.remove(ix);
+                    elems
+                }));
+            }
+            candidates.extend(shrink_args(elems));
+            candidates.into_iter().map(Token::Vector).collect()
+        }
+        Token::Enum(selector) => {
+            let (discriminant, payload, variants) = &**selector;
+            let earlier_variants = variants
+                .variants()
+                .iter()
+                .take(usize::from(*discriminant))
+                .enumerate()
+                .map(|(ix, (_, ty))| (ix as u8, minimal(ty)));
+            let shrunk_payloads = shrink(payload)
+                .into_iter()
+                .map(|payload| (*discriminant, payload));
+            earlier_variants
+                .chain(shrunk_payloads)
+                .map(|(discriminant, payload)| {
+                    Token::Enum(Box::new((discriminant, payload, variants.clone())))
+                })
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Candidate simplifications of an unsigned integer: zero, followed by values successively closer
+/// to `n`, such that repeated shrinking converges on a failure threshold in logarithmic time.
+fn shrink_uint(n: u64) -> impl Iterator<Item = u64> {
+    std::iter::successors(Some(n), |delta| Some(delta / 2))
+        .take_while(|delta| *delta > 0)
+        .map(move |delta| n - delta)
+}
+
+/// Candidate simplifications of a string, replacing its characters with `a`.
+fn shrink_str(data: &str) -> Vec<String> {
+    let simplest = "a".repeat(data.len());
+    if data == simplest {
+        return vec![];
+    }
+    let mut candidates = vec![simplest];
+    candidates.extend(
+        data.char_indices()
+            .filter(|(_, c)| *c != 'a')
+            .map(|(ix, _)| {
+                let mut candidate = data.to_string();
+                candidate.replace_range(ix..ix + 1, "a");
+                candidate
+            }),
+    );
+    candidates
+}
+
+/// Format a value in Sway syntax, using its ABI type application (where known) to name the fields
+/// of structs and the variants of enums.
+fn format_value(
+    token: &Token,
+    application: Option<&TypeApplication>,
+    type_lookup: &HashMap<usize, TypeDeclaration>,
+) -> String {
+    let components = abi::type_components(application, type_lookup);
+    let type_name = abi::type_name(application, type_lookup);
+    match token {
+        Token::Unit => "()".to_string(),
+        Token::U8(n) | Token::Byte(n) => n.to_string(),
+        Token::U16(n) => n.to_string(),
+        Token::U32(n) => n.to_string(),
+        Token::U64(n) => n.to_string(),
+        Token::Bool(b) => b.to_string(),
+        Token::B256(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::String(string) => format!("{:?}", string.get_encodable_str().unwrap_or_default()),
+        Token::Array(elems) => {
+            let elems = format_elems(elems, |_| components.first(), type_lookup);
+            format!("[{elems}]")
+        }
+        Token::Vector(elems) => {
+            let elem_application = abi::vec_elem_application(application);
+            format!(
+                "[{}]",
+                format_elems(elems, |_| elem_application, type_lookup)
+            )
+        }
+        Token::Tuple(elems) => {
+            let elems = format_elems(elems, |ix| components.get(ix), type_lookup);
+            format!("({elems})")
+        }
+        Token::Struct(fields) if components.len() == fields.len() => {
+            let fields = fields
+                .iter()
+                .zip(components)
+                .map(|(field, component)| {
+                    let value = format_value(field, Some(component), type_lookup);
+                    format!("{}: {value}", component.name)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{type_name} {{ {fields} }}")
+        }
+        Token::Struct(fields) => {
+            let fields = format_elems(fields, |_| None, type_lookup);
+            format!("{type_name} {{ {fields} }}")
+        }
+        Token::Enum(selector) => {
+            let (discriminant, payload, _) = &**selector;
+            let variant = components.get(usize::from(*discriminant));
+            let variant_name = variant.map_or_else(|| discriminant.to_string(), |v| v.name.clone());
+            match payload {
+                Token::Unit => format!("{type_name}::{variant_name}"),
+                payload => {
+                    let payload = format_value(payload, variant, type_lookup);
+                    format!("{type_name}::{variant_name}({payload})")
+                }
+            }
+        }
+    }
+}
+
+/// Format a sequence of values separated by commas, given the ABI type application of each.
+fn format_elems<'a>(
+    elems: &[Token],
+    application: impl Fn(usize) -> Option<&'a TypeApplication>,
+    type_lookup: &HashMap<usize, TypeDeclaration>,
+) -> String {
+    elems
+        .iter()
+        .enumerate()
+        .map(|(ix, elem)| format_value(elem, application(ix), type_lookup))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuels_types::enum_variants::EnumVariants;
+
+    #[test]
+    fn test_seed_is_stable() {
+        // The derived seeds must never change, so that a given `--fuzz-seed` always reproduces
+        // the same run.
+        assert_eq!(test_seed(0, "test_foo"), 0x10f2_7047_9d6f_0b1e);
+        assert_eq!(test_seed(42, "test_foo"), 0xaaf9_2d7f_d7b9_d464);
+        assert_ne!(test_seed(0, "test_foo"), test_seed(0, "test_bar"));
+    }
+
+    fn point_type() -> ParamType {
+        ParamType::Struct {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), ParamType::U64),
+                ("y".to_string(), ParamType::Bool),
+            ],
+            generics: vec![],
+        }
+    }
+
+    fn shape_variants() -> EnumVariants {
+        EnumVariants::new(vec![
+            ("Empty".to_string(), ParamType::Unit),
+            ("Dot".to_string(), point_type()),
+        ])
+        .unwrap()
+    }
+
+    fn application(name: &str, type_id: usize) -> TypeApplication {
+        TypeApplication {
+            name: name.to_string(),
+            type_id,
+            type_arguments: None,
+        }
+    }
+
+    fn declaration(
+        type_id: usize,
+        type_field: &str,
+        components: &[(&str, usize)],
+    ) -> TypeDeclaration {
+        TypeDeclaration {
+            type_id,
+            type_field: type_field.to_string(),
+            components: (!components.is_empty()).then(|| {
+                components
+                    .iter()
+                    .map(|(name, type_id)| application(name, *type_id))
+                    .collect()
+            }),
+            type_parameters: None,
+        }
+    }
+
+    /// The types of a test ABI declaring `struct Point { x: u64, y: bool }`,
+    /// `enum Shape { Empty: (), Dot: Point }` and `[Point; 2]`.
+    fn type_lookup() -> HashMap<usize, TypeDeclaration> {
+        [
+            declaration(0, "u64", &[]),
+            declaration(1, "bool", &[]),
+            declaration(2, "()", &[]),
+            declaration(3, "struct Point", &[("x", 0), ("y", 1)]),
+            declaration(4, "enum Shape", &[("Empty", 2), ("Dot", 3)]),
+            declaration(5, "[_; 2]", &[("__array_element", 3)]),
+        ]
+        .into_iter()
+        .map(|decl| (decl.type_id, decl))
+        .collect()
+    }
+
+    fn point(x: u64, y: bool) -> Token {
+        Token::Struct(vec![Token::U64(x), Token::Bool(y)])
+    }
+
+    #[test]
+    fn test_same_seed_generates_same_args() {
+        let types = [
+            point_type(),
+            ParamType::Vector(Box::new(ParamType::U32)),
+            ParamType::Enum {
+                name: "Shape".to_string(),
+                variants: shape_variants(),
+                generics: vec![],
+            },
+            ParamType::String(4),
+            ParamType::B256,
+        ];
+        let generate_args = |seed, test_name| {
+            let mut rng = StdRng::seed_from_u64(test_seed(seed, test_name));
+            (0..16)
+                .flat_map(|_| {
+                    types
+                        .iter()
+                        .map(|ty| generate(ty, &mut rng))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(generate_args(7, "test_a"), generate_args(7, "test_a"));
+        assert_ne!(generate_args(7, "test_a"), generate_args(8, "test_a"));
+        assert_ne!(generate_args(7, "test_a"), generate_args(7, "test_b"));
+    }
+
+    #[test]
+    fn test_shrink_uint_to_threshold() {
+        let mut runs = 0;
+        let (args, failure) = shrink_failure(vec![Token::U64(987_654_321)], |args| {
+            runs += 1;
+            Ok(matches!(args, [Token::U64(n)] if *n >= 1000).then_some(runs))
+        })
+        .unwrap();
+        assert_eq!(args, vec![Token::U64(1000)]);
+        assert!(failure.is_some());
+        assert!(runs < MAX_SHRINK_RUNS);
+    }
+
+    #[test]
+    fn test_shrink_vec_and_struct() {
+        // Fails whenever the vector contains an element greater than 10, regardless of the point.
+        let args = vec![
+            Token::Vector([3, 40, 7, 255, 0, 12].map(Token::U8).to_vec()),
+            point(12345, true),
+        ];
+        let (args, _) = shrink_failure(args, |args| {
+            let fails = match &args[0] {
+                Token::Vector(elems) => elems.iter().any(|e| matches!(e, Token::U8(n) if *n > 10)),
+                _ => false,
+            };
+            Ok(fails.then_some(()))
+        })
+        .unwrap();
+        assert_eq!(
+            args,
+            vec![Token::Vector(vec![Token::U8(11)]), point(0, false)]
+        );
+    }
+
+    #[test]
+    fn test_shrink_passing_args_unchanged() {
+        let args = vec![Token::U64(5), Token::Bool(true)];
+        let (shrunk, failure) = shrink_failure(args.clone(), |_| Ok(None::<()>)).unwrap();
+        assert_eq!(shrunk, args);
+        assert!(failure.is_none());
+    }
+
+    #[test]
+    fn test_shrink_enum_to_earlier_variant() {
+        let variants = shape_variants();
+        let dot = Token::Enum(Box::new((1, point(9, true), variants.clone())));
+        assert_eq!(
+            shrink(&dot)[0],
+            Token::Enum(Box::new((0, Token::Unit, variants)))
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        let type_lookup = type_lookup();
+        let format = |token: &Token, type_id| {
+            format_value(token, Some(&application("arg", type_id)), &type_lookup)
+        };
+        assert_eq!(format(&point(1, true), 3), "Point { x: 1, y: true }");
+        assert_eq!(
+            format(
+                &Token::Enum(Box::new((0, Token::Unit, shape_variants()))),
+                4
+            ),
+            "Shape::Empty"
+        );
+        assert_eq!(
+            format(
+                &Token::Enum(Box::new((1, point(0, false), shape_variants()))),
+                4
+            ),
+            "Shape::Dot(Point { x: 0, y: false })"
+        );
+        assert_eq!(
+            format(&Token::Array(vec![point(1, true), point(2, false)]), 5),
+            "[Point { x: 1, y: true }, Point { x: 2, y: false }]"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Recording and comparing the gas consumed by each test.
+//!
+//! A gas snapshot is a plain text file with one line per test of the form
+//! `<package>::<test> <gas_used>`, sorted by test path so that the file produces minimal diffs
+//! under version control.
+
+use crate::{Tested, TestedPackage};
+use anyhow::{anyhow, bail, Context, Result};
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+/// The gas used by each test, keyed by the test's `<package>::<test>` path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasSnapshot {
+    pub tests: BTreeMap<String, u64>,
+}
+
+/// A difference between the recorded gas snapshot and the gas used by the latest test run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GasSnapshotDiff {
+    /// The test is new and has no recorded gas usage.
+    Added { test: String, gas_used: u64 },
+    /// The test was recorded but was not executed.
+    Removed { test: String, gas_used: u64 },
+    /// The gas used by the test differs from the recorded gas usage.
+    Changed { test: String, old: u64, new: u64 },
+}
+
+impl GasSnapshot {
+    /// Collect the gas used by each executed test.
+    pub fn from_tested(tested: &Tested) -> Self {
+        let pkgs: Vec<&TestedPackage> = match tested {
+            Tested::Package(pkg) => vec![pkg],
+            Tested::Workspace(pkgs) => pkgs.iter().collect(),
+        };
+        let tests = pkgs
+            .into_iter()
+            .flat_map(|pkg| {
+                pkg.tests.iter().map(|test| {
+                    let test_path = format!("{}::{}", pkg.built.pkg_name, test.name);
+                    (test_path, test.gas_used)
+                })
+            })
+            .collect();
+        Self { tests }
+    }
+
+    /// Parse a gas snapshot from its string representation.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut tests = BTreeMap::new();
+        for (ix, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line_number = ix + 1;
+            let (test, gas_used) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| anyhow!("line {line_number}: expected `<test> <gas_used>`"))?;
+            let gas_used = gas_used
+                .trim()
+                .parse()
+                .map_err(|e| anyhow!("line {line_number}: invalid gas value: {e}"))?;
+            if tests.insert(test.to_string(), gas_used).is_some() {
+                bail!("line {line_number}: duplicate entry for test `{test}`");
+            }
+        }
+        Ok(Self { tests })
+    }
+
+    /// Read the gas snapshot at the given path.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read gas snapshot {}", path.display()))?;
+        Self::parse(&contents)
+            .with_context(|| format!("failed to parse gas snapshot {}", path.display()))
+    }
+
+    /// Write the gas snapshot to the given path, overwriting any existing snapshot.
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write gas snapshot {}", path.display()))
+    }
+
+    /// Compare `self` (the recorded snapshot) against a `new` snapshot, returning all differences
+    /// ordered by test path.
+    pub fn diff(&self, new: &GasSnapshot) -> Vec<GasSnapshotDiff> {
+        let mut diffs = vec![];
+        for (test, &old) in &self.tests {
+            match new.tests.get(test) {
+                None => diffs.push(GasSnapshotDiff::Removed {
+                    test: test.clone(),
+                    gas_used: old,
+                }),
+                Some(&new) if new != old => diffs.push(GasSnapshotDiff::Changed {
+                    test: test.clone(),
+                    old,
+                    new,
+                }),
+                Some(_) => (),
+            }
+        }
+        for (test, &gas_used) in &new.tests {
+            if !self.tests.contains_key(test) {
+                diffs.push(GasSnapshotDiff::Added {
+                    test: test.clone(),
+                    gas_used,
+                });
+            }
+        }
+        diffs.sort_by(|a, b| a.test().cmp(b.test()));
+        diffs
+    }
+}
+
+impl GasSnapshotDiff {
+    /// The `<package>::<test>` path of the test that differs.
+    pub fn test(&self) -> &str {
+        match self {
+            Self::Added { test, .. } | Self::Removed { test, .. } | Self::Changed { test, .. } => {
+                test
+            }
+        }
+    }
+}
+
+impl fmt::Display for GasSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (test, gas_used) in &self.tests {
+            writeln!(f, "{test} {gas_used}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GasSnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Added { test, gas_used } => write!(f, "+ {test} {gas_used}"),
+            Self::Removed { test, gas_used } => write!(f, "- {test} {gas_used}"),
+            Self::Changed { test, old, new } => {
+                let delta = i128::from(*new) - i128::from(*old);
+                write!(f, "~ {test} {old} -> {new} ({delta:+})")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GasSnapshot, GasSnapshotDiff};
+    use forc_pkg::test_util;
+    use std::fs;
+
+    fn snapshot(tests: &[(&str, u64)]) -> GasSnapshot {
+        let tests = tests
+            .iter()
+            .map(|(test, gas_used)| (test.to_string(), *gas_used))
+            .collect();
+        GasSnapshot { tests }
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let dir = test_util::temp_dir();
+        let path = dir.path().join("gas.snap");
+        let recorded = snapshot(&[("pkg::test_b", 20), ("pkg::test_a", 10), ("other::test", 5)]);
+        recorded.to_file(&path).unwrap();
+
+        // Tests are written in order of their test path.
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "other::test 5\npkg::test_a 10\npkg::test_b 20\n");
+        assert_eq!(GasSnapshot::from_file(&path).unwrap(), recorded);
+    }
+
+    #[test]
+    fn test_parse() {
+        let parsed = GasSnapshot::parse("\n  pkg::test_a   10  \n\npkg::test_b 20\n").unwrap();
+        assert_eq!(
+            parsed,
+            snapshot(&[("pkg::test_a", 10), ("pkg::test_b", 20)])
+        );
+
+        let err = GasSnapshot::parse("pkg::test_a 10\npkg::test_a 11").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: duplicate entry for test `pkg::test_a`"
+        );
+        let err = GasSnapshot::parse("pkg::test_a").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: expected `<test> <gas_used>`");
+        assert!(GasSnapshot::parse("pkg::test_a ten").is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let recorded = snapshot(&[("pkg::a", 10), ("pkg::b", 20), ("pkg::c", 30)]);
+        assert!(recorded.diff(&recorded).is_empty());
+
+        let new = snapshot(&[("pkg::a", 10), ("pkg::b", 15), ("pkg::d", 40)]);
+        let diffs = recorded.diff(&new);
+        assert_eq!(
+            diffs,
+            vec![
+                GasSnapshotDiff::Changed {
+                    test: "pkg::b".to_string(),
+                    old: 20,
+                    new: 15,
+                },
+                GasSnapshotDiff::Removed {
+                    test: "pkg::c".to_string(),
+                    gas_used: 30,
+                },
+                GasSnapshotDiff::Added {
+                    test: "pkg::d".to_string(),
+                    gas_used: 40,
+                },
+            ]
+        );
+        let diffs: Vec<String> = diffs.iter().map(|diff| diff.to_string()).collect();
+        assert_eq!(
+            diffs,
+            ["~ pkg::b 20 -> 15 (-5)", "- pkg::c 30", "+ pkg::d 40"]
+        );
+    }
+}
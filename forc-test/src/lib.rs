@@ -1,12 +1,33 @@
-use std::{collections::HashSet, fs, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use forc_pkg as pkg;
-use fuel_tx as tx;
+use fuel_tx::{self as tx, IntoChecked};
 use fuel_vm::{self as vm, prelude::Opcode};
-use pkg::BuiltPackage;
 use rand::{Rng, SeedableRng};
-use sway_core::{language::ty::TyFunctionDeclaration, transform::AttributeKind};
-use sway_types::{Span, Spanned};
+use rayon::prelude::*;
+use sway_core::FinalizedEntry;
+use sway_types::Span;
+
+mod fuzz;
+mod gas_snapshot;
+mod report;
+
+pub use fuzz::{Counterexample, CounterexampleArg, FuzzConfig, FuzzResult};
+pub use gas_snapshot::{GasSnapshot, GasSnapshotDiff};
+pub use pkg::logs::{decode_logs, DecodedLog};
+pub use report::{junit_xml, TestCaseReport, TestStatus};
+
+/// The seed used to generate the transaction metadata for deployments and test execution.
+const TEST_METADATA_SEED: u64 = 0x7E57u64;
+/// The maturity of all transactions created for deployments and test execution, unless
+/// configured otherwise.
+const TEST_MATURITY: tx::Word = 1;
+/// The block height at which all deployments and tests are executed, unless configured otherwise.
+const TEST_BLOCK_HEIGHT: tx::Word = (u32::MAX >> 1) as tx::Word;
 
 /// The result of a `forc test` invocation.
 #[derive(Debug)]
@@ -21,13 +42,30 @@ pub struct TestedPackage {
     pub built: Box<pkg::BuiltPackage>,
     /// The resulting `ProgramState` after executing the test.
     pub tests: Vec<TestResult>,
+    /// The names of the tests that were not executed due to the `#[test(ignore)]` attribute.
+    pub ignored: Vec<String>,
+    /// The number of tests that were not executed as they did not match the test filter.
+    pub filtered_out: usize,
+}
+
+/// A test of a tested package that was either executed or ignored.
+#[derive(Debug)]
+pub enum DeclaredTest<'a> {
+    Executed(&'a TestResult),
+    /// A test that was not executed due to the `#[test(ignore)]` attribute.
+    Ignored {
+        /// The name of the test function.
+        name: &'a str,
+        /// The declaration of the test function.
+        decl: &'a pkg::TestDecl,
+    },
 }
 
 #[derive(Debug)]
 pub struct TestDetails {
     /// The file that contains the test function.
     pub file_path: Arc<PathBuf>,
-    /// The line number for the test declaration.
+    /// The line number for the test declaration, starting from 1.
     pub line_number: usize,
 }
 
@@ -38,25 +76,91 @@ pub struct TestResult {
     pub name: String,
     /// The time taken for the test to execute.
     pub duration: std::time::Duration,
+    /// The gas consumed by the VM while executing the test.
+    ///
+    /// For tests that accept arguments, this is the mean gas used across all generated arguments.
+    pub gas_used: u64,
     /// The span for the function declaring this tests.
     pub span: Span,
     /// The resulting state after executing the test function.
     pub state: vm::state::ProgramState,
+    /// The receipts produced by the transaction executing the test.
+    pub receipts: Vec<tx::Receipt>,
     /// The required state of the VM for this test to pass.
     pub condition: TestPassCondition,
+    /// The result of executing the test with generated arguments, if the test accepts arguments.
+    ///
+    /// When the test fails, the `state` and `receipts` are those of the reported counterexample.
+    pub fuzz: Option<FuzzResult>,
 }
 
 /// The possible conditions for a test result to be considered "passing".
 #[derive(Debug)]
 pub enum TestPassCondition {
-    ShouldRevert,
+    /// The test must revert, optionally with the given revert code.
+    ShouldRevert(Option<u64>),
     ShouldNotRevert,
 }
 
 /// A package or a workspace that has been built, ready for test execution.
 pub enum BuiltTests {
-    Package(Box<pkg::BuiltPackage>),
-    Workspace(Vec<pkg::BuiltPackage>),
+    Package(Box<PackageTests>),
+    Workspace(Vec<PackageTests>),
+}
+
+/// A package that has been built with tests, along with the contracts that must be deployed prior
+/// to the execution of its tests.
+#[derive(Debug)]
+pub struct PackageTests {
+    /// The package built with all of its tests included.
+    pub built: pkg::BuiltPackage,
+    /// The contracts to deploy before executing tests, in order of deployment.
+    ///
+    /// This includes all of the package's contract dependencies followed by the package itself in
+    /// the case that it is a contract.
+    pub contracts: Vec<ContractToDeploy>,
+    /// The configuration of the transaction and VM environment in which each test is executed, as
+    /// declared under the `[test]` table of the package's manifest.
+    pub test_config: pkg::TestConfig,
+}
+
+/// A contract that is deployed to the in-memory VM storage prior to test execution.
+#[derive(Clone, Debug)]
+pub struct ContractToDeploy {
+    /// The contract, built without tests.
+    pub built: pkg::BuiltPackage,
+    /// The salt with which the contract is deployed.
+    pub salt: tx::Salt,
+}
+
+/// The state of the VM against which each test within a package is executed.
+#[derive(Clone, Debug, Default)]
+struct TestSetup {
+    /// The in-memory storage with all contracts deployed.
+    storage: vm::storage::MemoryStorage,
+    /// The IDs of the deployed contracts, provided as inputs to each test transaction.
+    contract_ids: Vec<tx::ContractId>,
+}
+
+/// Selects the subset of tests that are executed.
+#[derive(Clone, Debug, Default)]
+pub struct TestFilter {
+    /// When specified, only tests whose names contain this phrase are executed.
+    pub filter_phrase: Option<String>,
+    /// Only execute tests whose names match the `filter_phrase` exactly.
+    pub exact_match: bool,
+    /// Also execute the tests that are marked with the `#[test(ignore)]` attribute.
+    pub include_ignored: bool,
+}
+
+/// The number of threads used to execute tests.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum TestRunnerCount {
+    /// Execute tests using exactly the given number of threads.
+    Manual(usize),
+    /// Let the thread pool select the number of threads, i.e. one per available logical CPU.
+    #[default]
+    Auto,
 }
 
 /// The set of options provided to the `test` function.
@@ -93,37 +197,47 @@ impl Opts {
             release: self.release,
             time_phases: self.time_phases,
             tests: true,
+            no_cache: false,
         }
     }
 }
 
-impl TestResult {
-    /// Whether or not the test passed.
-    pub fn passed(&self) -> bool {
-        match &self.condition {
-            TestPassCondition::ShouldRevert => {
-                matches!(self.state, vm::state::ProgramState::Revert(_))
-            }
-            TestPassCondition::ShouldNotRevert => {
-                !matches!(self.state, vm::state::ProgramState::Revert(_))
+impl TestedPackage {
+    /// The executed and ignored tests of the package, in the order in which they are declared.
+    pub fn declared_tests(&self) -> Vec<DeclaredTest<'_>> {
+        // Both the executed and the ignored tests are already in order of declaration.
+        let mut tests = self.tests.iter().peekable();
+        let mut ignored = self.ignored.iter().peekable();
+        let mut declared = vec![];
+        for (entry, decl) in self.built.tests() {
+            if let Some(test) = tests.next_if(|test| test.name == entry.fn_name) {
+                declared.push(DeclaredTest::Executed(test));
+            } else if let Some(name) = ignored.next_if(|name| **name == entry.fn_name) {
+                declared.push(DeclaredTest::Ignored { name, decl });
             }
         }
+        declared
     }
+}
 
+impl DeclaredTest<'_> {
     /// Return `TestDetails` from the span of the function declaring this test.
     pub fn details(&self) -> anyhow::Result<TestDetails> {
-        let file_path = self
-            .span
+        match self {
+            DeclaredTest::Executed(test) => test.details(),
+            DeclaredTest::Ignored { decl, .. } => TestDetails::from_span(&decl.span()?),
+        }
+    }
+}
+
+impl TestDetails {
+    /// Locate the function declared at the given span.
+    fn from_span(span: &Span) -> anyhow::Result<Self> {
+        let file_path = span
             .path()
             .ok_or_else(|| anyhow::anyhow!("Missing span for test function"))?
             .to_owned();
-        let span_start = self.span.start();
-        let file_str = fs::read_to_string(&*file_path)?;
-        let line_number = file_str[..span_start]
-            .chars()
-            .into_iter()
-            .filter(|&c| c == '\n')
-            .count();
+        let line_number = span.start_pos().line_col().0;
         Ok(TestDetails {
             file_path,
             line_number,
@@ -131,178 +245,641 @@ impl TestResult {
     }
 }
 
+impl TestResult {
+    /// Whether or not the test passed.
+    pub fn passed(&self) -> bool {
+        self.condition.is_met(&self.state)
+    }
+
+    /// The revert code of the test, if the test reverted.
+    pub fn revert_code(&self) -> Option<u64> {
+        revert_code(&self.state)
+    }
+
+    /// The values logged by the test, decoded using the `logged_types` of the given program ABI.
+    ///
+    /// This should be the ABI of the package in which the test is declared.
+    pub fn logs(&self, program_abi: &fuels_types::ProgramABI) -> Vec<DecodedLog> {
+        decode_logs(&self.receipts, program_abi, &tx::ContractId::zeroed())
+    }
+
+    /// Return `TestDetails` from the span of the function declaring this test.
+    pub fn details(&self) -> anyhow::Result<TestDetails> {
+        TestDetails::from_span(&self.span)
+    }
+}
+
+impl TestPassCondition {
+    /// Whether or not a test that finished in the given state satisfies this condition.
+    fn is_met(&self, state: &vm::state::ProgramState) -> bool {
+        match self {
+            TestPassCondition::ShouldRevert(None) => revert_code(state).is_some(),
+            TestPassCondition::ShouldRevert(Some(expected)) => {
+                revert_code(state) == Some(*expected)
+            }
+            TestPassCondition::ShouldNotRevert => revert_code(state).is_none(),
+        }
+    }
+}
+
+impl TestFilter {
+    /// Whether or not the test with the given name is selected by the filter phrase.
+    fn matches(&self, test_name: &str) -> bool {
+        match &self.filter_phrase {
+            None => true,
+            Some(phrase) if self.exact_match => test_name == phrase,
+            Some(phrase) => test_name.contains(phrase.as_str()),
+        }
+    }
+}
+
 impl BuiltTests {
-    /// The total number of tests.
-    pub fn test_count(&self) -> usize {
-        let pkgs: Vec<&BuiltPackage> = match self {
+    /// The number of tests that will be executed given the test filter.
+    pub fn test_count(&self, test_filter: &TestFilter) -> anyhow::Result<usize> {
+        let pkgs: Vec<&PackageTests> = match self {
             BuiltTests::Package(pkg) => vec![pkg],
             BuiltTests::Workspace(workspace) => workspace.iter().collect(),
         };
-        pkgs.iter()
-            .map(|pkg| pkg.entries.iter().filter(|e| e.is_test()).count())
-            .sum()
+        let mut count = 0;
+        for pkg in pkgs {
+            for (entry, test_decl) in pkg.built.tests() {
+                if test_selected(entry, test_decl, test_filter)? {
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
     }
 
-    /// Run all built tests, return the result.
-    pub fn run(self) -> anyhow::Result<Tested> {
-        run_tests(self)
+    /// Run all built tests selected by the test filter, return the result.
+    ///
+    /// Tests are executed in parallel across a thread pool sized according to
+    /// `test_runner_count`. Results are always reported in the order in which tests are declared.
+    /// Tests that accept arguments are executed with arguments generated according to the
+    /// `fuzz_config`.
+    pub fn run(
+        self,
+        test_runner_count: TestRunnerCount,
+        test_filter: &TestFilter,
+        fuzz_config: &FuzzConfig,
+    ) -> anyhow::Result<Tested> {
+        let test_runners = match test_runner_count {
+            TestRunnerCount::Manual(runner_count) => rayon::ThreadPoolBuilder::new()
+                .num_threads(runner_count)
+                .build(),
+            TestRunnerCount::Auto => rayon::ThreadPoolBuilder::new().build(),
+        }?;
+        run_tests(self, test_filter, fuzz_config, &test_runners)
     }
 }
 
 /// First builds the package or workspace, ready for execution.
+///
+/// All contracts that must be deployed prior to executing the tests (i.e. contract members and
+/// the contract dependencies of all members) are provided as built without tests.
 pub fn build(opts: Opts) -> anyhow::Result<BuiltTests> {
     let build_opts = opts.into_build_opts();
-    let built_tests = match pkg::build_with_options(build_opts)? {
-        pkg::Built::Package(pkg) => BuiltTests::Package(pkg),
-        pkg::Built::Workspace(workspace) => {
-            BuiltTests::Workspace(workspace.values().cloned().collect())
+    let build_plan = pkg::BuildPlan::from_build_opts(&build_opts)?;
+    let (built, built_contracts) = pkg::build_with_plan(&build_plan, build_opts)?;
+    let graph = build_plan.graph();
+
+    let pkg_tests = |built: pkg::BuiltPackage| -> anyhow::Result<PackageTests> {
+        let member = build_plan
+            .find_member_index(&built.pkg_name)
+            .expect("built package is not a member of the build plan");
+        let mut deploy_nodes: HashSet<pkg::NodeIx> = build_plan.contract_deps(member).collect();
+        deploy_nodes.insert(member);
+        let mut contracts = vec![];
+        for node in build_plan.compilation_order() {
+            if !deploy_nodes.contains(node) {
+                continue;
+            }
+            if let Some(built) = built_contracts.get(node) {
+                let salt = pkg::contract_dep_salt(graph, *node)?.unwrap_or_else(tx::Salt::zeroed);
+                let built = built.clone();
+                contracts.push(ContractToDeploy { built, salt });
+            }
+        }
+        let manifest = &build_plan.manifest_map()[&graph[member].id()];
+        let test_config = manifest.test.clone().unwrap_or_default();
+        Ok(PackageTests {
+            built,
+            contracts,
+            test_config,
+        })
+    };
+
+    let built_tests = match built {
+        pkg::Built::Package(pkg) => BuiltTests::Package(Box::new(pkg_tests(*pkg)?)),
+        pkg::Built::Workspace(mut workspace) => {
+            // Produce the members in order of compilation for consistent output.
+            let members = build_plan
+                .member_nodes()
+                .filter_map(|member| workspace.remove(&graph[member].name))
+                .map(pkg_tests)
+                .collect::<anyhow::Result<_>>()?;
+            BuiltTests::Workspace(members)
         }
     };
     Ok(built_tests)
 }
 
-fn test_pass_condition(
-    test_function_decl: &TyFunctionDeclaration,
-) -> anyhow::Result<TestPassCondition> {
-    let test_args: HashSet<String> = test_function_decl
-        .attributes
-        .get(&AttributeKind::Test)
-        .expect("test declaration is missing test attribute")
+/// The arguments accepted by the `#[test]` attribute.
+const VALID_TEST_ARGS: &[&str] = &["should_revert", "ignore"];
+
+/// Collect and validate the arguments provided to the `#[test]` attribute of the given test.
+///
+/// Returns a map from each argument's name to its value, if any.
+fn test_args<'a>(
+    test_name: &str,
+    test_decl: &'a pkg::TestDecl,
+) -> anyhow::Result<HashMap<&'a str, Option<&'a str>>> {
+    let test_args: HashMap<&str, Option<&str>> = test_decl
+        .args
         .iter()
-        .flat_map(|attr| attr.args.iter().map(|arg| arg.to_string()))
+        .map(|(name, value)| (&name[..], value.as_deref()))
         .collect();
-    let test_name = &test_function_decl.name;
-    if test_args.is_empty() {
-        Ok(TestPassCondition::ShouldNotRevert)
-    } else if test_args.get("should_revert").is_some() {
-        Ok(TestPassCondition::ShouldRevert)
-    } else {
+    if test_args.keys().any(|arg| !VALID_TEST_ARGS.contains(arg)) {
         anyhow::bail!("Invalid test argument(s) for test: {test_name}.")
     }
+    Ok(test_args)
+}
+
+fn test_pass_condition(
+    test_name: &str,
+    test_decl: &pkg::TestDecl,
+) -> anyhow::Result<TestPassCondition> {
+    let test_args = test_args(test_name, test_decl)?;
+    match test_args.get("should_revert") {
+        None => Ok(TestPassCondition::ShouldNotRevert),
+        Some(None) => Ok(TestPassCondition::ShouldRevert(None)),
+        Some(Some(value)) => {
+            let revert_code = parse_revert_code(value).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid revert code for test: {test_name}. \
+                    Expected a `u64` value, e.g. `should_revert = \"42\"`."
+                )
+            })?;
+            Ok(TestPassCondition::ShouldRevert(Some(revert_code)))
+        }
+    }
+}
+
+/// Parse an expected revert code from the value of a `should_revert` argument.
+///
+/// The value may be either an integer literal or a string containing a decimal or `0x` prefixed
+/// hexadecimal integer.
+fn parse_revert_code(value: &str) -> Option<u64> {
+    let value = value.trim();
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Whether or not the given test is marked with `#[test(ignore)]`.
+fn test_ignored(test_name: &str, test_decl: &pkg::TestDecl) -> anyhow::Result<bool> {
+    let test_args = test_args(test_name, test_decl)?;
+    Ok(test_args.contains_key("ignore"))
+}
+
+/// Whether or not the given test entry is executed under the given test filter.
+fn test_selected(
+    entry: &FinalizedEntry,
+    test_decl: &pkg::TestDecl,
+    test_filter: &TestFilter,
+) -> anyhow::Result<bool> {
+    if !test_filter.matches(&entry.fn_name) {
+        return Ok(false);
+    }
+    let ignored = test_ignored(&entry.fn_name, test_decl)?;
+    Ok(test_filter.include_ignored || !ignored)
 }
 
 /// Build the the given package and run its tests, returning the results.
-fn run_tests(built: BuiltTests) -> anyhow::Result<Tested> {
+fn run_tests(
+    built: BuiltTests,
+    test_filter: &TestFilter,
+    fuzz_config: &FuzzConfig,
+    test_runners: &rayon::ThreadPool,
+) -> anyhow::Result<Tested> {
     match built {
         BuiltTests::Package(pkg) => {
-            let tested_pkg = run_pkg_tests(*pkg)?;
+            let tested_pkg = run_pkg_tests(*pkg, test_filter, fuzz_config, test_runners)?;
             Ok(Tested::Package(Box::new(tested_pkg)))
         }
         BuiltTests::Workspace(workspace) => {
-            let tested_pkgs = workspace
-                .into_iter()
-                .map(run_pkg_tests)
-                .collect::<anyhow::Result<Vec<TestedPackage>>>()?;
+            let tested_pkgs = test_runners.install(|| {
+                workspace
+                    .into_par_iter()
+                    .map(|pkg| run_pkg_tests(pkg, test_filter, fuzz_config, test_runners))
+                    .collect::<anyhow::Result<Vec<TestedPackage>>>()
+            })?;
             Ok(Tested::Workspace(tested_pkgs))
         }
     }
 }
 
-fn run_pkg_tests(built_pkg: BuiltPackage) -> anyhow::Result<TestedPackage> {
-    // Run all tests and collect their results.
-    // TODO: We can easily parallelise this, but let's wait until testing is stable first.
-    let tests = built_pkg
-        .entries
-        .iter()
-        .filter(|entry| entry.is_test())
-        .map(|entry| {
-            let offset = u32::try_from(entry.imm).expect("test instruction offset out of range");
-            let name = entry.fn_name.clone();
-            let (state, duration) = exec_test(&built_pkg.bytecode, offset);
-            let test_decl_id = entry
-                .test_decl_id
-                .clone()
-                .expect("test entry point is missing declaration id");
-            let span = test_decl_id.span();
-            let test_function_decl =
-                sway_core::declaration_engine::de_get_function(test_decl_id, &span)
-                    .expect("declaration engine is missing function declaration for test");
-            let condition = test_pass_condition(&test_function_decl)?;
-            Ok(TestResult {
-                name,
-                duration,
-                span,
-                state,
-                condition,
+fn run_pkg_tests(
+    pkg_tests: PackageTests,
+    test_filter: &TestFilter,
+    fuzz_config: &FuzzConfig,
+    test_runners: &rayon::ThreadPool,
+) -> anyhow::Result<TestedPackage> {
+    let PackageTests {
+        built: built_pkg,
+        contracts,
+        test_config,
+    } = pkg_tests;
+
+    // Deploy the package's contracts once, then provide a copy of the resulting state to each test.
+    let test_setup = deploy_test_contracts(&contracts, &test_config)?;
+
+    // Run all tests in parallel and collect their results. Each test is executed within its own
+    // interpreter, and `collect` on an indexed parallel iterator preserves declaration order.
+    let mut test_entries = vec![];
+    let mut ignored = vec![];
+    let mut filtered_out = 0;
+    for (entry, test_decl) in built_pkg.tests() {
+        if !test_filter.matches(&entry.fn_name) {
+            filtered_out += 1;
+        } else if !test_filter.include_ignored && test_ignored(&entry.fn_name, test_decl)? {
+            ignored.push(entry.fn_name.clone());
+        } else {
+            test_entries.push((entry, test_decl));
+        }
+    }
+    let tests = test_runners.install(|| {
+        test_entries
+            .into_par_iter()
+            .map(|(entry, test_decl)| {
+                let name = entry.fn_name.clone();
+                let bytecode = patch_entry_bytecode(&built_pkg, entry.imm)?;
+                let span = test_decl.span()?;
+                let condition = test_pass_condition(&name, test_decl)?;
+                let params = match &built_pkg.json_abi_tests {
+                    Some(test_abi) => Some(fuzz::TestParams::from_abi(test_abi, &name)?),
+                    None => None,
+                };
+
+                // Tests that accept arguments are executed many times with generated arguments.
+                if let Some(params) = params.filter(|params| !params.is_empty()) {
+                    let fuzzed = fuzz::fuzz_test(
+                        &name,
+                        &params,
+                        &bytecode,
+                        &test_setup,
+                        &test_config,
+                        &condition,
+                        fuzz_config,
+                    )?;
+                    return Ok(TestResult {
+                        name,
+                        duration: fuzzed.duration,
+                        gas_used: fuzzed.gas_used,
+                        span,
+                        state: fuzzed.state,
+                        receipts: fuzzed.receipts,
+                        condition,
+                        fuzz: Some(fuzzed.result),
+                    });
+                }
+
+                let (state, duration, receipts) =
+                    exec_test(&bytecode, vec![], &test_setup, &test_config)?;
+                let gas_used = script_gas_used(&receipts);
+                Ok(TestResult {
+                    name,
+                    duration,
+                    gas_used,
+                    span,
+                    state,
+                    receipts,
+                    condition,
+                    fuzz: None,
+                })
             })
-        })
-        .collect::<anyhow::Result<_>>()?;
+            .collect::<anyhow::Result<_>>()
+    })?;
 
     let tested_pkg = TestedPackage {
         built: Box::new(built_pkg),
         tests,
+        ignored,
+        filtered_out,
     };
 
     Ok(tested_pkg)
 }
 
-/// Given some bytecode and an instruction offset for some test's desired entry point, patch the
-/// bytecode with a `JI` (jump) instruction to jump to the desired test.
+/// Given a built package and the instruction offset of one of its entry points, patch the
+/// package's bytecode such that execution begins at the given entry point.
 ///
-/// We want to splice in the `JI` only after the initial data section setup is complete, and only
-/// if the entry point doesn't begin exactly after the data section setup.
-///
-/// The following is how the beginning of the bytecode is laid out:
-///
-/// ```ignore
-/// [0] ji   i4                       ; Jumps to the data section setup.
-/// [1] noop
-/// [2] DATA_SECTION_OFFSET[0..32]
-/// [3] DATA_SECTION_OFFSET[32..64]
-/// [4] lw   $ds $is 1                ; The data section setup, i.e. where the first ji lands.
-/// [5] add  $$ds $$ds $is
-/// [6] <first-entry-point>           ; This is where we want to jump from to our test code!
-/// ```
-fn patch_test_bytecode(bytecode: &[u8], test_offset: u32) -> std::borrow::Cow<[u8]> {
-    // TODO: Standardize this or add metadata to bytecode.
-    const PROGRAM_START_INST_OFFSET: u32 = 6;
-    const PROGRAM_START_BYTE_OFFSET: usize = PROGRAM_START_INST_OFFSET as usize * Opcode::LEN;
+/// Once the preamble has set up the data section register, execution continues at the instruction
+/// offset reported by the compiler as the package's `preamble_len`. Unless the desired entry point
+/// begins exactly there, the instruction at that offset is replaced with a `JI` (jump) to the entry
+/// point. This is how each test is executed, though any other entry may be targeted in the same
+/// manner, e.g. for benchmarking.
+pub fn patch_entry_bytecode(
+    built: &pkg::BuiltPackage,
+    entry_offset: u64,
+) -> anyhow::Result<std::borrow::Cow<'_, [u8]>> {
+    // The largest instruction offset that may be encoded within the `JI` immediate.
+    const MAX_JI_OFFSET: u64 = (1 << 24) - 1;
 
     // If our desired entry point is the program start, no need to jump.
-    if test_offset == PROGRAM_START_INST_OFFSET {
-        return std::borrow::Cow::Borrowed(bytecode);
+    if entry_offset == built.preamble_len {
+        return Ok(std::borrow::Cow::Borrowed(&built.bytecode));
+    }
+    if entry_offset > MAX_JI_OFFSET {
+        anyhow::bail!("entry point offset {entry_offset} is out of range of the `JI` instruction");
     }
 
     // Create the jump instruction and splice it into the bytecode.
-    let ji = Opcode::JI(test_offset);
+    let ji = Opcode::JI(entry_offset as vm::prelude::Immediate24);
     let ji_bytes = ji.to_bytes();
-    let start = PROGRAM_START_BYTE_OFFSET;
+    let start = usize::try_from(built.preamble_len)? * Opcode::LEN;
     let end = start + ji_bytes.len();
-    let mut patched = bytecode.to_vec();
+    if end > built.bytecode.len() {
+        anyhow::bail!(
+            "preamble length of {} instructions exceeds the bytecode of `{}`",
+            built.preamble_len,
+            built.pkg_name
+        );
+    }
+    let mut patched = built.bytecode.clone();
     patched.splice(start..end, ji_bytes);
-    std::borrow::Cow::Owned(patched)
+    Ok(std::borrow::Cow::Owned(patched))
 }
 
-// Execute the test whose entry point is at the given instruction offset as if it were a script.
-fn exec_test(bytecode: &[u8], test_offset: u32) -> (vm::state::ProgramState, std::time::Duration) {
-    // Patch the bytecode to jump to the relevant test.
-    let bytecode = patch_test_bytecode(bytecode, test_offset).into_owned();
+/// Deploy the given contracts in order to a fresh in-memory storage.
+///
+/// The storage is initialized at the block height declared by the `TestConfig` if there is one.
+fn deploy_test_contracts(
+    contracts: &[ContractToDeploy],
+    test_config: &pkg::TestConfig,
+) -> anyhow::Result<TestSetup> {
+    let mut test_setup = TestSetup::default();
+    if let Some(block_height) = test_config.block_height {
+        test_setup.storage = vm::storage::MemoryStorage::new(block_height, Default::default());
+    }
+    for contract in contracts {
+        let ContractToDeploy { built, salt } = contract;
+        let (contract_id, storage) = deploy_test_contract(
+            &built.pkg_name,
+            &built.bytecode,
+            &built.storage_slots,
+            salt,
+            test_setup.storage,
+            test_config,
+        )?;
+        test_setup.storage = storage;
+        test_setup.contract_ids.push(contract_id);
+    }
+    Ok(test_setup)
+}
 
-    // Create a transaction to execute the test function.
-    let script_input_data = vec![];
-    let mut rng = rand::rngs::StdRng::seed_from_u64(0x7E57u64);
-    let maturity = 1;
-    let block_height = (u32::MAX >> 1) as u64;
+/// Deploy the given contract to the given storage, returning its ID and the resulting storage.
+///
+/// The deployment is executed at the same block height and with the same maturity as the tests,
+/// according to the given `TestConfig`.
+fn deploy_test_contract(
+    pkg_name: &str,
+    bytecode: &[u8],
+    storage_slots: &[tx::StorageSlot],
+    salt: &tx::Salt,
+    storage: vm::storage::MemoryStorage,
+    test_config: &pkg::TestConfig,
+) -> anyhow::Result<(tx::ContractId, vm::storage::MemoryStorage)> {
+    // Determine the contract ID.
+    let mut storage_slots = storage_slots.to_vec();
+    storage_slots.sort();
+    let bytecode = bytecode.to_vec();
+    let contract = tx::Contract::from(bytecode.clone());
+    let root = contract.root();
+    let state_root = tx::Contract::initial_state_root(storage_slots.iter());
+    let contract_id = contract.id(salt, &root, &state_root);
+
+    // Create a transaction to deploy the contract.
+    let mut rng = rand::rngs::StdRng::seed_from_u64(TEST_METADATA_SEED);
     let secret_key = rng.gen();
     let utxo_id = rng.gen();
     let amount = 1;
     let asset_id = Default::default();
     let tx_ptr = rng.gen();
     let params = tx::ConsensusParameters::default();
-    let tx = tx::TransactionBuilder::script(bytecode, script_input_data)
+    let tx = tx::TransactionBuilder::create(bytecode.into(), *salt, storage_slots)
         .add_unsigned_coin_input(secret_key, utxo_id, amount, asset_id, tx_ptr, 0)
-        .gas_limit(tx::ConsensusParameters::DEFAULT.max_gas_per_tx)
-        .maturity(maturity)
-        .finalize_checked(block_height as tx::Word, &params);
+        .add_output(tx::Output::contract_created(contract_id, state_root))
+        .maturity(test_maturity(test_config))
+        .finalize()
+        .into_checked(test_block_height(test_config), &params)
+        .map_err(|e| anyhow::anyhow!("invalid deployment of contract `{pkg_name}`: {e}"))?;
+
+    // Deploy the contract.
+    let mut interpreter = vm::interpreter::Interpreter::with_storage(storage, params);
+    interpreter
+        .transact(tx)
+        .map_err(|e| anyhow::anyhow!("failed to deploy contract `{pkg_name}` for testing: {e}"))?;
+    let storage = interpreter.as_ref().clone();
+    Ok((contract_id, storage))
+}
+
+// Execute the given bytecode, patched to begin at the entry point of a test, as if it were a script.
+//
+// The test transaction and VM environment are constructed according to the given `TestConfig`.
+// The `script_data` contains the ABI-encoded arguments of the test, if any.
+fn exec_test(
+    bytecode: &[u8],
+    script_data: Vec<u8>,
+    test_setup: &TestSetup,
+    test_config: &pkg::TestConfig,
+) -> anyhow::Result<(
+    vm::state::ProgramState,
+    std::time::Duration,
+    Vec<tx::Receipt>,
+)> {
+    // Create a transaction to execute the test function.
+    let bytecode = bytecode.to_vec();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(TEST_METADATA_SEED);
+    let secret_key = rng.gen();
+    let params = tx::ConsensusParameters::default();
+    let mut tx = tx::TransactionBuilder::script(bytecode, script_data);
+    tx.gas_limit(test_config.gas_limit.unwrap_or(params.max_gas_per_tx))
+        .gas_price(test_config.gas_price.unwrap_or_default())
+        .maturity(test_maturity(test_config));
+
+    // Provide the configured coins as inputs, or a single coin of the base asset by default.
+    let default_coins = [pkg::manifest::TestCoin {
+        amount: 1,
+        asset_id: Default::default(),
+    }];
+    let coins = test_config.coins.as_deref().unwrap_or(&default_coins);
+    for coin in coins {
+        let utxo_id = rng.gen();
+        let tx_ptr = rng.gen();
+        tx.add_unsigned_coin_input(secret_key, utxo_id, coin.amount, coin.asset_id, tx_ptr, 0);
+    }
+
+    for output in test_config.outputs.iter().flatten() {
+        let output = match *output {
+            pkg::manifest::TestOutput::Coin {
+                to,
+                amount,
+                asset_id,
+            } => tx::Output::coin(to, amount, asset_id),
+            pkg::manifest::TestOutput::Change { to, asset_id } => {
+                tx::Output::change(to, 0, asset_id)
+            }
+            pkg::manifest::TestOutput::Variable => {
+                tx::Output::variable(tx::Address::zeroed(), 0, tx::AssetId::zeroed())
+            }
+        };
+        tx.add_output(output);
+    }
+
+    // Provide all deployed contracts as inputs so that the test may call into them.
+    for &contract_id in &test_setup.contract_ids {
+        let input_index = tx.inputs().len().try_into().map_err(|_| {
+            anyhow::anyhow!(
+                "invalid test transaction: the {} configured coins and {} contracts exceed the \
+                limit of 256 inputs",
+                coins.len(),
+                test_setup.contract_ids.len()
+            )
+        })?;
+        tx.add_input(tx::Input::contract(
+            tx::UtxoId::new(tx::Bytes32::zeroed(), 0),
+            tx::Bytes32::zeroed(),
+            tx::Bytes32::zeroed(),
+            tx::TxPointer::new(0, 0),
+            contract_id,
+        ))
+        .add_output(tx::Output::contract(
+            input_index,
+            tx::Bytes32::zeroed(),
+            tx::Bytes32::zeroed(),
+        ));
+    }
+    let tx = tx
+        .finalize()
+        .into_checked(test_block_height(test_config), &params)
+        .map_err(|e| anyhow::anyhow!("invalid test transaction: {e}"))?;
 
     // Setup the interpreter.
-    let storage = vm::storage::MemoryStorage::default();
+    let storage = test_setup.storage.clone();
     let mut interpreter = vm::interpreter::Interpreter::with_storage(storage, params);
 
     // Execute and return the result.
     let start = std::time::Instant::now();
-    let transition = interpreter.transact(tx).unwrap();
+    let transition = interpreter
+        .transact(tx)
+        .map_err(|e| anyhow::anyhow!("failed to execute test transaction: {e}"))?;
     let duration = start.elapsed();
     let state = *transition.state();
-    (state, duration)
+    let receipts = transition.receipts().to_vec();
+    Ok((state, duration, receipts))
+}
+
+/// The block height at which deployments and tests are executed according to the `TestConfig`.
+fn test_block_height(test_config: &pkg::TestConfig) -> tx::Word {
+    test_config
+        .block_height
+        .map(tx::Word::from)
+        .unwrap_or(TEST_BLOCK_HEIGHT)
+}
+
+/// The maturity of the deployment and test transactions according to the `TestConfig`.
+fn test_maturity(test_config: &pkg::TestConfig) -> tx::Word {
+    test_config.maturity.unwrap_or(TEST_MATURITY)
+}
+
+/// The revert code of a program that finished in the given state, if it reverted.
+fn revert_code(state: &vm::state::ProgramState) -> Option<u64> {
+    match state {
+        vm::state::ProgramState::Revert(revert_code) => Some(*revert_code),
+        _ => None,
+    }
+}
+
+/// The gas used by a script, as reported by the `ScriptResult` receipt of its execution.
+fn script_gas_used(receipts: &[tx::Receipt]) -> u64 {
+    receipts
+        .iter()
+        .find_map(tx::Receipt::gas_used)
+        .expect("script execution must produce a `ScriptResult` receipt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fuel_vm::prelude::InterpreterStorage;
+
+    fn deploy(test_config: &pkg::TestConfig) -> anyhow::Result<vm::storage::MemoryStorage> {
+        let bytecode: Vec<u8> = vec![Opcode::RET(vm::consts::REG_ONE)].into_iter().collect();
+        let storage = deploy_test_contracts(&[], test_config)?.storage;
+        let (_, storage) = deploy_test_contract(
+            "foo",
+            &bytecode,
+            &[],
+            &tx::Salt::zeroed(),
+            storage,
+            test_config,
+        )?;
+        Ok(storage)
+    }
+
+    #[test]
+    fn test_deploy_with_default_config() {
+        deploy(&Default::default()).unwrap();
+    }
+
+    #[test]
+    fn test_deploy_with_test_config() {
+        let test_config = pkg::TestConfig {
+            block_height: Some(100),
+            maturity: Some(50),
+            ..Default::default()
+        };
+        let storage = deploy(&test_config).unwrap();
+        assert_eq!(storage.block_height().unwrap(), 100);
+
+        // The deployment is subject to the same maturity as the tests.
+        let test_config = pkg::TestConfig {
+            maturity: Some(101),
+            ..test_config
+        };
+        let err = deploy(&test_config).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid deployment of contract `foo`"));
+    }
+
+    #[test]
+    fn test_details_line_number() {
+        let src = "library foo;\n\n#[test]\nfn test_foo() {}\n";
+        let path = Arc::new(PathBuf::from("src/lib.sw"));
+        let start = src.find("#[test]").unwrap();
+        let span = Span::new(src.into(), start, src.len(), Some(path.clone())).unwrap();
+        let details = TestDetails::from_span(&span).unwrap();
+        assert_eq!(details.file_path, path);
+        assert_eq!(details.line_number, 3);
+    }
+
+    #[test]
+    fn test_exec_with_too_many_inputs() {
+        let bytecode: Vec<u8> = vec![Opcode::RET(vm::consts::REG_ONE)].into_iter().collect();
+        let coin = pkg::manifest::TestCoin {
+            amount: 1,
+            asset_id: Default::default(),
+        };
+        let test_config = pkg::TestConfig {
+            coins: Some(vec![coin; 256]),
+            ..Default::default()
+        };
+        let mut test_setup = deploy_test_contracts(&[], &test_config).unwrap();
+        test_setup.contract_ids.push(tx::ContractId::zeroed());
+        let err = exec_test(&bytecode, vec![], &test_setup, &test_config).unwrap_err();
+        assert!(err.to_string().contains("exceed the limit of 256 inputs"));
+    }
 }
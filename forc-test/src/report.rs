@@ -0,0 +1,298 @@
+//! Machine-readable reports of the results of a `forc test` invocation.
+
+use crate::{DeclaredTest, Tested, TestedPackage};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, path::PathBuf};
+
+/// The outcome of a single test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// A serializable summary of a single test, including tests that were ignored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TestCaseReport {
+    /// The name of the package in which the test is declared.
+    pub package: String,
+    /// The name of the test function.
+    pub name: String,
+    pub status: TestStatus,
+    /// The file that contains the test function, if known.
+    pub file_path: Option<PathBuf>,
+    /// The line number of the test declaration, starting from 1, if known.
+    pub line_number: Option<usize>,
+    /// The time taken for the test to execute in seconds. `None` for ignored tests.
+    pub duration_secs: Option<f64>,
+    /// The gas consumed while executing the test. `None` for ignored tests.
+    pub gas_used: Option<u64>,
+    /// The revert code of the test, if the test reverted.
+    pub revert_code: Option<u64>,
+    /// The number of sets of generated arguments with which the test was executed. `None` for
+    /// tests that do not accept arguments.
+    pub fuzz_runs: Option<u64>,
+    /// The simplest arguments found for which the test fails, if the test accepts arguments and
+    /// failed.
+    pub counterexample: Option<String>,
+}
+
+impl TestCaseReport {
+    /// Produce a report for every executed and ignored test, in the order in which they are
+    /// declared.
+    pub fn from_tested(tested: &Tested) -> anyhow::Result<Vec<Self>> {
+        let pkgs: Vec<&TestedPackage> = match tested {
+            Tested::Package(pkg) => vec![pkg],
+            Tested::Workspace(pkgs) => pkgs.iter().collect(),
+        };
+        let mut reports = vec![];
+        for pkg in pkgs {
+            let package = &pkg.built.pkg_name;
+            for declared_test in pkg.declared_tests() {
+                let details = declared_test.details()?;
+                let test = match declared_test {
+                    DeclaredTest::Executed(test) => test,
+                    DeclaredTest::Ignored { name, .. } => {
+                        reports.push(Self {
+                            package: package.clone(),
+                            name: name.to_string(),
+                            status: TestStatus::Ignored,
+                            file_path: Some((*details.file_path).clone()),
+                            line_number: Some(details.line_number),
+                            duration_secs: None,
+                            gas_used: None,
+                            revert_code: None,
+                            fuzz_runs: None,
+                            counterexample: None,
+                        });
+                        continue;
+                    }
+                };
+                let status = match test.passed() {
+                    true => TestStatus::Passed,
+                    false => TestStatus::Failed,
+                };
+                reports.push(Self {
+                    package: package.clone(),
+                    name: test.name.clone(),
+                    status,
+                    file_path: Some((*details.file_path).clone()),
+                    line_number: Some(details.line_number),
+                    duration_secs: Some(test.duration.as_secs_f64()),
+                    gas_used: Some(test.gas_used),
+                    revert_code: test.revert_code(),
+                    fuzz_runs: test.fuzz.as_ref().map(|fuzz| fuzz.runs),
+                    counterexample: test
+                        .fuzz
+                        .as_ref()
+                        .and_then(|fuzz| fuzz.counterexample.as_ref())
+                        .map(|counterexample| counterexample.to_string()),
+                });
+            }
+        }
+        Ok(reports)
+    }
+}
+
+/// Render the given test reports as a JUnit XML document with one `<testsuite>` per package.
+pub fn junit_xml(reports: &[TestCaseReport]) -> String {
+    // Group the reports by package, preserving the order of first appearance.
+    let mut suites: Vec<(&str, Vec<&TestCaseReport>)> = vec![];
+    for report in reports {
+        match suites.iter_mut().find(|(pkg, _)| *pkg == report.package) {
+            Some((_, tests)) => tests.push(report),
+            None => suites.push((&report.package, vec![report])),
+        }
+    }
+
+    let count =
+        |tests: &[&TestCaseReport], status| tests.iter().filter(|t| t.status == status).count();
+    let time =
+        |tests: &[&TestCaseReport]| -> f64 { tests.iter().filter_map(|t| t.duration_secs).sum() };
+
+    let all: Vec<&TestCaseReport> = reports.iter().collect();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.6}\">",
+        all.len(),
+        count(&all, TestStatus::Failed),
+        count(&all, TestStatus::Ignored),
+        time(&all),
+    );
+    for (pkg, tests) in &suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.6}\">",
+            escape_xml(pkg),
+            tests.len(),
+            count(tests, TestStatus::Failed),
+            count(tests, TestStatus::Ignored),
+            time(tests),
+        );
+        for test in tests {
+            let mut attrs = format!(
+                "name=\"{}\" classname=\"{}\"",
+                escape_xml(&test.name),
+                escape_xml(pkg)
+            );
+            if let Some(file_path) = &test.file_path {
+                let _ = write!(
+                    attrs,
+                    " file=\"{}\"",
+                    escape_xml(&file_path.to_string_lossy())
+                );
+            }
+            if let Some(line_number) = test.line_number {
+                let _ = write!(attrs, " line=\"{line_number}\"");
+            }
+            let _ = write!(
+                attrs,
+                " time=\"{:.6}\"",
+                test.duration_secs.unwrap_or_default()
+            );
+            let _ = writeln!(xml, "    <testcase {attrs}>");
+            if let Some(gas_used) = test.gas_used {
+                xml.push_str("      <properties>\n");
+                let _ = writeln!(
+                    xml,
+                    "        <property name=\"gas_used\" value=\"{gas_used}\"/>"
+                );
+                xml.push_str("      </properties>\n");
+            }
+            match test.status {
+                TestStatus::Passed => (),
+                TestStatus::Failed => {
+                    let mut message = match test.revert_code {
+                        Some(revert_code) => format!("reverted with revert code {revert_code}"),
+                        None => "did not revert".to_string(),
+                    };
+                    if let Some(counterexample) = &test.counterexample {
+                        let _ = write!(message, " with counterexample: {counterexample}");
+                    }
+                    let _ = writeln!(xml, "      <failure message=\"{}\"/>", escape_xml(&message));
+                }
+                TestStatus::Ignored => xml.push_str("      <skipped/>\n"),
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{junit_xml, TestCaseReport, TestStatus};
+    use std::path::PathBuf;
+
+    fn report(package: &str, name: &str, status: TestStatus) -> TestCaseReport {
+        let executed = status != TestStatus::Ignored;
+        TestCaseReport {
+            package: package.to_string(),
+            name: name.to_string(),
+            status,
+            file_path: Some(PathBuf::from("src/main.sw")),
+            line_number: Some(4),
+            duration_secs: executed.then_some(0.5),
+            gas_used: executed.then_some(100),
+            revert_code: None,
+            fuzz_runs: None,
+            counterexample: None,
+        }
+    }
+
+    #[test]
+    fn test_json_report() {
+        let passed = report("pkg", "test_a", TestStatus::Passed);
+        let json = serde_json::to_string(&passed).unwrap();
+        assert_eq!(
+            json,
+            r#"{"package":"pkg","name":"test_a","status":"passed","file_path":"src/main.sw","line_number":4,"duration_secs":0.5,"gas_used":100,"revert_code":null,"fuzz_runs":null,"counterexample":null}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<TestCaseReport>(&json).unwrap(),
+            passed
+        );
+
+        let ignored = report("pkg", "test_b", TestStatus::Ignored);
+        let json = serde_json::to_string(&ignored).unwrap();
+        assert!(json.contains(
+            r#""status":"ignored","file_path":"src/main.sw","line_number":4,"duration_secs":null"#
+        ));
+        assert_eq!(
+            serde_json::from_str::<TestCaseReport>(&json).unwrap(),
+            ignored
+        );
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let mut reverted = report("pkg", "test_b", TestStatus::Failed);
+        reverted.revert_code = Some(42);
+        let mut fuzzed = report("other", "test_c", TestStatus::Failed);
+        fuzzed.fuzz_runs = Some(256);
+        fuzzed.counterexample = Some("a = 1, s = \"<&>\"".to_string());
+        let reports = [
+            report("pkg", "test_a", TestStatus::Passed),
+            fuzzed,
+            reverted,
+            report("pkg", "test_d", TestStatus::Ignored),
+        ];
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="4" failures="2" skipped="1" time="1.500000">
+  <testsuite name="pkg" tests="3" failures="1" skipped="1" time="1.000000">
+    <testcase name="test_a" classname="pkg" file="src/main.sw" line="4" time="0.500000">
+      <properties>
+        <property name="gas_used" value="100"/>
+      </properties>
+    </testcase>
+    <testcase name="test_b" classname="pkg" file="src/main.sw" line="4" time="0.500000">
+      <properties>
+        <property name="gas_used" value="100"/>
+      </properties>
+      <failure message="reverted with revert code 42"/>
+    </testcase>
+    <testcase name="test_d" classname="pkg" file="src/main.sw" line="4" time="0.000000">
+      <skipped/>
+    </testcase>
+  </testsuite>
+  <testsuite name="other" tests="1" failures="1" skipped="0" time="0.500000">
+    <testcase name="test_c" classname="other" file="src/main.sw" line="4" time="0.500000">
+      <properties>
+        <property name="gas_used" value="100"/>
+      </properties>
+      <failure message="did not revert with counterexample: a = 1, s = &quot;&lt;&amp;&gt;&quot;"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#;
+        assert_eq!(junit_xml(&reports), expected);
+    }
+
+    #[test]
+    fn test_junit_xml_escapes_names() {
+        let reports = [report("p&q", "test_<'a'>", TestStatus::Passed)];
+        let xml = junit_xml(&reports);
+        assert!(xml.contains(r#"<testsuite name="p&amp;q""#));
+        assert!(xml.contains(r#"<testcase name="test_&lt;&apos;a&apos;&gt;" classname="p&amp;q""#));
+    }
+}
@@ -113,8 +113,16 @@ pub fn default_output_directory(manifest_dir: &Path) -> PathBuf {
     manifest_dir.join(DEFAULT_OUTPUT_DIRECTORY)
 }
 
+/// The environment variable that, if set, overrides the location of the user's `.forc` directory.
+pub const FORC_HOME_ENV_VAR: &str = "FORC_HOME";
+
 /// Returns the user's `.forc` directory, `$HOME/.forc` by default.
+///
+/// The location may be overridden with the `FORC_HOME` environment variable.
 pub fn user_forc_directory() -> PathBuf {
+    if let Some(forc_home) = std::env::var_os(FORC_HOME_ENV_VAR) {
+        return PathBuf::from(forc_home);
+    }
     dirs::home_dir()
         .expect("unable to find the user home directory")
         .join(constants::USER_FORC_DIRECTORY)
@@ -125,6 +133,11 @@ pub fn git_checkouts_directory() -> PathBuf {
     user_forc_directory().join("git").join("checkouts")
 }
 
+/// The location at which `forc` will store registry indices and fetched registry packages.
+pub fn registry_directory() -> PathBuf {
+    user_forc_directory().join("registry")
+}
+
 pub fn print_on_success(
     terse_mode: bool,
     proj_name: &str,
@@ -159,6 +172,19 @@ pub fn print_on_success(
     }
 }
 
+/// Print that the given package was loaded from the build cache rather than compiled.
+pub fn print_on_fresh(terse_mode: bool, proj_name: &str, tree_type: &TreeType) {
+    let type_str = match &tree_type {
+        TreeType::Script => "script",
+        TreeType::Contract => "contract",
+        TreeType::Predicate => "predicate",
+        TreeType::Library { .. } => "library",
+    };
+    if !terse_mode {
+        println_green_err(&format!("  Fresh {} {:?}.", type_str, proj_name));
+    }
+}
+
 pub fn print_on_success_library(terse_mode: bool, proj_name: &str, warnings: &[CompileWarning]) {
     if !terse_mode {
         warnings.iter().for_each(format_warning);
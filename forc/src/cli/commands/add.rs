@@ -0,0 +1,60 @@
+use crate::ops::forc_add;
+use anyhow::Result;
+use clap::Parser;
+
+/// Add a dependency to the `Forc.toml` of the current or target package.
+///
+/// The dependency is added to `[dependencies]`, or to `[contract-dependencies]` if
+/// `--contract-dep` is specified. If the dependency is already declared, its declaration is
+/// replaced. The formatting and comments of the existing manifest are preserved.
+///
+/// Once the manifest is edited, the dependency graph is resolved and `Forc.lock` is updated. If
+/// either the edited manifest is invalid or the dependency cannot be resolved, the manifest is
+/// left unchanged.
+#[derive(Debug, Default, Parser)]
+pub struct Command {
+    /// The name of the dependency.
+    pub name: String,
+    /// Path to the dependency's package, relative to the directory of the manifest being edited.
+    #[clap(long, conflicts_with_all = &["git", "version"])]
+    pub path: Option<String>,
+    /// URL of the git repository containing the dependency.
+    #[clap(long, conflicts_with = "version")]
+    pub git: Option<String>,
+    /// Git branch of the dependency.
+    #[clap(long, requires = "git", conflicts_with_all = &["tag", "rev"])]
+    pub branch: Option<String>,
+    /// Git tag of the dependency.
+    #[clap(long, requires = "git", conflicts_with = "rev")]
+    pub tag: Option<String>,
+    /// Git commit of the dependency.
+    #[clap(long, requires = "git")]
+    pub rev: Option<String>,
+    /// Semver requirement for a dependency fetched from a package registry.
+    #[clap(long = "version", value_name = "VERSION_REQ")]
+    pub version: Option<String>,
+    /// Location of the registry index from which the dependency is fetched. If not specified,
+    /// the default registry is used.
+    #[clap(long, requires = "version")]
+    pub registry: Option<String>,
+    /// The name of the package within the source, if it differs from `name`.
+    #[clap(long)]
+    pub package: Option<String>,
+    /// Add the dependency to `[contract-dependencies]` rather than `[dependencies]`.
+    #[clap(long)]
+    pub contract_dep: bool,
+    /// The salt used when computing the contract ID of a contract dependency.
+    #[clap(long, requires = "contract-dep")]
+    pub salt: Option<String>,
+    /// Path to the package to edit, if not specified, current working directory will be used.
+    #[clap(long)]
+    pub manifest_path: Option<String>,
+    /// Offline mode, prevents Forc from using the network when managing dependencies.
+    /// Meaning it will only try to use previously downloaded dependencies.
+    #[clap(long = "offline")]
+    pub offline_mode: bool,
+}
+
+pub(crate) fn exec(command: Command) -> Result<()> {
+    forc_add::add(command)
+}
@@ -16,6 +16,12 @@ use clap::Parser;
 ///
 /// - `contract` and `library` projects will also produce the public ABI in JSON format
 /// `<project-name>-abi.json`.
+///
+/// Compiled packages are cached under `~/.forc/cache/build`. A package is loaded from the cache
+/// rather than compiled if its sources, dependencies, build profile and compiler are unchanged
+/// since it was last built. Library dependencies are still recompiled whenever a package that
+/// depends on them must be compiled, and packages that compile with warnings are never cached.
+/// Use `--no-cache` to build every package from source.
 #[derive(Debug, Default, Parser)]
 pub struct Command {
     #[clap(flatten)]
@@ -23,6 +29,9 @@ pub struct Command {
     /// Also build all tests within the project.
     #[clap(long)]
     pub tests: bool,
+    /// Build every package from source without loading from or writing to the build cache.
+    #[clap(long)]
+    pub no_cache: bool,
 }
 
 pub(crate) fn exec(command: Command) -> Result<()> {
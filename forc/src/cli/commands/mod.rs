@@ -1,3 +1,4 @@
+pub mod add;
 pub mod addr2line;
 pub mod build;
 pub mod check;
@@ -7,6 +8,10 @@ pub mod init;
 pub mod new;
 pub mod parse_bytecode;
 pub mod plugins;
+pub mod remove;
 pub mod template;
 pub mod test;
+pub mod tree;
 pub mod update;
+pub mod vendor;
+pub mod verify;
@@ -3,7 +3,8 @@ use ansi_term::Colour;
 use anyhow::{bail, Result};
 use clap::Parser;
 use forc_pkg as pkg;
-use forc_test::TestedPackage;
+use forc_test::{DeclaredTest, GasSnapshot, TestCaseReport, TestResult, TestedPackage};
+use std::path::PathBuf;
 use tracing::info;
 
 /// Run the Sway unit tests for the current project.
@@ -17,69 +18,220 @@ use tracing::info;
 /// a unique entry point for a single program and has access to the namespace of the module in
 /// which it is declared.
 ///
-/// Unit tests decorated with the `#[test(script)]` attribute that are declared within `contract`
-/// projects may also call directly into their associated contract's ABI.
+/// Prior to executing the tests, `contract` packages are deployed to an in-memory VM storage along
+/// with all of their `[contract-dependencies]`, allowing tests to call into these contracts by ID.
 ///
 /// Upon successful compilation, test scripts are executed to their completion. A test is
 /// considered a failure in the case that a revert (`rvrt`) instruction is encountered during
 /// execution. Otherwise, it is considered a success.
+///
+/// Tests decorated with `#[test(ignore)]` are not executed unless `--include-ignored` is
+/// specified.
+///
+/// Values logged by a test are decoded using the package's ABI and printed when the test fails, or
+/// for every test when `--logs` is specified.
+///
+/// Test results are printed in a human readable format by default. Alternatively, `--format json`
+/// prints one JSON object per test, while `--format junit` prints a JUnit XML report.
+///
+/// Tests that accept arguments are executed many times with arguments generated from the types of
+/// their parameters. When such a test fails, its arguments are shrunk to the simplest failing
+/// arguments that can be found, which are then reported as a counterexample.
+///
+/// The gas used by each test may be recorded to a snapshot file with `--gas-snapshot`. Running
+/// with `--check-gas-snapshot` compares the gas used by each test against a previously recorded
+/// snapshot and fails if any test's gas usage differs, e.g. to catch gas regressions in CI.
 #[derive(Debug, Parser)]
 pub struct Command {
     #[clap(flatten)]
     pub build: cli::shared::Build,
     /// When specified, only tests containing the given string will be executed.
     pub filter: Option<String>,
+    /// When specified, only the test exactly matching the given string will be executed.
+    #[clap(long)]
+    pub exact: bool,
+    /// Also run the tests that are marked with `#[test(ignore)]`.
+    #[clap(long)]
+    pub include_ignored: bool,
+    /// Number of threads to utilize when running the tests. By default, this is the number of
+    /// threads available in your system.
+    #[clap(long)]
+    pub test_threads: Option<usize>,
+    /// Print the values logged by each test. Logs are always printed for failing tests.
+    #[clap(long)]
+    pub logs: bool,
+    /// Write the gas used by each test to the snapshot file at the given path.
+    #[clap(long, value_name = "PATH")]
+    pub gas_snapshot: Option<PathBuf>,
+    /// Compare the gas used by each test against the snapshot file at the given path, failing
+    /// if the gas used by any test differs from the recorded value.
+    #[clap(long, value_name = "PATH", conflicts_with = "gas-snapshot")]
+    pub check_gas_snapshot: Option<PathBuf>,
+    /// The format in which test results are printed.
+    #[clap(long, value_enum, default_value = "human")]
+    pub format: TestOutputFormat,
+    /// The number of sets of generated arguments with which each test that accepts arguments is
+    /// executed.
+    #[clap(long, default_value_t = forc_test::FuzzConfig::DEFAULT_RUNS)]
+    pub fuzz_runs: u64,
+    /// The seed from which the arguments of tests are generated. The same seed always produces the
+    /// same arguments, so a different seed may be provided to explore other inputs.
+    #[clap(long, default_value_t = 0)]
+    pub fuzz_seed: u64,
+}
+
+/// The format in which test results are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TestOutputFormat {
+    /// Human readable output.
+    Human,
+    /// A stream of JSON objects, one per line for each test.
+    Json,
+    /// A JUnit XML report with one test suite per package.
+    Junit,
 }
 
 pub(crate) fn exec(cmd: Command) -> Result<()> {
-    if let Some(ref _filter) = cmd.filter {
-        bail!("unit test filter not yet supported");
+    if cmd.exact && cmd.filter.is_none() {
+        bail!("`--exact` requires a test filter to be specified");
+    }
+    if cmd.filter.is_some() && (cmd.gas_snapshot.is_some() || cmd.check_gas_snapshot.is_some()) {
+        bail!("gas snapshots cover all tests and cannot be used with a test filter");
     }
 
+    let test_filter = forc_test::TestFilter {
+        filter_phrase: cmd.filter.clone(),
+        exact_match: cmd.exact,
+        include_ignored: cmd.include_ignored,
+    };
+    let test_runner_count = match cmd.test_threads {
+        Some(runner_count) => forc_test::TestRunnerCount::Manual(runner_count),
+        None => forc_test::TestRunnerCount::Auto,
+    };
+    let fuzz_config = forc_test::FuzzConfig {
+        runs: cmd.fuzz_runs,
+        seed: cmd.fuzz_seed,
+    };
+    let format = cmd.format;
+    let print_logs = cmd.logs;
+    let gas_snapshot_path = cmd.gas_snapshot.clone();
+    let check_gas_snapshot_path = cmd.check_gas_snapshot.clone();
     let opts = opts_from_cmd(cmd);
     let built_tests = forc_test::build(opts)?;
     let start = std::time::Instant::now();
-    info!("   Running {} tests", built_tests.test_count());
-    let tested = built_tests.run()?;
+    let test_count = built_tests.test_count(&test_filter)?;
+    if format == TestOutputFormat::Human {
+        info!("   Running {} tests", test_count);
+    }
+    let tested = built_tests.run(test_runner_count, &test_filter, &fuzz_config)?;
     let duration = start.elapsed();
 
-    // Eventually we'll print this in a fancy manner, but this will do for testing.
-    match tested {
-        forc_test::Tested::Workspace(pkgs) => {
-            for pkg in pkgs {
-                let built = &pkg.built.pkg_name;
-                info!("\n   tested -- {built}\n");
-                print_tested_pkg(&pkg)?;
+    match format {
+        // Eventually we'll print this in a fancy manner, but this will do for testing.
+        TestOutputFormat::Human => match &tested {
+            forc_test::Tested::Workspace(pkgs) => {
+                for pkg in pkgs {
+                    let built = &pkg.built.pkg_name;
+                    info!("\n   tested -- {built}\n");
+                    print_tested_pkg(pkg, print_logs)?;
+                }
+                info!("\n   Finished in {:?}", duration);
+            }
+            forc_test::Tested::Package(pkg) => print_tested_pkg(pkg, print_logs)?,
+        },
+        TestOutputFormat::Json => {
+            for report in TestCaseReport::from_tested(&tested)? {
+                println!("{}", serde_json::to_string(&report)?);
             }
-            info!("\n   Finished in {:?}", duration);
         }
-        forc_test::Tested::Package(pkg) => print_tested_pkg(&pkg)?,
-    };
+        TestOutputFormat::Junit => {
+            let reports = TestCaseReport::from_tested(&tested)?;
+            print!("{}", forc_test::junit_xml(&reports));
+        }
+    }
+
+    let gas_snapshot = GasSnapshot::from_tested(&tested);
+    if let Some(path) = gas_snapshot_path {
+        gas_snapshot.to_file(&path)?;
+        if format == TestOutputFormat::Human {
+            info!("\n   Wrote gas snapshot to {}", path.display());
+        }
+    }
+    if let Some(path) = check_gas_snapshot_path {
+        check_gas_snapshot(&path, &gas_snapshot)?;
+        if format == TestOutputFormat::Human {
+            info!("\n   Gas snapshot {} is up to date", path.display());
+        }
+    }
 
     Ok(())
 }
 
-fn print_tested_pkg(pkg: &TestedPackage) -> Result<()> {
+fn check_gas_snapshot(path: &std::path::Path, gas_snapshot: &GasSnapshot) -> Result<()> {
+    let recorded = GasSnapshot::from_file(path)?;
+    let diffs = recorded.diff(gas_snapshot);
+    if diffs.is_empty() {
+        return Ok(());
+    }
+    let diffs_str = diffs
+        .iter()
+        .map(|diff| format!("  {diff}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    bail!(
+        "gas used by {} test(s) differs from the snapshot at {}:\n{}",
+        diffs.len(),
+        path.display(),
+        diffs_str
+    )
+}
+
+fn print_tested_pkg(pkg: &TestedPackage, print_logs: bool) -> Result<()> {
     let succeeded = pkg.tests.iter().filter(|t| t.passed()).count();
     let failed = pkg.tests.len() - succeeded;
     let mut failed_test_details = Vec::new();
-    for test in &pkg.tests {
+    for declared_test in pkg.declared_tests() {
+        let test = match declared_test {
+            DeclaredTest::Executed(test) => test,
+            DeclaredTest::Ignored { name, .. } => {
+                info!(
+                    "      test {} ... {}",
+                    name,
+                    Colour::Yellow.paint("ignored")
+                );
+                continue;
+            }
+        };
         let test_passed = test.passed();
         let (state, color) = match test_passed {
             true => ("ok", Colour::Green),
             false => ("FAILED", Colour::Red),
         };
-        info!(
-            "      test {} ... {} ({:?})",
-            test.name,
-            color.paint(state),
-            test.duration
-        );
+        match &test.fuzz {
+            Some(fuzz) => info!(
+                "      test {} ... {} ({:?}, {} runs, mean {} gas)",
+                test.name,
+                color.paint(state),
+                test.duration,
+                fuzz.runs,
+                test.gas_used
+            ),
+            None => info!(
+                "      test {} ... {} ({:?}, {} gas)",
+                test.name,
+                color.paint(state),
+                test.duration,
+                test.gas_used
+            ),
+        }
+        if print_logs {
+            print_test_logs(pkg, test);
+        }
 
         // If the test is failing, save details.
         if !test_passed {
             let details = test.details()?;
-            failed_test_details.push((test.name.clone(), details));
+            failed_test_details.push((test, details));
         }
     }
     let (state, color) = match succeeded == pkg.tests.len() {
@@ -88,13 +240,35 @@ fn print_tested_pkg(pkg: &TestedPackage) -> Result<()> {
     };
     if failed != 0 {
         info!("\n   failures:");
-        for (failed_test_name, failed_test_detail) in failed_test_details {
+        for (failed_test, failed_test_detail) in failed_test_details {
             let path = &*failed_test_detail.file_path;
             let line_number = failed_test_detail.line_number;
             info!(
                 "      - test {}, {:?}:{} ",
-                failed_test_name, path, line_number
+                failed_test.name, path, line_number
             );
+            if let forc_test::TestPassCondition::ShouldRevert(Some(expected)) =
+                failed_test.condition
+            {
+                match failed_test.revert_code() {
+                    Some(revert_code) => info!(
+                        "        expected revert code {expected}, found revert code {revert_code}"
+                    ),
+                    None => info!("        expected revert code {expected}, but did not revert"),
+                }
+            } else if let Some(revert_code) = failed_test.revert_code() {
+                info!("        reverted with revert code {revert_code}");
+            }
+            if let Some(counterexample) = failed_test
+                .fuzz
+                .as_ref()
+                .and_then(|fuzz| fuzz.counterexample.as_ref())
+            {
+                info!("        counterexample: {counterexample}");
+            }
+            if !print_logs {
+                print_test_logs(pkg, failed_test);
+            }
         }
         info!("\n");
     }
@@ -105,16 +279,24 @@ fn print_tested_pkg(pkg: &TestedPackage) -> Result<()> {
         .map(|test_result| test_result.duration)
         .sum();
     info!(
-        "   Result: {}. {} passed. {} failed. Finished in {:?}.",
+        "   Result: {}. {} passed. {} failed. {} ignored. {} filtered out. Finished in {:?}.",
         color.paint(state),
         succeeded,
         failed,
+        pkg.ignored.len(),
+        pkg.filtered_out,
         pkg_test_durations
     );
 
     Ok(())
 }
 
+fn print_test_logs(pkg: &TestedPackage, test: &TestResult) {
+    for log in test.logs(&pkg.built.json_abi_program) {
+        info!("        {log}");
+    }
+}
+
 fn opts_from_cmd(cmd: Command) -> forc_test::Opts {
     forc_test::Opts {
         pkg: pkg::PkgOpts {
@@ -0,0 +1,37 @@
+use crate::ops::forc_tree;
+use anyhow::Result;
+use clap::Parser;
+
+/// Print the dependency tree of the current or target project.
+///
+/// Each package is printed along with its version (if known) and its pinned source, i.e. the
+/// commit of git dependencies and the selected version of registry dependencies. Contract
+/// dependencies are marked with `[contract]`, all other dependencies are libraries.
+///
+/// Packages that have already been printed are marked with `(*)` and their dependencies are
+/// omitted.
+#[derive(Debug, Default, Parser)]
+pub struct Command {
+    /// Path to the project, if not specified, current working directory will be used.
+    #[clap(short, long)]
+    pub path: Option<String>,
+    /// Offline mode, prevents Forc from using the network when managing dependencies.
+    /// Meaning it will only try to use previously downloaded dependencies.
+    #[clap(long = "offline")]
+    pub offline_mode: bool,
+    /// Requires that the Forc.lock file is up-to-date. If the lock file is missing, or it
+    /// needs to be updated, Forc will exit with an error
+    #[clap(long)]
+    pub locked: bool,
+    /// Invert the tree for the given package, printing the packages that depend on it.
+    #[clap(short, long, value_name = "PACKAGE")]
+    pub invert: Option<String>,
+    /// Only print packages that are pinned more than once within the graph, e.g. at different
+    /// commits or versions, along with the packages that depend on them.
+    #[clap(short, long, conflicts_with = "invert")]
+    pub duplicates: bool,
+}
+
+pub(crate) fn exec(command: Command) -> Result<()> {
+    forc_tree::tree(command)
+}
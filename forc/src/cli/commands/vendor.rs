@@ -0,0 +1,39 @@
+use crate::ops::forc_vendor;
+use anyhow::Result;
+use clap::Parser;
+
+/// Vendor the git and registry dependencies of the current or target project.
+///
+/// The source of every git and registry package pinned within `Forc.lock` is copied to a `vendor`
+/// directory alongside the lock file, replacing any previously vendored sources. The vendored
+/// sources are described within `vendor/vendor.toml`.
+///
+/// While `vendor/vendor.toml` exists, all git and registry dependencies are resolved from the
+/// vendor directory, allowing the project to be built without network access. Dependencies that
+/// have not been vendored result in an error rather than being fetched.
+#[derive(Debug, Default, Parser)]
+pub struct Command {
+    /// Path to the project, if not specified, current working directory will be used.
+    #[clap(short, long)]
+    pub path: Option<String>,
+    /// Offline mode, prevents Forc from using the network when managing dependencies.
+    /// Meaning it will only try to use previously downloaded dependencies.
+    #[clap(long = "offline")]
+    pub offline_mode: bool,
+    /// Requires that the Forc.lock file is up-to-date. If the lock file is missing, or it
+    /// needs to be updated, Forc will exit with an error
+    #[clap(long)]
+    pub locked: bool,
+    /// Rather than vendoring, verify that the existing vendored sources match those pinned
+    /// within `Forc.lock`.
+    ///
+    /// Every pinned git and registry package must be vendored, and the vendored contents must be
+    /// unmodified and identical to the pinned commit or version. Pinned sources that are not
+    /// available locally are fetched for comparison, unless `--offline` is specified.
+    #[clap(long)]
+    pub verify: bool,
+}
+
+pub(crate) fn exec(command: Command) -> Result<()> {
+    forc_vendor::vendor(command)
+}
@@ -0,0 +1,37 @@
+use crate::ops::forc_verify;
+use anyhow::Result;
+use clap::{ArgGroup, Parser};
+use sway_core::fuel_prelude::fuel_tx::{ContractId, Salt};
+
+/// Verify that the given bytecode or contract ID was built from the current or target project.
+///
+/// The package described by the build info is rebuilt from the project's sources using the
+/// recorded build profile and the dependencies pinned within `Forc.lock`. Any differences between
+/// the inputs recorded within the build info and those of the rebuild are reported, after which
+/// the rebuilt bytecode is compared against the given binary or contract ID.
+#[derive(Debug, Parser)]
+#[clap(group(ArgGroup::new("expected").required(true).args(&["bin-path", "contract-id"])))]
+pub struct Command {
+    /// Path to the `<package>-build-info.json` file written alongside the package's bytecode.
+    pub build_info: String,
+    /// Path to the bytecode to verify.
+    #[clap(long)]
+    pub bin_path: Option<String>,
+    /// The contract ID to verify.
+    #[clap(long)]
+    pub contract_id: Option<ContractId>,
+    /// The salt with which the contract was deployed. Defaults to zero.
+    #[clap(long, requires = "contract-id")]
+    pub salt: Option<Salt>,
+    /// Path to the project, if not specified, current working directory will be used.
+    #[clap(short, long)]
+    pub path: Option<String>,
+    /// Offline mode, prevents Forc from using the network when managing dependencies.
+    /// Meaning it will only try to use previously downloaded dependencies.
+    #[clap(long = "offline")]
+    pub offline_mode: bool,
+}
+
+pub(crate) fn exec(command: Command) -> Result<()> {
+    forc_verify::verify(command)
+}
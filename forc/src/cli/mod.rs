@@ -1,9 +1,10 @@
 use std::str::FromStr;
 
 use self::commands::{
-    addr2line, build, check, clean, completions, init, new, parse_bytecode, plugins, template,
-    test, update,
+    add, addr2line, build, check, clean, completions, init, new, parse_bytecode, plugins, remove,
+    template, test, tree, update, vendor, verify,
 };
+pub use add::Command as AddCommand;
 use addr2line::Command as Addr2LineCommand;
 use anyhow::{anyhow, Result};
 pub use build::Command as BuildCommand;
@@ -11,15 +12,19 @@ pub use check::Command as CheckCommand;
 use clap::{Parser, Subcommand};
 pub use clean::Command as CleanCommand;
 pub use completions::Command as CompletionsCommand;
-use forc_tracing::{init_tracing_subscriber, TracingSubscriberOptions};
+use forc_tracing::{init_tracing_subscriber, TracingSubscriberOptions, TracingWriterMode};
 pub use init::Command as InitCommand;
 pub use new::Command as NewCommand;
 use parse_bytecode::Command as ParseBytecodeCommand;
 pub use plugins::Command as PluginsCommand;
+pub use remove::Command as RemoveCommand;
 pub use template::Command as TemplateCommand;
 pub use test::Command as TestCommand;
 use tracing::metadata::LevelFilter;
+pub use tree::Command as TreeCommand;
 pub use update::Command as UpdateCommand;
+pub use vendor::Command as VendorCommand;
+pub use verify::Command as VerifyCommand;
 
 mod commands;
 mod plugin;
@@ -47,6 +52,7 @@ struct Opt {
 
 #[derive(Subcommand, Debug)]
 enum Forc {
+    Add(AddCommand),
     #[clap(name = "addr2line")]
     Addr2Line(Addr2LineCommand),
     #[clap(visible_alias = "b")]
@@ -57,11 +63,15 @@ enum Forc {
     New(NewCommand),
     Init(InitCommand),
     ParseBytecode(ParseBytecodeCommand),
+    Remove(RemoveCommand),
     #[clap(visible_alias = "t")]
     Test(TestCommand),
+    Tree(TreeCommand),
     Update(UpdateCommand),
     Plugins(PluginsCommand),
     Template(TemplateCommand),
+    Vendor(VendorCommand),
+    Verify(VerifyCommand),
     /// This is a catch-all for unknown subcommands and their arguments.
     ///
     /// When we receive an unknown subcommand, we check for a plugin exe named
@@ -76,16 +86,24 @@ enum Forc {
 
 pub async fn run_cli() -> Result<()> {
     let opt = Opt::parse();
+    // Reserve stdout for machine-readable test reports.
+    let writer_mode = match &opt.command {
+        Forc::Test(command) if command.format != test::TestOutputFormat::Human => {
+            Some(TracingWriterMode::Stderr)
+        }
+        _ => None,
+    };
     let tracing_options = TracingSubscriberOptions {
         verbosity: Some(opt.verbose),
         silent: Some(opt.silent),
         log_level: opt.log_level,
-        ..Default::default()
+        writer_mode,
     };
 
     init_tracing_subscriber(tracing_options);
 
     match opt.command {
+        Forc::Add(command) => add::exec(command),
         Forc::Addr2Line(command) => addr2line::exec(command),
         Forc::Build(command) => build::exec(command),
         Forc::Check(command) => check::exec(command),
@@ -95,9 +113,13 @@ pub async fn run_cli() -> Result<()> {
         Forc::New(command) => new::exec(command),
         Forc::ParseBytecode(command) => parse_bytecode::exec(command),
         Forc::Plugins(command) => plugins::exec(command),
+        Forc::Remove(command) => remove::exec(command),
         Forc::Test(command) => test::exec(command),
+        Forc::Tree(command) => tree::exec(command),
         Forc::Update(command) => update::exec(command).await,
         Forc::Template(command) => template::exec(command),
+        Forc::Vendor(command) => vendor::exec(command),
+        Forc::Verify(command) => verify::exec(command),
         Forc::Plugin(args) => {
             let output = plugin::execute_external_subcommand(args)?;
             let code = output
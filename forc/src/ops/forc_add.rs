@@ -0,0 +1,91 @@
+use crate::{cli::AddCommand, utils::manifest_edit};
+use anyhow::{anyhow, bail, Result};
+use forc_util::validate_name;
+use std::path::PathBuf;
+use toml_edit::{InlineTable, Item, Value};
+
+/// Running `forc add <name>` declares the dependency within the package's `Forc.toml` and updates
+/// the `Forc.lock` to include it.
+///
+/// The dependency is declared under `[contract-dependencies]` if `--contract-dep` is specified,
+/// otherwise under `[dependencies]`. An existing declaration of the same name is replaced in
+/// place.
+pub fn add(command: AddCommand) -> Result<()> {
+    let AddCommand {
+        name,
+        manifest_path,
+        offline_mode: offline,
+        contract_dep,
+        ..
+    } = &command;
+    validate_name(name, "dependency name")?;
+    let this_dir = match manifest_path {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_dir()?,
+    };
+    let (table_name, other_table_name) = match contract_dep {
+        true => ("contract-dependencies", "dependencies"),
+        false => ("dependencies", "contract-dependencies"),
+    };
+    let dep = dependency_value(&command)?;
+
+    manifest_edit::edit_pkg_manifest(&this_dir, *offline, |doc| {
+        if doc
+            .get(other_table_name)
+            .and_then(|table| table.get(name))
+            .is_some()
+        {
+            bail!("`{name}` is already declared under `[{other_table_name}]`");
+        }
+        let table = doc
+            .as_table_mut()
+            .entry(table_name)
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("`{table_name}` is not a table"))?;
+        match table.get_mut(name) {
+            Some(item) => *item = dep,
+            None => {
+                table.insert(name, dep);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Construct the manifest entry for the dependency described by the command.
+///
+/// A registry dependency with no other details uses the short form, e.g. `foo = "0.1"`, all other
+/// dependencies are written as inline tables.
+fn dependency_value(command: &AddCommand) -> Result<Item> {
+    if command.path.is_none() && command.git.is_none() && command.version.is_none() {
+        bail!(
+            "a source for `{}` is required, specify one of `--path`, `--git` or `--version`",
+            command.name
+        );
+    }
+    let fields = [
+        ("path", &command.path),
+        ("git", &command.git),
+        ("branch", &command.branch),
+        ("tag", &command.tag),
+        ("rev", &command.rev),
+        ("version", &command.version),
+        ("registry", &command.registry),
+        ("package", &command.package),
+        ("salt", &command.salt),
+    ];
+    let mut table = InlineTable::new();
+    for (key, field) in fields {
+        if let Some(field) = field {
+            table.insert(key, field.as_str().into());
+        }
+    }
+    if table.len() == 1 {
+        if let Some(version) = &command.version {
+            return Ok(toml_edit::value(version.as_str()));
+        }
+    }
+    table.fmt();
+    Ok(toml_edit::value(Value::InlineTable(table)))
+}
@@ -34,5 +34,6 @@ fn opts_from_cmd(cmd: BuildCommand) -> pkg::BuildOpts {
         binary_outfile: cmd.build.binary_outfile,
         debug_outfile: cmd.build.debug_outfile,
         tests: cmd.tests,
+        no_cache: cmd.no_cache,
     }
 }
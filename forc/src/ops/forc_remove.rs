@@ -0,0 +1,38 @@
+use crate::{
+    cli::RemoveCommand,
+    utils::manifest_edit::{self, DEPENDENCY_TABLES},
+};
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+
+/// Running `forc remove <name>...` removes the declarations of the given dependencies from the
+/// package's `Forc.toml` and updates the `Forc.lock` accordingly.
+pub fn remove(command: RemoveCommand) -> Result<()> {
+    let RemoveCommand {
+        names,
+        manifest_path,
+        offline_mode: offline,
+    } = command;
+    let this_dir = match manifest_path {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_dir()?,
+    };
+
+    manifest_edit::edit_pkg_manifest(&this_dir, offline, |doc| {
+        for name in &names {
+            let mut removed = false;
+            for table_name in DEPENDENCY_TABLES {
+                if let Some(table) = doc
+                    .get_mut(table_name)
+                    .and_then(|table| table.as_table_like_mut())
+                {
+                    removed |= table.remove(name).is_some();
+                }
+            }
+            if !removed {
+                bail!("the dependency `{name}` could not be found in `[dependencies]` or `[contract-dependencies]`");
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,279 @@
+use crate::cli::TreeCommand;
+use anyhow::{anyhow, bail, Result};
+use forc_pkg::{self as pkg, BuildPlan, DepKind, NodeIx, SourcePinned};
+use petgraph::{visit::EdgeRef, Direction};
+use pkg::manifest::ManifestFile;
+use std::{collections::HashSet, fmt::Write, path::PathBuf};
+
+/// Running `forc tree` prints the dependency tree of each member that would be built by
+/// `forc build` in the current directory.
+///
+/// Use `--invert <package>` to print the packages that depend on every instance of the given
+/// package, or `--duplicates` to do the same for every package that is pinned more than once.
+pub fn tree(command: TreeCommand) -> Result<()> {
+    let TreeCommand {
+        path,
+        offline_mode: offline,
+        locked,
+        invert,
+        duplicates,
+    } = command;
+
+    let this_dir = match path {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_dir()?,
+    };
+    let manifest_file = ManifestFile::from_dir(&this_dir)?;
+    let member_manifests = manifest_file.member_manifests()?;
+    let lock_path = manifest_file.lock_path()?;
+    let plan = BuildPlan::from_lock_and_manifests(&lock_path, &member_manifests, locked, offline)?;
+
+    let (roots, direction) = match invert {
+        Some(pkg_name) => {
+            let nodes = nodes_named(&plan, &pkg_name);
+            if nodes.is_empty() {
+                bail!("package `{pkg_name}` could not be found in the dependency graph");
+            }
+            (nodes, Direction::Incoming)
+        }
+        None if duplicates => (duplicate_nodes(&plan), Direction::Incoming),
+        None => {
+            let roots = match &manifest_file {
+                ManifestFile::Package(pkg_manifest) => vec![plan
+                    .find_member_index(&pkg_manifest.project.name)
+                    .ok_or_else(|| anyhow!("Cannot find project node in the graph"))?],
+                ManifestFile::Workspace(_) => plan.member_nodes().collect(),
+            };
+            (roots, Direction::Outgoing)
+        }
+    };
+
+    let trees: Vec<String> = roots
+        .into_iter()
+        .map(|root| render_tree(&plan, root, direction))
+        .collect();
+    print!("{}", trees.join("\n"));
+    Ok(())
+}
+
+/// All nodes for packages of the given name, ordered by their source.
+fn nodes_named(plan: &BuildPlan, pkg_name: &str) -> Vec<NodeIx> {
+    let graph = plan.graph();
+    let mut nodes: Vec<NodeIx> = graph
+        .node_indices()
+        .filter(|&node| graph[node].name == pkg_name)
+        .collect();
+    nodes.sort_by_key(|&node| graph[node].source.to_string());
+    nodes
+}
+
+/// All nodes for packages that appear within the graph more than once, ordered by name and then
+/// by source.
+fn duplicate_nodes(plan: &BuildPlan) -> Vec<NodeIx> {
+    let graph = plan.graph();
+    let mut names: Vec<&str> = graph
+        .node_indices()
+        .map(|node| &graph[node].name[..])
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| nodes_named(plan, name))
+        .filter(|nodes| nodes.len() > 1)
+        .flatten()
+        .collect()
+}
+
+/// Render the tree of packages reachable from `root` in the given direction, i.e. its dependencies
+/// for `Outgoing` or its dependents for `Incoming`.
+fn render_tree(plan: &BuildPlan, root: NodeIx, direction: Direction) -> String {
+    let mut out = String::new();
+    let mut visited = HashSet::new();
+    visited.insert(root);
+    let _ = writeln!(out, "{}", describe(plan, root, None));
+    render_children(plan, root, direction, "", &mut visited, &mut out);
+    out
+}
+
+fn render_children(
+    plan: &BuildPlan,
+    node: NodeIx,
+    direction: Direction,
+    prefix: &str,
+    visited: &mut HashSet<NodeIx>,
+    out: &mut String,
+) {
+    let graph = plan.graph();
+    let mut children: Vec<(NodeIx, &DepKind)> = graph
+        .edges_directed(node, direction)
+        .map(|edge| {
+            let child = match direction {
+                Direction::Outgoing => edge.target(),
+                Direction::Incoming => edge.source(),
+            };
+            (child, &edge.weight().kind)
+        })
+        .collect();
+    children.sort_by(|(a, _), (b, _)| graph[*a].name.cmp(&graph[*b].name));
+    let n_children = children.len();
+    for (i, (child, kind)) in children.into_iter().enumerate() {
+        let (branch, indent) = match i + 1 == n_children {
+            true => ("└── ", "    "),
+            false => ("├── ", "│   "),
+        };
+        let first_visit = visited.insert(child);
+        let has_children = graph.edges_directed(child, direction).next().is_some();
+        let omitted = if !first_visit && has_children {
+            " (*)"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "{prefix}{branch}{}{omitted}",
+            describe(plan, child, Some(kind))
+        );
+        if first_visit {
+            let prefix = format!("{prefix}{indent}");
+            render_children(plan, child, direction, &prefix, visited, out);
+        }
+    }
+}
+
+/// Describe the package along with its version and source, marking contract dependencies.
+fn describe(plan: &BuildPlan, node: NodeIx, kind: Option<&DepKind>) -> String {
+    let pinned = &plan.graph()[node];
+    let manifest = plan.manifest_map().get(&pinned.id());
+    let mut s = pinned.name.clone();
+    let version = match &pinned.source {
+        SourcePinned::Registry(reg) => Some(reg.version.clone()),
+        _ => manifest.and_then(|manifest| manifest.project.version.clone()),
+    };
+    if let Some(version) = version {
+        let _ = write!(s, " v{version}");
+    }
+    match (&pinned.source, manifest) {
+        (SourcePinned::Member | SourcePinned::Path(_), Some(manifest)) => {
+            let _ = write!(s, " ({})", manifest.dir().display());
+        }
+        (source, _) => {
+            let _ = write!(s, " ({source})");
+        }
+    }
+    if let Some(DepKind::Contract { .. }) = kind {
+        s.push_str(" [contract]");
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forc_pkg::test_util::{self, TempDir};
+
+    /// Plan a package `app` with the following dependencies:
+    ///
+    /// - `a`, which depends on the `shared` package at a path.
+    /// - `b`, which depends on `a` and on version 1.0.0 of `shared` within a registry.
+    /// - `c`, a contract dependency.
+    ///
+    /// Returns the plan along with the temporary directory and its canonical path.
+    fn plan() -> (TempDir, String, BuildPlan) {
+        let dir = test_util::temp_dir();
+        let root = dir.path().canonicalize().unwrap();
+        test_util::publish(&root.join("index"), "shared", "1.0.0", "");
+        test_util::write_lib(&root.join("shared"), "shared", "");
+        test_util::write_lib(
+            &root.join("a"),
+            "a",
+            "[dependencies]\nshared = { path = \"../shared\" }\n",
+        );
+        test_util::write_lib(
+            &root.join("b"),
+            "b",
+            "[dependencies]\na = { path = \"../a\" }\n\
+            shared = { version = \"1.0.0\", registry = \"../index\" }\n",
+        );
+        test_util::write_contract(&root.join("c"), "c", "");
+        test_util::write_lib(
+            &root.join("app"),
+            "app",
+            "[dependencies]\na = { path = \"../a\" }\nb = { path = \"../b\" }\n\n\
+            [contract-dependencies]\nc = { path = \"../c\" }\n",
+        );
+        let manifests = ManifestFile::from_dir(&root.join("app"))
+            .unwrap()
+            .member_manifests()
+            .unwrap();
+        let plan = BuildPlan::from_manifests(&manifests, true).unwrap();
+        (dir, root.display().to_string(), plan)
+    }
+
+    /// Render the trees of the given roots as `forc tree` does, with the temporary directory
+    /// replaced by `<dir>`.
+    fn render(plan: &BuildPlan, root: &str, nodes: Vec<NodeIx>, direction: Direction) -> String {
+        let trees: Vec<String> = nodes
+            .into_iter()
+            .map(|node| render_tree(plan, node, direction))
+            .collect();
+        trees.join("\n").replace(root, "<dir>")
+    }
+
+    #[test]
+    fn test_render_tree() {
+        test_util::with_forc_home(|_| {
+            let (_dir, root, plan) = plan();
+            let app = plan.find_member_index("app").unwrap();
+            let expected = "\
+app (<dir>/app)
+├── a (<dir>/a)
+│   └── shared (<dir>/shared)
+├── b (<dir>/b)
+│   ├── a (<dir>/a) (*)
+│   └── shared v1.0.0 (registry+file://<dir>/index/?1.0.0#1.0.0)
+└── c (<dir>/c) [contract]
+";
+            assert_eq!(
+                render(&plan, &root, vec![app], Direction::Outgoing),
+                expected
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_inverted_tree() {
+        test_util::with_forc_home(|_| {
+            let (_dir, root, plan) = plan();
+            let expected = "\
+a (<dir>/a)
+├── app (<dir>/app)
+└── b (<dir>/b)
+    └── app (<dir>/app)
+";
+            let nodes = nodes_named(&plan, "a");
+            assert_eq!(render(&plan, &root, nodes, Direction::Incoming), expected);
+            assert!(nodes_named(&plan, "d").is_empty());
+        })
+    }
+
+    #[test]
+    fn test_render_duplicates() {
+        test_util::with_forc_home(|_| {
+            let (_dir, root, plan) = plan();
+            let expected = "\
+shared (<dir>/shared)
+└── a (<dir>/a)
+    ├── app (<dir>/app)
+    └── b (<dir>/b)
+        └── app (<dir>/app)
+
+shared v1.0.0 (registry+file://<dir>/index/?1.0.0#1.0.0)
+└── b (<dir>/b)
+    └── app (<dir>/app)
+";
+            let nodes = duplicate_nodes(&plan);
+            assert_eq!(render(&plan, &root, nodes, Direction::Incoming), expected);
+        })
+    }
+}
@@ -39,8 +39,9 @@ pub async fn update(command: UpdateCommand) -> Result<()> {
     let offline = false;
     let member_manifests = manifest.member_manifests()?;
     let new_plan = pkg::BuildPlan::from_manifests(&member_manifests, offline)?;
-    let new_lock = Lock::from_graph(new_plan.graph());
-    let diff = new_lock.diff(&old_lock);
+    let new_lock = Lock::from_graph(new_plan.graph(), new_plan.checksums());
+    let (new_pkgs, old_pkgs) = (new_lock.without_checksums(), old_lock.without_checksums());
+    let diff = new_pkgs.diff(&old_pkgs);
     let member_names = member_manifests
         .values()
         .map(|manifest| manifest.project.name.clone())
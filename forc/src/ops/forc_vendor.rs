@@ -0,0 +1,45 @@
+use crate::cli::VendorCommand;
+use anyhow::Result;
+use forc_pkg::{manifest::ManifestFile, vendor, BuildPlan};
+use std::path::PathBuf;
+use tracing::info;
+
+/// Running `forc vendor` copies the sources of all git and registry dependencies of the project
+/// to its `vendor` directory, or verifies them in the case that `--verify` is specified.
+pub fn vendor(command: VendorCommand) -> Result<()> {
+    let VendorCommand {
+        path,
+        offline_mode: offline,
+        locked,
+        verify,
+    } = command;
+
+    let this_dir = match path {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_dir()?,
+    };
+    let manifest_file = ManifestFile::from_dir(&this_dir)?;
+    let lock_path = manifest_file.lock_path()?;
+    let vendor_dir = vendor::vendor_dir(&lock_path);
+
+    if verify {
+        vendor::verify(&lock_path, offline)?;
+        info!("    Verified {}", vendor_dir.display());
+        return Ok(());
+    }
+
+    let member_manifests = manifest_file.member_manifests()?;
+    let plan = BuildPlan::from_lock_and_manifests_unvendored(
+        &lock_path,
+        &member_manifests,
+        locked,
+        offline,
+    )?;
+    let config = vendor::vendor(&plan, &lock_path)?;
+    info!(
+        "    Vendored {} source(s) to {}",
+        config.sources.len(),
+        vendor_dir.display()
+    );
+    Ok(())
+}
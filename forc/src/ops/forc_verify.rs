@@ -0,0 +1,91 @@
+use crate::cli::VerifyCommand;
+use anyhow::{anyhow, bail, Result};
+use forc_pkg::{self as pkg, build_info::BuildInfo, manifest::ManifestFile, BuildPlan};
+use std::{collections::HashSet, fs, path::PathBuf};
+use sway_core::language::parsed::TreeType;
+use tracing::{info, warn};
+
+/// Running `forc verify` rebuilds the package described by the given build info and compares the
+/// result against the expected bytecode or contract ID.
+pub fn verify(command: VerifyCommand) -> Result<()> {
+    let VerifyCommand {
+        build_info,
+        bin_path,
+        contract_id,
+        salt,
+        path,
+        offline_mode: offline,
+    } = command;
+
+    let build_info = BuildInfo::from_path(&PathBuf::from(build_info))?;
+    let this_dir = match path {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_dir()?,
+    };
+    let manifest_file = ManifestFile::from_dir(&this_dir)?;
+    let lock_path = manifest_file.lock_path()?;
+    let member_manifests = manifest_file.member_manifests()?;
+    info!(
+        "   Verifying `{}` built with forc {}",
+        build_info.package, build_info.compiler_version
+    );
+
+    // Rebuild the package from the dependencies pinned within the existing lock file.
+    let plan = BuildPlan::from_lock_and_manifests(&lock_path, &member_manifests, true, offline)?;
+    let node = plan.find_member_index(&build_info.package).ok_or_else(|| {
+        anyhow!(
+            "`{}` is not a member of the project at {}",
+            build_info.package,
+            this_dir.display()
+        )
+    })?;
+    let outputs: HashSet<_> = std::iter::once(node).collect();
+    // Always compile from source, so that verification never relies on cached bytecode.
+    let (_, built) = pkg::build(&plan, &build_info.profile, &outputs, true)?
+        .pop()
+        .expect("the requested package was built");
+    let rebuilt_info = built
+        .build_info
+        .as_ref()
+        .expect("build info is collected for requested packages");
+
+    let diffs = build_info.diff_inputs(rebuilt_info);
+    if !diffs.is_empty() {
+        warn!("  The inputs to the rebuild differ from those recorded within the build info:");
+        for diff in &diffs {
+            warn!("    {}", diff);
+        }
+    }
+
+    if let Some(bin_path) = bin_path {
+        let expected = fs::read(&bin_path)?;
+        if expected != built.bytecode {
+            bail!(
+                "the bytecode of `{}` does not match {}",
+                build_info.package,
+                bin_path
+            );
+        }
+        info!("    Verified bytecode {}", bin_path);
+    }
+
+    if let Some(expected) = contract_id {
+        if built.tree_type != TreeType::Contract {
+            bail!(
+                "`{}` is not a contract, its bytecode may only be verified with `--bin-path`",
+                build_info.package
+            );
+        }
+        let contract_id = pkg::contract_id(&built, &salt.unwrap_or_default());
+        if contract_id != expected {
+            bail!(
+                "the contract ID 0x{} of `{}` does not match 0x{}",
+                contract_id,
+                build_info.package,
+                expected
+            );
+        }
+        info!("    Verified contract ID 0x{}", contract_id);
+    }
+    Ok(())
+}
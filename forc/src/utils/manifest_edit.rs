@@ -0,0 +1,135 @@
+//! Utilities shared by the commands that edit a package's `Forc.toml`, e.g. `forc add` and
+//! `forc remove`.
+
+use anyhow::{bail, Context, Result};
+use forc_pkg::{manifest::ManifestFile, BuildPlan, PackageManifestFile};
+use std::{fs, path::Path};
+use toml_edit::Document;
+use tracing::info;
+
+/// The manifest tables in which dependencies may be declared.
+pub(crate) const DEPENDENCY_TABLES: [&str; 2] = ["dependencies", "contract-dependencies"];
+
+/// Apply `edit` to the `Forc.toml` of the package at or above `dir`, and update the `Forc.lock`.
+///
+/// The manifest is edited with `toml_edit` so that its existing formatting and comments are
+/// preserved. The edited manifest is validated and the dependency graph is resolved before
+/// returning. In the case that either fails, both the manifest and lock file are restored.
+pub(crate) fn edit_pkg_manifest(
+    dir: &Path,
+    offline: bool,
+    edit: impl FnOnce(&mut Document) -> Result<()>,
+) -> Result<()> {
+    let manifest_path = match ManifestFile::from_dir(dir)? {
+        ManifestFile::Package(pkg_manifest) => pkg_manifest.path().to_path_buf(),
+        ManifestFile::Workspace(ws_manifest) => bail!(
+            "{} is a workspace manifest, dependencies must be edited within a member package",
+            ws_manifest.path().display()
+        ),
+    };
+    let old_manifest = fs::read_to_string(&manifest_path)?;
+    let mut doc = old_manifest
+        .parse::<Document>()
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+    edit(&mut doc)?;
+
+    let pkg_manifest = PackageManifestFile::from_file(manifest_path.clone())?;
+    let lock_path = pkg_manifest.lock_path()?;
+    let old_lock = fs::read_to_string(&lock_path).ok();
+    fs::write(&manifest_path, doc.to_string())?;
+
+    let res = ManifestFile::from_file(manifest_path.clone())
+        .context("edited manifest is invalid")
+        .and_then(|manifest| {
+            let member_manifests = manifest.member_manifests()?;
+            BuildPlan::from_lock_and_manifests(&lock_path, &member_manifests, false, offline)
+        });
+    if let Err(e) = res {
+        fs::write(&manifest_path, old_manifest)?;
+        match old_lock {
+            Some(old_lock) => fs::write(&lock_path, old_lock)?,
+            None if lock_path.exists() => fs::remove_file(&lock_path)?,
+            None => (),
+        }
+        return Err(e);
+    }
+    info!("     Updated {}", manifest_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forc_pkg::test_util;
+    use sway_utils::constants;
+
+    #[test]
+    fn test_edit_preserves_formatting_and_comments() {
+        let dir = test_util::temp_dir();
+        test_util::write_lib(&dir.path().join("a"), "a", "");
+        test_util::write_lib(&dir.path().join("b"), "b", "");
+        let app_dir = dir.path().join("app");
+        test_util::write_lib(
+            &app_dir,
+            "app",
+            "# Dependencies shared with other packages.\n[dependencies]\n\
+            a   =   { path = \"../a\" }   # Keep this one.\nb = { path = \"../b\" }\n",
+        );
+        let manifest_path = app_dir.join(constants::MANIFEST_FILE_NAME);
+        let old_manifest = fs::read_to_string(&manifest_path).unwrap();
+
+        edit_pkg_manifest(&app_dir, true, |doc| {
+            doc["dependencies"].as_table_mut().unwrap().remove("b");
+            Ok(())
+        })
+        .unwrap();
+        let expected = old_manifest.replace("b = { path = \"../b\" }\n", "");
+        assert_ne!(expected, old_manifest);
+        assert_eq!(fs::read_to_string(&manifest_path).unwrap(), expected);
+        assert!(app_dir.join(constants::LOCK_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_failed_edit_restores_manifest_and_lock() {
+        let dir = test_util::temp_dir();
+        test_util::write_lib(&dir.path().join("a"), "a", "");
+        let app_dir = dir.path().join("app");
+        test_util::write_lib(&app_dir, "app", "[dependencies]\n");
+        let manifest_path = app_dir.join(constants::MANIFEST_FILE_NAME);
+        let lock_path = app_dir.join(constants::LOCK_FILE_NAME);
+
+        // Without a previous lock, no lock is left behind.
+        let add_missing = |doc: &mut Document| {
+            let dep: toml_edit::Value = "{ path = \"../missing\" }".parse().unwrap();
+            doc["dependencies"]["missing"] = toml_edit::value(dep);
+            Ok(())
+        };
+        let old_manifest = fs::read_to_string(&manifest_path).unwrap();
+        assert!(edit_pkg_manifest(&app_dir, true, add_missing).is_err());
+        assert_eq!(fs::read_to_string(&manifest_path).unwrap(), old_manifest);
+        assert!(!lock_path.exists());
+
+        // Otherwise, the previous lock is restored.
+        edit_pkg_manifest(&app_dir, true, |doc| {
+            let dep: toml_edit::Value = "{ path = \"../a\" }".parse().unwrap();
+            doc["dependencies"]["a"] = toml_edit::value(dep);
+            Ok(())
+        })
+        .unwrap();
+        let old_manifest = fs::read_to_string(&manifest_path).unwrap();
+        let old_lock = fs::read_to_string(&lock_path).unwrap();
+        assert!(edit_pkg_manifest(&app_dir, true, add_missing).is_err());
+        assert_eq!(fs::read_to_string(&manifest_path).unwrap(), old_manifest);
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), old_lock);
+
+        // An edit producing an invalid manifest is also rolled back.
+        let err = edit_pkg_manifest(&app_dir, true, |doc| {
+            doc["project"]["name"] = toml_edit::value("not a valid name");
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "edited manifest is invalid");
+        assert_eq!(fs::read_to_string(&manifest_path).unwrap(), old_manifest);
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), old_lock);
+    }
+}
@@ -12,6 +12,7 @@ pub struct Annotated<T> {
 //    #[attribute()]
 //    #[attribute(value)]
 //    #[attribute(value0, value1, value2)]
+//    #[attribute(name = "value")]
 
 #[derive(Clone, Debug)]
 pub struct AttributeDecl {
@@ -28,7 +29,7 @@ impl Spanned for AttributeDecl {
 #[derive(Clone, Debug)]
 pub struct Attribute {
     pub name: Ident,
-    pub args: Option<Parens<Punctuated<Ident, CommaToken>>>,
+    pub args: Option<Parens<Punctuated<AttributeArg, CommaToken>>>,
 }
 
 impl Spanned for Attribute {
@@ -39,3 +40,19 @@ impl Spanned for Attribute {
             .unwrap_or_else(|| self.name.span())
     }
 }
+
+/// An argument to an attribute, optionally assigned a literal value, e.g. `should_revert = "42"`.
+#[derive(Clone, Debug)]
+pub struct AttributeArg {
+    pub name: Ident,
+    pub value: Option<Literal>,
+}
+
+impl Spanned for AttributeArg {
+    fn span(&self) -> Span {
+        match &self.value {
+            Some(value) => Span::join(self.name.span(), value.span()),
+            None => self.name.span(),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt;
 
-#[derive(Eq, PartialEq, Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum Intrinsic {
     GetStorageKey,
     IsReferenceType,
@@ -35,6 +35,7 @@ pub use {
     },
     extension_trait::extension_trait,
     num_bigint::BigUint,
+    serde::{Deserialize, Serialize},
     std::{
         fmt, marker::PhantomData, mem, ops::ControlFlow, path::PathBuf, str::FromStr, sync::Arc,
     },
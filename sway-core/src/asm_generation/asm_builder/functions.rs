@@ -173,7 +173,13 @@ impl<'ir> AsmBuilder<'ir> {
         }
 
         if func_is_entry {
-            self.compile_external_args(function)
+            // Tests are always executed as scripts, regardless of the kind of program in which
+            // they are declared, so their arguments are always read from the script data.
+            let args_kind = match test_decl_id {
+                Some(_) => ProgramKind::Script,
+                None => self.program_kind,
+            };
+            self.compile_external_args(function, args_kind)
         } else {
             // Make copies of the arg registers.
             self.compile_fn_call_args(function)
@@ -288,8 +294,8 @@ impl<'ir> AsmBuilder<'ir> {
         }
     }
 
-    // Handle loading the arguments of a contract call
-    fn compile_external_args(&mut self, function: Function) {
+    // Handle loading the arguments of a contract call or of a script, predicate or test entry.
+    fn compile_external_args(&mut self, function: Function, program_kind: ProgramKind) {
         match function.args_iter(self.context).count() {
             // Nothing to do if there are no arguments
             0 => (),
@@ -299,11 +305,11 @@ impl<'ir> AsmBuilder<'ir> {
             1 => {
                 let (_, val) = function.args_iter(self.context).next().unwrap();
                 let single_arg_reg = self.value_to_register(val);
-                match self.program_kind {
+                match program_kind {
                     ProgramKind::Contract => self.read_args_base_from_frame(&single_arg_reg),
                     ProgramKind::Library => (), // Nothing to do here
                     ProgramKind::Script | ProgramKind::Predicate => {
-                        if let ProgramKind::Predicate = self.program_kind {
+                        if let ProgramKind::Predicate = program_kind {
                             self.read_args_base_from_predicate_data(&single_arg_reg);
                         } else {
                             self.read_args_base_from_script_data(&single_arg_reg);
@@ -328,7 +334,7 @@ impl<'ir> AsmBuilder<'ir> {
             // Otherwise, the args are bundled together and pointed to by the base register.
             _ => {
                 let args_base_reg = self.reg_seqr.next();
-                match self.program_kind {
+                match program_kind {
                     ProgramKind::Contract => self.read_args_base_from_frame(&args_base_reg),
                     ProgramKind::Library => return, // Nothing to do here
                     ProgramKind::Predicate => {
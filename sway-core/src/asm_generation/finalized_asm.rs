@@ -8,6 +8,7 @@ use sway_error::error::CompileError;
 use sway_types::span::Span;
 
 use either::Either;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::io::Read;
 
@@ -18,10 +19,16 @@ pub struct FinalizedAsm {
     pub data_section: DataSection,
     pub program_section: InstructionSet,
     pub program_kind: ProgramKind,
+    /// The number of instructions in the program's preamble.
+    ///
+    /// The preamble sets up the data section register, after which execution continues at this
+    /// instruction offset. Replacing the instruction at this offset with a jump allows for
+    /// execution to begin at any of the `entries` instead, e.g. a test or benchmark.
+    pub preamble_len: u64,
     pub entries: Vec<FinalizedEntry>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FinalizedEntry {
     /// The original entry point function name.
     pub fn_name: String,
@@ -31,6 +38,10 @@ pub struct FinalizedEntry {
     pub selector: Option<[u8; 4]>,
     /// If this entry is constructed from a test function contains the declaration id for that
     /// function, otherwise contains `None`.
+    ///
+    /// Declaration IDs are only meaningful within the compilation that produced them, and so are
+    /// never serialized.
+    #[serde(skip)]
     pub test_decl_id: Option<DeclarationId>,
 }
 
@@ -49,6 +49,8 @@ pub(super) struct AllocatedProgram {
     kind: ProgramKind,
     data_section: DataSection,
     prologue: AllocatedAbstractInstructionSet,
+    /// The label marking the end of the preamble, at which the program proper begins.
+    program_start: Label,
     functions: Vec<AllocatedAbstractInstructionSet>,
     entries: Vec<(SelectorOpt, Label, FnName, Option<DeclarationId>)>,
 }
@@ -58,5 +60,7 @@ pub(super) struct FinalProgram {
     kind: ProgramKind,
     data_section: DataSection,
     ops: InstructionSet,
+    /// The number of instructions in the preamble, i.e. the offset at which the program begins.
+    preamble_len: ImmOffset,
     entries: Vec<(SelectorOpt, ImmOffset, FnName, Option<DeclarationId>)>,
 }
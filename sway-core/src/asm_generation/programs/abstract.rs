@@ -7,7 +7,7 @@ use crate::{
     },
     asm_lang::{
         allocated_ops::{AllocatedOpcode, AllocatedRegister},
-        AllocatedAbstractOp, ConstantRegister, ControlFlowOp, VirtualImmediate12,
+        AllocatedAbstractOp, ConstantRegister, ControlFlowOp, Label, VirtualImmediate12,
         VirtualImmediate18,
     },
 };
@@ -36,7 +36,7 @@ impl AbstractProgram {
     pub(crate) fn into_allocated_program(mut self) -> Result<AllocatedProgram, CompileError> {
         // Build our bytecode prologue which has a preamble and for contracts is the switch based on
         // function selector.
-        let mut prologue = self.build_preamble();
+        let (mut prologue, program_start) = self.build_preamble();
 
         if self.kind == ProgramKind::Contract {
             self.build_contract_abi_switch(&mut prologue);
@@ -79,6 +79,7 @@ impl AbstractProgram {
             kind: self.kind,
             data_section: self.data_section,
             prologue,
+            program_start,
             functions,
             entries,
         })
@@ -95,9 +96,12 @@ impl AbstractProgram {
     /// 3    LW $ds $is               1 (where 1 is in words and $is is a byte address to base off of)
     /// -    ADD $ds $ds $is
     /// 4    .program_start:
-    fn build_preamble(&mut self) -> AllocatedAbstractInstructionSet {
+    ///
+    /// Also returns the `.program_start` label, the offset of which is the length of the preamble.
+    fn build_preamble(&mut self) -> (AllocatedAbstractInstructionSet, Label) {
         let label = self.reg_seqr.get_label();
-        AllocatedAbstractInstructionSet {
+        let program_start = self.reg_seqr.get_label();
+        let preamble = AllocatedAbstractInstructionSet {
             ops: [
                 // word 1
                 AllocatedAbstractOp {
@@ -138,9 +142,15 @@ impl AbstractProgram {
                     comment: "".into(),
                     owning_span: None,
                 },
+                AllocatedAbstractOp {
+                    opcode: Either::Right(ControlFlowOp::Label(program_start)),
+                    comment: "program start".into(),
+                    owning_span: None,
+                },
             ]
             .to_vec(),
-        }
+        };
+        (preamble, program_start)
     }
 
     /// Builds the contract switch statement based on the first argument to a contract call: the
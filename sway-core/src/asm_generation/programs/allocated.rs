@@ -19,6 +19,11 @@ impl AllocatedProgram {
             ops: realized_ops.pad_to_even(),
         };
 
+        let preamble_len = label_offsets
+            .remove(&self.program_start)
+            .expect("no offset for program start")
+            .offs;
+
         // Collect the entry point offsets.
         let entries = self
             .entries
@@ -36,6 +41,7 @@ impl AllocatedProgram {
             kind: self.kind,
             data_section: self.data_section,
             ops,
+            preamble_len,
             entries,
         })
     }
@@ -8,6 +8,7 @@ impl FinalProgram {
             data_section: self.data_section,
             program_section: self.ops,
             program_kind: self.kind,
+            preamble_len: self.preamble_len,
             entries: self
                 .entries
                 .into_iter()
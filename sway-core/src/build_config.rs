@@ -1,4 +1,5 @@
-use std::{path::PathBuf, sync::Arc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, path::PathBuf, str::FromStr, sync::Arc};
 
 /// Configuration for the overall build and compilation process.
 #[derive(Clone)]
@@ -11,6 +12,124 @@ pub struct BuildConfig {
     pub(crate) print_finalized_asm: bool,
     pub(crate) print_ir: bool,
     pub(crate) include_tests: bool,
+    pub(crate) optimization: OptLevel,
+    pub(crate) passes: Option<Vec<OptPass>>,
+    pub(crate) inline_threshold: Option<usize>,
+}
+
+/// The level of optimization applied to the IR before generating ASM.
+///
+/// Levels 0 and 1 are not yet supported. Calls to methods of a generic type's trait constraints
+/// are not always resolved to the implementation for the concrete type, and some programs only
+/// compile correctly once every pass of level 2 has run.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum OptLevel {
+    /// Repeats constant folding and CFG simplification to take advantage of the opportunities
+    /// each provides to the other.
+    #[default]
+    O2,
+    /// As `O2`, but only inlines functions when doing so is unlikely to increase the size of the
+    /// bytecode. The other passes never grow the bytecode, so only the inline threshold differs.
+    Size,
+}
+
+/// An optimization pass that may be run over the IR.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OptPass {
+    /// Promote local variables to registers.
+    Mem2reg,
+    /// Inline function calls according to the inline threshold.
+    Inline,
+    /// Fold constant expressions.
+    ConstCombine,
+    /// Merge and remove redundant blocks.
+    SimplifyCfg,
+    /// Remove dead functions and instructions.
+    Dce,
+}
+
+impl OptLevel {
+    /// The default maximum number of instructions in a function for it to be inlined regardless
+    /// of how many times it is called.
+    pub const DEFAULT_INLINE_THRESHOLD: usize = 4;
+    const SIZE_INLINE_THRESHOLD: usize = 1;
+
+    /// The passes run at this level of optimization, in order.
+    pub fn passes(&self) -> Vec<OptPass> {
+        use OptPass::*;
+        match self {
+            Self::O2 | Self::Size => vec![
+                Mem2reg,
+                Inline,
+                ConstCombine,
+                SimplifyCfg,
+                ConstCombine,
+                SimplifyCfg,
+                Dce,
+            ],
+        }
+    }
+
+    /// The inline threshold used at this level of optimization.
+    pub fn inline_threshold(&self) -> usize {
+        match self {
+            Self::Size => Self::SIZE_INLINE_THRESHOLD,
+            _ => Self::DEFAULT_INLINE_THRESHOLD,
+        }
+    }
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::O2 => write!(f, "2"),
+            Self::Size => write!(f, "size"),
+        }
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" | "1" => Err(format!(
+                "optimization level {s} is not yet supported, as some programs only compile \
+                correctly at level 2 or \"size\""
+            )),
+            "2" => Ok(Self::O2),
+            "size" => Ok(Self::Size),
+            _ => Err(format!(
+                "invalid optimization level {s:?}, expected 2 or \"size\""
+            )),
+        }
+    }
+}
+
+// Serialized as in the manifest, i.e. as an integer or the string "size".
+impl Serialize for OptLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::O2 => serializer.serialize_u8(2),
+            Self::Size => serializer.serialize_str("size"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OptLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Level(u64),
+            Name(String),
+        }
+        let s = match Repr::deserialize(deserializer)? {
+            Repr::Level(level) => level.to_string(),
+            Repr::Name(name) => name,
+        };
+        s.parse().map_err(serde::de::Error::custom)
+    }
 }
 
 impl BuildConfig {
@@ -33,8 +152,10 @@ impl BuildConfig {
         let canonical_root_module = match root_module.has_root() {
             true => root_module,
             false => {
+                // Compare against the whole directory name rather than its stem, as the stem of
+                // a versioned directory like `foo-0.1.0` is `foo-0.1`.
                 assert!(
-                    root_module.starts_with(canonical_manifest_dir.file_stem().unwrap()),
+                    root_module.starts_with(canonical_manifest_dir.file_name().unwrap()),
                     "file_name must be either absolute or relative to manifest directory",
                 );
                 canonical_manifest_dir
@@ -50,6 +171,9 @@ impl BuildConfig {
             print_finalized_asm: false,
             print_ir: false,
             include_tests: false,
+            optimization: OptLevel::default(),
+            passes: None,
+            inline_threshold: None,
         }
     }
 
@@ -93,7 +217,131 @@ impl BuildConfig {
         }
     }
 
+    /// The level of optimization applied to the IR.
+    ///
+    /// Default: `OptLevel::O2`
+    pub fn optimization(self, optimization: OptLevel) -> Self {
+        Self {
+            optimization,
+            ..self
+        }
+    }
+
+    /// The optimization passes to run, in order. If `None`, the passes of the optimization level
+    /// are run.
+    ///
+    /// Functions are always inlined where required for correctness, even if `OptPass::Inline` is
+    /// not listed.
+    ///
+    /// Default: `None`
+    pub fn passes(self, passes: Option<Vec<OptPass>>) -> Self {
+        Self { passes, ..self }
+    }
+
+    /// The maximum number of instructions in a function for it to be inlined regardless of how
+    /// many times it is called. If `None`, the threshold of the optimization level is used.
+    ///
+    /// Default: `None`
+    pub fn inline_threshold(self, inline_threshold: Option<usize>) -> Self {
+        Self {
+            inline_threshold,
+            ..self
+        }
+    }
+
+    /// The optimization passes to run, in order.
+    pub(crate) fn opt_passes(&self) -> Vec<OptPass> {
+        self.passes
+            .clone()
+            .unwrap_or_else(|| self.optimization.passes())
+    }
+
+    /// The inline threshold to use when running `OptPass::Inline`.
+    pub(crate) fn opt_inline_threshold(&self) -> usize {
+        self.inline_threshold
+            .unwrap_or_else(|| self.optimization.inline_threshold())
+    }
+
     pub fn canonical_root_module(&self) -> Arc<PathBuf> {
         self.canonical_root_module.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opt_level_from_str() {
+        assert_eq!("2".parse::<OptLevel>(), Ok(OptLevel::O2));
+        assert_eq!("size".parse::<OptLevel>(), Ok(OptLevel::Size));
+        for s in ["0", "1"] {
+            assert_eq!(
+                s.parse::<OptLevel>(),
+                Err(format!(
+                    "optimization level {s} is not yet supported, as some programs only compile \
+                    correctly at level 2 or \"size\""
+                ))
+            );
+        }
+        for s in ["3", "s", ""] {
+            assert_eq!(
+                s.parse::<OptLevel>(),
+                Err(format!(
+                    "invalid optimization level {s:?}, expected 2 or \"size\""
+                ))
+            );
+        }
+        for level in [OptLevel::O2, OptLevel::Size] {
+            assert_eq!(level.to_string().parse::<OptLevel>(), Ok(level));
+        }
+    }
+
+    #[test]
+    fn test_root_module_relative_to_versioned_manifest_dir() {
+        // Registry packages are fetched into directories named after their version.
+        let config = BuildConfig::root_from_file_name_and_manifest_path(
+            PathBuf::from("foo-0.1.0/src/main.sw"),
+            PathBuf::from("/registry/foo-0.1.0"),
+        );
+        assert_eq!(
+            *config.canonical_root_module(),
+            PathBuf::from("/registry/foo-0.1.0/src/main.sw")
+        );
+    }
+
+    #[test]
+    fn test_opt_passes() {
+        use OptPass::*;
+        let config = BuildConfig::root_from_file_name_and_manifest_path(
+            PathBuf::from("/project/src/main.sw"),
+            PathBuf::from("/project"),
+        );
+        let o2_passes = vec![
+            Mem2reg,
+            Inline,
+            ConstCombine,
+            SimplifyCfg,
+            ConstCombine,
+            SimplifyCfg,
+            Dce,
+        ];
+        assert_eq!(config.opt_passes(), o2_passes);
+        assert_eq!(
+            config.opt_inline_threshold(),
+            OptLevel::DEFAULT_INLINE_THRESHOLD
+        );
+
+        // Optimizing for size runs the same passes, but inlines fewer functions.
+        let size = config.clone().optimization(OptLevel::Size);
+        assert_eq!(size.opt_passes(), o2_passes);
+        assert_eq!(size.opt_inline_threshold(), 1);
+
+        // Explicit passes and thresholds take precedence over those of the level.
+        let custom = size
+            .passes(Some(vec![Mem2reg, Dce]))
+            .inline_threshold(Some(8));
+        assert_eq!(custom.opt_passes(), vec![Mem2reg, Dce]);
+        assert_eq!(custom.opt_inline_threshold(), 8);
+    }
+}
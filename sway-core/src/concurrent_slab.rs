@@ -73,6 +73,21 @@ where
         let inner = self.inner.read().unwrap();
         inner.iter().any(f)
     }
+
+    pub fn is_empty(&self) -> bool {
+        let inner = self.inner.read().unwrap();
+        inner.is_empty()
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        let inner = self.inner.read().unwrap();
+        inner.clone()
+    }
+
+    pub fn set_all(&self, values: Vec<T>) {
+        let mut inner = self.inner.write().unwrap();
+        *inner = values;
+    }
 }
 
 impl ConcurrentSlab<TypeInfo> {
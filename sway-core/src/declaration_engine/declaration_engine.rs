@@ -5,6 +5,7 @@ use std::{
 };
 
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{Span, Spanned};
 
@@ -36,6 +37,13 @@ impl fmt::Display for DeclarationEngine {
     }
 }
 
+/// The contents of a [DeclarationEngine], from which an identical engine may be restored.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct DeclarationEngineSnapshot {
+    declarations: Vec<DeclarationWrapper>,
+    parents: Vec<(usize, Vec<DeclarationId>)>,
+}
+
 impl DeclarationEngine {
     fn clear(&self) {
         self.slab.clear();
@@ -43,6 +51,27 @@ impl DeclarationEngine {
         parents.clear();
     }
 
+    fn is_empty(&self) -> bool {
+        self.slab.is_empty() && self.parents.read().unwrap().is_empty()
+    }
+
+    fn snapshot(&self) -> DeclarationEngineSnapshot {
+        let parents = self.parents.read().unwrap();
+        DeclarationEngineSnapshot {
+            declarations: self.slab.to_vec(),
+            parents: parents
+                .iter()
+                .map(|(index, parents)| (*index, parents.clone()))
+                .collect(),
+        }
+    }
+
+    fn restore(&self, snapshot: DeclarationEngineSnapshot) {
+        self.slab.set_all(snapshot.declarations);
+        let mut parents = self.parents.write().unwrap();
+        *parents = snapshot.parents.into_iter().collect();
+    }
+
     fn look_up_decl_id(&self, index: DeclarationId) -> DeclarationWrapper {
         self.slab.get(*index)
     }
@@ -226,6 +255,19 @@ pub(crate) fn de_clear() {
     DECLARATION_ENGINE.clear();
 }
 
+pub(crate) fn de_is_empty() -> bool {
+    DECLARATION_ENGINE.is_empty()
+}
+
+pub(crate) fn de_snapshot() -> DeclarationEngineSnapshot {
+    DECLARATION_ENGINE.snapshot()
+}
+
+/// Replace the contents of the declaration engine with those of the given snapshot.
+pub(crate) fn de_restore(snapshot: DeclarationEngineSnapshot) {
+    DECLARATION_ENGINE.restore(snapshot)
+}
+
 pub fn de_look_up_decl_id(index: DeclarationId) -> DeclarationWrapper {
     DECLARATION_ENGINE.look_up_decl_id(index)
 }
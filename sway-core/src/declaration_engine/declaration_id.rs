@@ -1,5 +1,6 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
 use sway_types::{Span, Spanned};
 
 use crate::{
@@ -15,7 +16,7 @@ use super::{
 };
 
 /// An ID used to refer to an item in the [DeclarationEngine](super::declaration_engine::DeclarationEngine)
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DeclarationId(usize, Span);
 
 impl Clone for DeclarationId {
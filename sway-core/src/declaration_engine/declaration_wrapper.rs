@@ -1,5 +1,6 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::Span;
 
@@ -13,7 +14,7 @@ use super::{DeclMapping, ReplaceDecls, ReplaceFunctionImplementingType};
 
 /// The [DeclarationWrapper] type is used in the [DeclarationEngine]
 /// as a means of placing all declaration types into the same type.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum DeclarationWrapper {
     // no-op variant to fulfill the default trait
     Unknown,
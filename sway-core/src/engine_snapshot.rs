@@ -0,0 +1,46 @@
+//! Snapshots of the compiler's engines, with which the results of type-checking a set of libraries
+//! may be reused by later compilations.
+
+use crate::{
+    declaration_engine::{de_is_empty, de_restore, de_snapshot, DeclarationEngineSnapshot},
+    type_system::{TypeEngine, TypeEngineSnapshot},
+};
+use serde::{Deserialize, Serialize};
+
+/// The contents of a [TypeEngine] and of the declaration engine.
+///
+/// The declarations and namespaces produced by type-checking a library refer to both engines by
+/// index. A snapshot taken after compiling a set of libraries, starting from empty engines, may
+/// later be restored into empty engines in place of compiling those libraries again.
+#[derive(Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    types: TypeEngineSnapshot,
+    declarations: DeclarationEngineSnapshot,
+}
+
+impl EngineSnapshot {
+    /// Whether both the given [TypeEngine] and the declaration engine are empty.
+    pub fn engines_are_empty(type_engine: &TypeEngine) -> bool {
+        type_engine.is_empty() && de_is_empty()
+    }
+
+    /// Take a snapshot of the given [TypeEngine] and of the declaration engine.
+    pub fn take(type_engine: &TypeEngine) -> Self {
+        EngineSnapshot {
+            types: type_engine.snapshot(),
+            declarations: de_snapshot(),
+        }
+    }
+
+    /// Restore the given [TypeEngine] and the declaration engine from this snapshot.
+    ///
+    /// Returns `false`, leaving both engines unchanged, if either engine is not empty.
+    pub fn restore(self, type_engine: &TypeEngine) -> bool {
+        if !Self::engines_are_empty(type_engine) {
+            return false;
+        }
+        type_engine.restore(self.types);
+        de_restore(self.declarations);
+        true
+    }
+}
@@ -1,8 +1,9 @@
 use std::hash::{Hash, Hasher};
 
+use serde::{Deserialize, Serialize};
 use sway_types::{Ident, Span};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AsmOp {
     pub(crate) op_name: Ident,
     pub(crate) op_args: Vec<Ident>,
@@ -38,7 +39,7 @@ impl PartialEq for AsmOp {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AsmRegister {
     pub(crate) name: String,
 }
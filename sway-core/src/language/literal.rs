@@ -1,5 +1,6 @@
 use crate::type_system::*;
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{integer_bits::IntegerBits, span};
 
@@ -9,7 +10,7 @@ use std::{
     num::{IntErrorKind, ParseIntError},
 };
 
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub enum Literal {
     U8(u8),
     U16(u16),
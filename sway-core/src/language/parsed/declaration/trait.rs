@@ -1,6 +1,7 @@
 use super::{FunctionDeclaration, FunctionParameter};
 
 use crate::{language::*, transform, type_system::*};
+use serde::{Deserialize, Serialize};
 use sway_types::{ident::Ident, span::Span, Spanned};
 
 #[derive(Debug, Clone)]
@@ -15,7 +16,7 @@ pub struct TraitDeclaration {
     pub span: Span,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Supertrait {
     pub name: CallPath,
 }
@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 /// The purity of a function is related to its access of contract storage. If a function accesses
 /// or could potentially access contract storage, it is [Purity::Impure]. If a function does not utilize any
 /// any accesses (reads _or_ writes) of storage, then it is [Purity::Pure].
-#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Purity {
     Pure,
     Reads,
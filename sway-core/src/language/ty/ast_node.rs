@@ -1,5 +1,6 @@
 use std::fmt::{self, Debug};
 
+use serde::{Deserialize, Serialize};
 use sway_types::{Ident, Span};
 
 use crate::{
@@ -15,7 +16,7 @@ pub trait GetDeclIdent {
     fn get_decl_ident(&self) -> Option<Ident>;
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyAstNode {
     pub content: TyAstNodeContent,
     pub(crate) span: Span,
@@ -224,7 +225,7 @@ impl TyAstNode {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TyAstNodeContent {
     Declaration(TyDeclaration),
     Expression(TyExpression),
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     declaration_engine::{DeclMapping, ReplaceDecls},
     language::ty::*,
@@ -5,7 +7,7 @@ use crate::{
     types::DeterministicallyAborts,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyCodeBlock {
     pub contents: Vec<TyAstNode>,
 }
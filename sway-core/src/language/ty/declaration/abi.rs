@@ -1,9 +1,10 @@
+use serde::{Deserialize, Serialize};
 use sway_types::{Ident, Span};
 
 use crate::{declaration_engine::DeclarationId, transform, type_system::*};
 
 /// A [TyAbiDeclaration] contains the type-checked version of the parse tree's `AbiDeclaration`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyAbiDeclaration {
     /// The name of the abi trait (also known as a "contract trait")
     pub name: Ident,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use sway_types::{Ident, Span};
 
 use crate::{
@@ -7,7 +8,7 @@ use crate::{
     EqWithTypeEngine, PartialEqWithTypeEngine,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyConstantDeclaration {
     pub name: Ident,
     pub value: TyExpression,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{Ident, Span, Spanned};
 
@@ -8,7 +9,7 @@ use crate::{
     type_system::*,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TyDeclaration {
     VariableDeclaration(Box<TyVariableDeclaration>),
     ConstantDeclaration(DeclarationId),
@@ -1,12 +1,13 @@
 use std::hash::{Hash, Hasher};
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{Ident, Span, Spanned};
 
 use crate::PartialEqWithTypeEngine;
 use crate::{error::*, language::Visibility, transform, type_system::*};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyEnumDeclaration {
     pub name: Ident,
     pub type_parameters: Vec<TypeParameter>,
@@ -102,7 +103,7 @@ impl TyEnumDeclaration {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TyEnumVariant {
     pub name: Ident,
     pub type_id: TypeId,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sway_types::{Ident, Span, Spanned};
 
@@ -11,7 +12,7 @@ use crate::{
 
 use sway_types::constants::{INLINE_ALWAYS_NAME, INLINE_NEVER_NAME};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyFunctionDeclaration {
     pub name: Ident,
     pub body: TyCodeBlock,
@@ -351,6 +352,7 @@ impl TyFunctionDeclaration {
             .last()?
             .args
             .first()?
+            .name
             .as_str()
         {
             INLINE_NEVER_NAME => Some(Inline::Never),
@@ -365,7 +367,7 @@ impl TyFunctionDeclaration {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TyFunctionParameter {
     pub name: Ident,
     pub is_reference: bool,
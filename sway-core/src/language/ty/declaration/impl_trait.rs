@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use sway_types::Span;
 
 use crate::{declaration_engine::DeclarationId, language::CallPath, type_system::*};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyImplTrait {
     pub impl_type_parameters: Vec<TypeParameter>,
     pub trait_name: CallPath,
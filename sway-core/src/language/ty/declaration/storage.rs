@@ -1,9 +1,10 @@
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{state::StateIndex, Ident, Span, Spanned};
 
 use crate::{error::*, language::ty::*, transform, type_system::*};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyStorageDeclaration {
     pub fields: Vec<TyStorageField>,
     pub span: Span,
@@ -154,7 +155,7 @@ impl TyStorageDeclaration {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyStorageField {
     pub name: Ident,
     pub type_id: TypeId,
@@ -1,11 +1,12 @@
 use std::hash::{Hash, Hasher};
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{Ident, Span, Spanned};
 
 use crate::{error::*, language::Visibility, transform, type_system::*};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyStructDeclaration {
     pub name: Ident,
     pub fields: Vec<TyStructField>,
@@ -103,7 +104,7 @@ impl TyStructDeclaration {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TyStructField {
     pub name: Ident,
     pub type_id: TypeId,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use sway_types::{Ident, Span};
 
 use crate::{
@@ -7,7 +8,7 @@ use crate::{
     type_system::*,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyTraitDeclaration {
     pub name: Ident,
     pub type_parameters: Vec<TypeParameter>,
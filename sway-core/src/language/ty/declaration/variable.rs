@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use sway_types::{Ident, Span};
 
 use crate::{language::ty::*, type_system::*};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyVariableDeclaration {
     pub name: Ident,
     pub body: TyExpression,
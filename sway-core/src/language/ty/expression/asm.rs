@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use sway_types::Ident;
 
 use crate::{language::ty::*, type_system::*};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyAsmRegisterDeclaration {
     pub(crate) initializer: Option<TyExpression>,
     pub(crate) name: Ident,
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::language::ty::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ContractCallParams {
     pub(crate) func_selector: [u8; 4],
     pub(crate) contract_address: Box<TyExpression>,
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
 use sway_types::{Span, Spanned};
 
 use crate::{
@@ -10,7 +11,7 @@ use crate::{
     types::DeterministicallyAborts,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyExpression {
     pub expression: TyExpressionVariant,
     pub return_type: TypeId,
@@ -3,6 +3,7 @@ use std::{
     fmt::{self, Write},
 };
 
+use serde::{Deserialize, Serialize};
 use sway_types::{state::StateIndex, Ident, Span};
 
 use crate::{
@@ -11,7 +12,7 @@ use crate::{
     type_system::*,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TyExpressionVariant {
     Literal(Literal),
     FunctionApplication {
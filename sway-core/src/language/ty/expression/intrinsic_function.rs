@@ -2,10 +2,11 @@ use std::fmt;
 
 use crate::{error::*, language::ty::*, type_system::*, types::DeterministicallyAborts};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use sway_ast::Intrinsic;
 use sway_types::Span;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TyIntrinsicFunctionKind {
     pub kind: Intrinsic,
     pub arguments: Vec<TyExpression>,
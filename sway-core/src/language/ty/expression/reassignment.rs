@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use serde::{Deserialize, Serialize};
 use sway_types::{state::StateIndex, Ident, Span, Spanned};
 
 use crate::{
@@ -8,7 +9,7 @@ use crate::{
     type_system::*,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyReassignment {
     // either a direct variable, so length of 1, or
     // at series of struct fields/array indices (array syntax)
@@ -48,7 +49,7 @@ impl ReplaceDecls for TyReassignment {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ProjectionKind {
     StructField {
         name: Ident,
@@ -117,7 +118,7 @@ impl ProjectionKind {
 }
 
 /// Describes each field being drilled down into in storage and its type.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyStorageReassignment {
     pub fields: Vec<TyStorageReassignDescriptor>,
     pub(crate) ix: StateIndex,
@@ -154,7 +155,7 @@ impl TyStorageReassignment {
 
 /// Describes a single subfield access in the sequence when reassigning to a subfield within
 /// storage.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyStorageReassignDescriptor {
     pub name: Ident,
     pub type_id: TypeId,
@@ -1,9 +1,10 @@
+use serde::{Deserialize, Serialize};
 use sway_types::{state::StateIndex, Ident, Span, Spanned};
 
 use crate::type_system::TypeId;
 
 /// Describes the full storage access including all the subfields
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TyStorageAccess {
     pub fields: Vec<TyStorageAccessDescriptor>,
     pub(crate) ix: StateIndex,
@@ -26,7 +27,7 @@ impl TyStorageAccess {
 }
 
 /// Describes a single subfield access in the sequence when accessing a subfield within storage.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TyStorageAccessDescriptor {
     pub name: Ident,
     pub(crate) type_id: TypeId,
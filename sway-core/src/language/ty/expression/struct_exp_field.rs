@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use sway_types::Ident;
 
 use crate::{
@@ -6,7 +7,7 @@ use crate::{
     type_system::*,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TyStructExpressionField {
     pub name: Ident,
     pub value: TyExpression,
@@ -391,12 +391,40 @@ impl TyProgram {
                     messages_types: Some(messages_types),
                 }
             }
-            _ => fuels_types::ProgramABI {
-                types: vec![],
-                functions: vec![],
-                logged_types: None,
-                messages_types: None,
-            },
+            TyProgramKind::Library { .. } => {
+                // Libraries have no ABI functions, however their logged types are still required
+                // in order to decode the logs of any unit tests declared within them.
+                let logged_types = self.generate_json_logged_types(type_engine, types);
+                fuels_types::ProgramABI {
+                    types: types.to_vec(),
+                    functions: vec![],
+                    logged_types: Some(logged_types),
+                    messages_types: None,
+                }
+            }
+        }
+    }
+
+    /// Generate a JSON ABI describing the program's unit tests, in which each test is represented
+    /// as a function along with the types of its parameters.
+    ///
+    /// The `types` should be those already collected by `generate_json_abi_program` so that the
+    /// resulting ABI also describes the program's logged types.
+    pub fn generate_json_abi_tests(
+        &self,
+        type_engine: &TypeEngine,
+        types: &mut Vec<fuels_types::TypeDeclaration>,
+    ) -> fuels_types::ProgramABI {
+        let functions = self
+            .test_fns()
+            .map(|(test_fn, _)| test_fn.generate_json_abi_function(type_engine, types))
+            .collect();
+        let logged_types = self.generate_json_logged_types(type_engine, types);
+        fuels_types::ProgramABI {
+            types: types.to_vec(),
+            functions,
+            logged_types: Some(logged_types),
+            messages_types: None,
         }
     }
 
@@ -7,6 +7,7 @@ mod build_config;
 mod concurrent_slab;
 mod control_flow_analysis;
 pub mod declaration_engine;
+mod engine_snapshot;
 pub mod ir_generation;
 pub mod language;
 mod metadata;
@@ -21,8 +22,9 @@ use crate::{error::*, source_map::SourceMap};
 pub use asm_generation::from_ir::compile_ir_to_asm;
 use asm_generation::FinalizedAsm;
 pub use asm_generation::FinalizedEntry;
-pub use build_config::BuildConfig;
+pub use build_config::{BuildConfig, OptLevel, OptPass};
 use control_flow_analysis::ControlFlowGraph;
+pub use engine_snapshot::EngineSnapshot;
 use metadata::MetadataManager;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -64,12 +66,20 @@ pub fn parse(
     type_engine: &TypeEngine,
     config: Option<&BuildConfig>,
 ) -> CompileResult<parsed::ParseProgram> {
+    // The names generated while converting the parse tree only depend on the program itself.
+    let mut context = to_parsed_lang::Context::default();
     CompileResult::with_handler(|h| match config {
-        None => parse_in_memory(h, type_engine, input),
+        None => parse_in_memory(&mut context, h, type_engine, input),
         // When a `BuildConfig` is given,
         // the module source may declare `dep`s that must be parsed from other files.
-        Some(config) => parse_module_tree(h, type_engine, input, config.canonical_root_module())
-            .map(|(kind, root)| parsed::ParseProgram { kind, root }),
+        Some(config) => parse_module_tree(
+            &mut context,
+            h,
+            type_engine,
+            input,
+            config.canonical_root_module(),
+        )
+        .map(|(kind, root)| parsed::ParseProgram { kind, root }),
     })
 }
 
@@ -84,12 +94,13 @@ pub fn parse_tree_type(input: Arc<str>) -> CompileResult<parsed::TreeType> {
 
 /// When no `BuildConfig` is given, we're assumed to be parsing in-memory with no submodules.
 fn parse_in_memory(
+    context: &mut to_parsed_lang::Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     src: Arc<str>,
 ) -> Result<parsed::ParseProgram, ErrorEmitted> {
     let module = sway_parse::parse_file(handler, src, None)?;
-    let (kind, tree) = to_parsed_lang::convert_parse_tree(handler, type_engine, module)?;
+    let (kind, tree) = to_parsed_lang::convert_parse_tree(context, handler, type_engine, module)?;
     let submodules = Default::default();
     let root = parsed::ParseModule { tree, submodules };
     Ok(parsed::ParseProgram { kind, root })
@@ -97,6 +108,7 @@ fn parse_in_memory(
 
 /// Parse all dependencies `deps` as submodules.
 fn parse_submodules(
+    context: &mut to_parsed_lang::Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     module: &sway_ast::Module,
@@ -121,9 +133,13 @@ fn parse_submodules(
             }
         };
 
-        if let Ok((kind, module)) =
-            parse_module_tree(handler, type_engine, dep_str.clone(), dep_path.clone())
-        {
+        if let Ok((kind, module)) = parse_module_tree(
+            context,
+            handler,
+            type_engine,
+            dep_str.clone(),
+            dep_path.clone(),
+        ) {
             let library_name = match kind {
                 parsed::TreeType::Library { name } => name,
                 _ => {
@@ -151,6 +167,7 @@ fn parse_submodules(
 /// Given the source of the module along with its path,
 /// parse this module including all of its submodules.
 fn parse_module_tree(
+    context: &mut to_parsed_lang::Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     src: Arc<str>,
@@ -162,10 +179,10 @@ fn parse_module_tree(
 
     // Parse all submodules before converting to the `ParseTree`.
     // This always recovers on parse errors for the file itself by skipping that file.
-    let submodules = parse_submodules(handler, type_engine, &module, module_dir);
+    let submodules = parse_submodules(context, handler, type_engine, &module, module_dir);
 
     // Convert from the raw parsed module to the `ParseTree` ready for type-check.
-    let (kind, tree) = to_parsed_lang::convert_parse_tree(handler, type_engine, module)?;
+    let (kind, tree) = to_parsed_lang::convert_parse_tree(context, handler, type_engine, module)?;
 
     Ok((kind, parsed::ParseModule { tree, submodules }))
 }
@@ -409,44 +426,19 @@ pub(crate) fn compile_ast_to_ir_to_asm(
         errors.extend(e);
     }
 
-    // Now we're working with all functions in the module.
-    let all_functions = ir
-        .module_iter()
-        .flat_map(|module| module.function_iter(&ir))
-        .collect::<Vec<_>>();
-
-    // Promote local values to registers.
     check!(
-        promote_to_registers(&mut ir, &all_functions),
+        optimize(
+            &mut ir,
+            build_config.opt_passes(),
+            build_config.opt_inline_threshold(),
+            &tree_type,
+            &entry_point_functions,
+        ),
         return err(warnings, errors),
         warnings,
         errors
     );
 
-    // Inline function calls.
-    check!(
-        inline_function_calls(&mut ir, &all_functions, &tree_type),
-        return err(warnings, errors),
-        warnings,
-        errors
-    );
-
-    let res = CompileResult::with_handler(|handler| {
-        // TODO: Experiment with putting combine-constants and simplify-cfg
-        // in a loop, but per function.
-        combine_constants(handler, &mut ir, &all_functions)?;
-        simplify_cfg(handler, &mut ir, &all_functions)?;
-        // Simplify-CFG helps combine constants.
-        combine_constants(handler, &mut ir, &all_functions)?;
-        // And that in-turn enables more simplify-cfg.
-        simplify_cfg(handler, &mut ir, &all_functions)?;
-
-        // Remove dead definitions based on the entry points root set.
-        dce(handler, &mut ir, &entry_point_functions)?;
-        Ok(())
-    });
-    check!(res, return err(warnings, errors), warnings, errors);
-
     if build_config.print_ir {
         tracing::info!("{}", ir);
     }
@@ -461,6 +453,70 @@ pub(crate) fn compile_ast_to_ir_to_asm(
     ok(final_asm, warnings, errors)
 }
 
+/// Run the given optimization passes over every function in the IR, in order.
+///
+/// Function calls that must be inlined for correctness are always inlined, even if the inlining
+/// pass is not requested, in which case they're inlined following the promotion of locals to
+/// registers.
+fn optimize(
+    ir: &mut Context,
+    passes: Vec<OptPass>,
+    inline_threshold: usize,
+    tree_type: &parsed::TreeType,
+    entry_point_functions: &[Function],
+) -> CompileResult<()> {
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    let all_functions = ir
+        .module_iter()
+        .flat_map(|module| module.function_iter(ir))
+        .collect::<Vec<_>>();
+    let mut required_inlining_pending = !passes.contains(&OptPass::Inline);
+    let inline_required = |ir: &mut Context, pending: &mut bool| {
+        if !std::mem::take(pending) {
+            return ok((), vec![], vec![]);
+        }
+        inline_function_calls_with_threshold(ir, &all_functions, tree_type, None)
+    };
+    for pass in passes {
+        if pass != OptPass::Mem2reg {
+            check!(
+                inline_required(ir, &mut required_inlining_pending),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+        }
+        let res = match pass {
+            OptPass::Mem2reg => promote_to_registers(ir, &all_functions),
+            OptPass::Inline => inline_function_calls_with_threshold(
+                ir,
+                &all_functions,
+                tree_type,
+                Some(inline_threshold),
+            ),
+            OptPass::ConstCombine => CompileResult::with_handler(|handler| {
+                combine_constants(handler, ir, &all_functions)
+            }),
+            OptPass::SimplifyCfg => {
+                CompileResult::with_handler(|handler| simplify_cfg(handler, ir, &all_functions))
+            }
+            // Remove dead definitions based on the entry points root set.
+            OptPass::Dce => {
+                CompileResult::with_handler(|handler| dce(handler, ir, entry_point_functions))
+            }
+        };
+        check!(res, return err(warnings, errors), warnings, errors);
+    }
+    check!(
+        inline_required(ir, &mut required_inlining_pending),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok((), warnings, errors)
+}
+
 fn promote_to_registers(ir: &mut Context, functions: &[Function]) -> CompileResult<()> {
     for function in functions {
         if let Err(ir_error) = sway_ir::optimize::promote_to_registers(ir, function) {
@@ -487,6 +543,25 @@ pub fn inline_function_calls(
     ir: &mut Context,
     functions: &[Function],
     tree_type: &parsed::TreeType,
+) -> CompileResult<()> {
+    inline_function_calls_with_threshold(
+        ir,
+        functions,
+        tree_type,
+        Some(OptLevel::DEFAULT_INLINE_THRESHOLD),
+    )
+}
+
+/// Inline function calls as per [inline_function_calls], inlining functions with at most
+/// `inline_threshold` instructions.
+///
+/// If `inline_threshold` is `None`, only those function calls that must be inlined for the program
+/// to compile correctly are inlined.
+pub fn inline_function_calls_with_threshold(
+    ir: &mut Context,
+    functions: &[Function],
+    tree_type: &parsed::TreeType,
+    inline_threshold: Option<usize>,
 ) -> CompileResult<()> {
     // Inspect ALL calls and count how often each function is called.
     // This is not required for predicates because we don't inline their function calls
@@ -528,24 +603,26 @@ pub fn inline_function_calls(
             return true;
         }
 
-        // If the function is called only once then definitely inline it.
-        if call_counts.get(func).copied().unwrap_or(0) == 1 {
-            return true;
-        }
+        if let Some(inline_threshold) = inline_threshold {
+            // If the function is called only once then definitely inline it.
+            if call_counts.get(func).copied().unwrap_or(0) == 1 {
+                return true;
+            }
 
-        // If the function is (still) small then also inline it.
-        const MAX_INLINE_INSTRS_COUNT: usize = 4;
-        if func.num_instructions(ctx) <= MAX_INLINE_INSTRS_COUNT {
-            return true;
+            // If the function is (still) small then also inline it.
+            if func.num_instructions(ctx) <= inline_threshold {
+                return true;
+            }
         }
 
         // As per https://github.com/FuelLabs/sway/issues/2819 we can hit problems if a function
         // argument is used as a pointer (probably because it has a ref type) although it actually
-        // isn't one.  Ref type args which aren't pointers need to be inlined.
+        // isn't one.  Ref type args which aren't pointers need to be inlined.  Likewise, ASMgen
+        // can't yet load from or store to pointer args, e.g. `ref mut` args of copy types.
         if func.args_iter(ctx).any(|(_name, arg_val)| {
             arg_val
                 .get_type(ctx)
-                .map(|ty| !ty.is_copy_type())
+                .map(|ty| !ty.is_copy_type() || ty.is_ptr_type())
                 .unwrap_or(false)
         }) {
             return true;
@@ -877,6 +954,98 @@ fn test_parenthesized() {
     prog.unwrap(&mut warnings, &mut errors);
 }
 
+#[test]
+fn test_optimize_required_inlining() {
+    use parsed::TreeType;
+    let optimized = |kind: &str, tree_type: TreeType, passes: Vec<OptPass>| {
+        let ir_str = format!(
+            "{kind} {{
+                fn is_one(x: u64) -> bool {{
+                    entry(x: u64):
+                    v0 = const u64 1
+                    v1 = cmp eq x v0
+                    ret bool v1
+                }}
+
+                entry fn main() -> bool {{
+                    entry():
+                    v0 = const u64 1
+                    v1 = call is_one(v0)
+                    v2 = call is_one(v0)
+                    ret bool v2
+                }}
+            }}"
+        );
+        let mut ir = sway_ir::parser::parse(&ir_str).unwrap();
+        let entry_points: Vec<Function> = ir
+            .module_iter()
+            .flat_map(|module| module.function_iter(&ir))
+            .filter(|func| func.is_entry(&ir))
+            .collect();
+        let res = optimize(
+            &mut ir,
+            passes,
+            OptLevel::DEFAULT_INLINE_THRESHOLD,
+            &tree_type,
+            &entry_points,
+        );
+        assert!(res.errors.is_empty());
+        ir.to_string()
+    };
+    use OptPass::*;
+
+    // Calls within predicates must always be inlined, even if the inlining pass is not run.
+    let predicate = optimized("predicate", TreeType::Predicate, vec![Mem2reg, Dce]);
+    assert!(!predicate.contains("call "));
+    let predicate = optimized("predicate", TreeType::Predicate, vec![]);
+    assert!(!predicate.contains("call "));
+
+    // Otherwise, calls are only inlined according to the threshold by the inlining pass.
+    let script = optimized("script", TreeType::Script, vec![Mem2reg, Dce]);
+    assert!(script.contains("call "));
+    let script = optimized("script", TreeType::Script, vec![Mem2reg, Inline, Dce]);
+    assert!(!script.contains("call "));
+
+    // Functions with pointer args, e.g. `ref mut` args of copy types, must always be inlined.
+    let mut ir = sway_ir::parser::parse(
+        "script {
+            fn mut_arg(b: mut ptr bool) -> () {
+                entry(b: mut ptr bool):
+                v0 = const bool true
+                store v0, ptr b
+                v1 = const unit ()
+                ret () v1
+            }
+
+            entry fn main() -> bool {
+                local mut ptr bool b
+
+                entry():
+                v0 = get_ptr mut ptr bool b, ptr bool, 0
+                v1 = call mut_arg(v0)
+                v2 = call mut_arg(v0)
+                v3 = load ptr v0
+                ret bool v3
+            }
+        }",
+    )
+    .unwrap();
+    let entry_points: Vec<Function> = ir
+        .module_iter()
+        .flat_map(|module| module.function_iter(&ir))
+        .filter(|func| func.is_entry(&ir))
+        .collect();
+    let res = optimize(
+        &mut ir,
+        vec![OptPass::Mem2reg, OptPass::Dce],
+        OptLevel::DEFAULT_INLINE_THRESHOLD,
+        &TreeType::Script,
+        &entry_points,
+    );
+    assert!(res.errors.is_empty());
+    assert!(!ir.to_string().contains("call "));
+}
+
 #[test]
 fn test_unary_ordering() {
     use crate::language::{self, parsed};
@@ -7,6 +7,7 @@ use crate::{
     error::*,
     language::{parsed::*, ty, Visibility},
     semantic_analysis::*,
+    transform,
     type_system::*,
 };
 use sway_types::{style::is_snake_case, Spanned};
@@ -48,6 +49,14 @@ impl ty::TyFunctionDeclaration {
         let mut fn_namespace = ctx.namespace.clone();
         let mut fn_ctx = ctx.by_ref().scoped(&mut fn_namespace).with_purity(purity);
 
+        // symbols provided only to tests don't shadow those declared within the module
+        if attributes.contains_key(&transform::AttributeKind::Test) {
+            let test_symbols = fn_ctx.namespace.test_symbols.clone();
+            for (name, decl) in test_symbols {
+                fn_ctx.namespace.symbols.entry(name).or_insert(decl);
+            }
+        }
+
         // type check the type parameters, which will also insert them into the namespace
         let mut new_type_parameters = vec![];
         for type_parameter in type_parameters.into_iter() {
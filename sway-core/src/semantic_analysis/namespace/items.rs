@@ -8,6 +8,7 @@ use crate::{
 
 use super::TraitMap;
 
+use serde::{Deserialize, Serialize};
 use sway_error::{
     error::CompileError,
     warning::{CompileWarning, Warning},
@@ -17,7 +18,7 @@ use sway_types::{span::Span, Spanned};
 use std::sync::Arc;
 
 /// Is this a glob (`use foo::*;`) import?
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub(crate) enum GlobImport {
     Yes,
     No,
@@ -28,7 +29,7 @@ pub(super) type UseSynonyms = im::HashMap<Ident, (Vec<Ident>, GlobImport)>;
 pub(super) type UseAliases = im::HashMap<String, Ident>;
 
 /// The set of items that exist within some lexical scope via declaration or importing.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Items {
     /// An ordered map from `Ident`s to their associated typed declarations.
     pub(crate) symbols: SymbolMap,
@@ -45,6 +46,11 @@ pub struct Items {
     pub(crate) use_aliases: UseAliases,
     /// If there is a storage declaration (which are only valid in contracts), store it here.
     pub(crate) declared_storage: Option<DeclarationId>,
+    /// Symbols that are only in scope within test functions.
+    ///
+    /// For example, a contract that is type-checked without its tests still provides its tests
+    /// with a placeholder for its own `CONTRACT_ID`.
+    pub(crate) test_symbols: SymbolMap,
 }
 
 impl Items {
@@ -13,6 +13,7 @@ use super::{
     ModuleName, Path,
 };
 
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use sway_ast::ItemConst;
 use sway_error::handler::Handler;
@@ -28,7 +29,7 @@ use sway_types::{span::Span, ConfigTimeConstant, Spanned};
 ///
 /// A `Module` contains a set of all items that exist within the lexical scope via declaration or
 /// importing, along with a map of each of its submodules.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Module {
     /// Submodules of the current module represented as an ordered map from each submodule's name
     /// to the associated `Module`.
@@ -57,6 +58,18 @@ impl Module {
         })
     }
 
+    /// Compile the given constants and provide them only to the test functions of this module and
+    /// of its submodules.
+    pub fn insert_test_constants(
+        &mut self,
+        type_engine: &TypeEngine,
+        constants: BTreeMap<String, ConfigTimeConstant>,
+    ) -> Result<(), vec1::Vec1<CompileError>> {
+        let constants = Module::default_with_constants(type_engine, constants)?;
+        self.items.test_symbols.extend(constants.items.symbols);
+        Ok(())
+    }
+
     fn default_with_constants_inner(
         handler: &Handler,
         type_engine: &TypeEngine,
@@ -94,6 +107,7 @@ impl Module {
             let attributes = Default::default();
             // convert to const decl
             let const_decl = to_parsed_lang::item_const_to_constant_declaration(
+                &mut to_parsed_lang::Context::default(),
                 handler,
                 type_engine,
                 const_item,
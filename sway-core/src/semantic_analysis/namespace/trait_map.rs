@@ -1,5 +1,6 @@
 use std::collections::BTreeSet;
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{Ident, Span, Spanned};
 
@@ -12,7 +13,7 @@ use crate::{
     TypeEngine, TypeInfo, TypeMapping,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TraitSuffix {
     name: Ident,
     args: Vec<TypeArgument>,
@@ -48,7 +49,7 @@ impl<T: OrdWithTypeEngine> OrdWithTypeEngine for CallPath<T> {
 
 type TraitName = CallPath<TraitSuffix>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TraitKey {
     name: TraitName,
     type_id: TypeId,
@@ -65,7 +66,7 @@ impl OrdWithTypeEngine for TraitKey {
 /// Map of function name to [TyFunctionDeclaration](ty::TyFunctionDeclaration)
 type TraitMethods = im::HashMap<String, DeclarationId>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 struct TraitEntry {
     key: TraitKey,
     value: TraitMethods,
@@ -78,7 +79,7 @@ type TraitImpls = Vec<TraitEntry>;
 ///
 /// Note: "impl self" blocks are considered traits and are stored in the
 /// [TraitMap].
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub(crate) struct TraitMap {
     trait_impls: TraitImpls,
 }
@@ -19,22 +19,35 @@
 //! is
 //!
 //!   #[foo(bar, bar)]
+//!
+//! Each arg may also be assigned a literal value, e.g. `#[test(should_revert = "42")]`.
 
+use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
+use sway_ast::Literal;
 use sway_types::{Ident, Span};
 
+/// An argument of an attribute, with an optional literal value
+/// and a span from its declaration.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AttributeArg {
+    pub name: Ident,
+    pub value: Option<Literal>,
+    pub span: Span,
+}
+
 /// An attribute has a name (i.e "doc", "storage"),
 /// a vector of possible arguments and
 /// a span from its declaration.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Attribute {
     pub name: Ident,
-    pub args: Vec<Ident>,
+    pub args: Vec<AttributeArg>,
     pub span: Span,
 }
 
 /// Valid kinds of attributes supported by the compiler
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AttributeKind {
     Doc,
     DocComment,
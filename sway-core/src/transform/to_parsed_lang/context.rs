@@ -0,0 +1,34 @@
+/// State shared across the conversion of all the modules of a program's parse tree.
+#[derive(Default)]
+pub struct Context {
+    /// The suffix of the name of the next variable returned by a match expression.
+    match_return_var_suffix: usize,
+    /// The suffix of the name of the next destructured struct.
+    destructured_struct_suffix: usize,
+    /// The suffix of the name of the next destructured tuple.
+    destructured_tuple_suffix: usize,
+}
+
+impl Context {
+    /// Returns a suffix that makes the name of a variable returned by a match expression unique
+    /// within the program.
+    pub fn next_match_return_var_suffix(&mut self) -> usize {
+        next_suffix(&mut self.match_return_var_suffix)
+    }
+
+    /// Returns a suffix that makes the name of a destructured struct unique within the program.
+    pub fn next_destructured_struct_suffix(&mut self) -> usize {
+        next_suffix(&mut self.destructured_struct_suffix)
+    }
+
+    /// Returns a suffix that makes the name of a destructured tuple unique within the program.
+    pub fn next_destructured_tuple_suffix(&mut self) -> usize {
+        next_suffix(&mut self.destructured_tuple_suffix)
+    }
+}
+
+fn next_suffix(counter: &mut usize) -> usize {
+    let suffix = *counter;
+    *counter += 1;
+    suffix
+}
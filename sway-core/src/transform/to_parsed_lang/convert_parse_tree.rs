@@ -1,3 +1,4 @@
+use super::Context;
 use crate::{
     language::{parsed::*, *},
     transform::attribute::*,
@@ -36,19 +37,17 @@ use std::{
     iter,
     mem::MaybeUninit,
     ops::ControlFlow,
-    sync::{
-        atomic::{AtomicUsize, Ordering},
-        Arc,
-    },
+    sync::Arc,
 };
 
 pub fn convert_parse_tree(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     module: Module,
 ) -> Result<(TreeType, ParseTree), ErrorEmitted> {
     let tree_type = convert_module_kind(&module.kind);
-    let tree = module_to_sway_parse_tree(handler, type_engine, module)?;
+    let tree = module_to_sway_parse_tree(context, handler, type_engine, module)?;
     Ok((tree_type, tree))
 }
 
@@ -63,6 +62,7 @@ pub fn convert_module_kind(kind: &ModuleKind) -> TreeType {
 }
 
 pub fn module_to_sway_parse_tree(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     module: Module,
@@ -72,7 +72,8 @@ pub fn module_to_sway_parse_tree(
         let mut root_nodes: Vec<AstNode> = vec![];
         let mut prev_item: Option<Annotated<ItemKind>> = None;
         for item in module.items {
-            let ast_nodes = item_to_ast_nodes(handler, type_engine, item.clone(), true, prev_item)?;
+            let ast_nodes =
+                item_to_ast_nodes(context, handler, type_engine, item.clone(), true, prev_item)?;
             root_nodes.extend(ast_nodes);
             prev_item = Some(item);
         }
@@ -91,6 +92,7 @@ fn ast_node_is_test_fn(node: &AstNode) -> bool {
 }
 
 fn item_to_ast_nodes(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item: Item,
@@ -153,14 +155,25 @@ fn item_to_ast_nodes(
             .map(AstNodeContent::UseStatement)
             .collect(),
         ItemKind::Struct(item_struct) => decl(Declaration::StructDeclaration(
-            item_struct_to_struct_declaration(handler, type_engine, item_struct, attributes)?,
+            item_struct_to_struct_declaration(
+                context,
+                handler,
+                type_engine,
+                item_struct,
+                attributes,
+            )?,
         )),
         ItemKind::Enum(item_enum) => decl(Declaration::EnumDeclaration(
-            item_enum_to_enum_declaration(handler, type_engine, item_enum, attributes)?,
+            item_enum_to_enum_declaration(context, handler, type_engine, item_enum, attributes)?,
         )),
         ItemKind::Fn(item_fn) => {
-            let function_declaration =
-                item_fn_to_function_declaration(handler, type_engine, item_fn, attributes)?;
+            let function_declaration = item_fn_to_function_declaration(
+                context,
+                handler,
+                type_engine,
+                item_fn,
+                attributes,
+            )?;
             for param in &function_declaration.parameters {
                 if matches!(param.type_info, TypeInfo::SelfType) {
                     let error = ConvertParseTreeError::SelfParameterNotAllowedForFreeFn {
@@ -172,22 +185,38 @@ fn item_to_ast_nodes(
             decl(Declaration::FunctionDeclaration(function_declaration))
         }
         ItemKind::Trait(item_trait) => decl(Declaration::TraitDeclaration(
-            item_trait_to_trait_declaration(handler, type_engine, item_trait, attributes)?,
+            item_trait_to_trait_declaration(context, handler, type_engine, item_trait, attributes)?,
         )),
-        ItemKind::Impl(item_impl) => {
-            decl(item_impl_to_declaration(handler, type_engine, item_impl)?)
-        }
+        ItemKind::Impl(item_impl) => decl(item_impl_to_declaration(
+            context,
+            handler,
+            type_engine,
+            item_impl,
+        )?),
         ItemKind::Abi(item_abi) => decl(Declaration::AbiDeclaration(item_abi_to_abi_declaration(
+            context,
             handler,
             type_engine,
             item_abi,
             attributes,
         )?)),
         ItemKind::Const(item_const) => decl(Declaration::ConstantDeclaration(
-            item_const_to_constant_declaration(handler, type_engine, item_const, attributes)?,
+            item_const_to_constant_declaration(
+                context,
+                handler,
+                type_engine,
+                item_const,
+                attributes,
+            )?,
         )),
         ItemKind::Storage(item_storage) => decl(Declaration::StorageDeclaration(
-            item_storage_to_storage_declaration(handler, type_engine, item_storage, attributes)?,
+            item_storage_to_storage_declaration(
+                context,
+                handler,
+                type_engine,
+                item_storage,
+                attributes,
+            )?,
         )),
     };
 
@@ -283,6 +312,7 @@ fn emit_all(handler: &Handler, errors: Vec<ConvertParseTreeError>) -> Option<Err
 }
 
 fn item_struct_to_struct_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_struct: ItemStruct,
@@ -297,7 +327,7 @@ fn item_struct_to_struct_declaration(
         .into_iter()
         .map(|type_field| {
             let attributes = item_attrs_to_map(handler, &type_field.attribute_list)?;
-            type_field_to_struct_field(handler, type_engine, type_field.value, attributes)
+            type_field_to_struct_field(context, handler, type_engine, type_field.value, attributes)
         })
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -327,6 +357,7 @@ fn item_struct_to_struct_declaration(
         attributes,
         fields,
         type_parameters: generic_params_opt_to_type_parameters(
+            context,
             handler,
             type_engine,
             item_struct.generics,
@@ -339,6 +370,7 @@ fn item_struct_to_struct_declaration(
 }
 
 fn item_enum_to_enum_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_enum: ItemEnum,
@@ -353,7 +385,14 @@ fn item_enum_to_enum_declaration(
         .enumerate()
         .map(|(tag, type_field)| {
             let attributes = item_attrs_to_map(handler, &type_field.attribute_list)?;
-            type_field_to_enum_variant(handler, type_engine, type_field.value, attributes, tag)
+            type_field_to_enum_variant(
+                context,
+                handler,
+                type_engine,
+                type_field.value,
+                attributes,
+                tag,
+            )
         })
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -381,6 +420,7 @@ fn item_enum_to_enum_declaration(
     let enum_declaration = EnumDeclaration {
         name: item_enum.name,
         type_parameters: generic_params_opt_to_type_parameters(
+            context,
             handler,
             type_engine,
             item_enum.generics,
@@ -395,6 +435,7 @@ fn item_enum_to_enum_declaration(
 }
 
 fn item_fn_to_function_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_fn: ItemFn,
@@ -410,18 +451,25 @@ fn item_fn_to_function_declaration(
         attributes,
         name: item_fn.fn_signature.name,
         visibility: pub_token_opt_to_visibility(item_fn.fn_signature.visibility),
-        body: braced_code_block_contents_to_code_block(handler, type_engine, item_fn.body)?,
+        body: braced_code_block_contents_to_code_block(
+            context,
+            handler,
+            type_engine,
+            item_fn.body,
+        )?,
         parameters: fn_args_to_function_parameters(
+            context,
             handler,
             type_engine,
             item_fn.fn_signature.arguments.into_inner(),
         )?,
         span,
         return_type: match item_fn.fn_signature.return_type_opt {
-            Some((_right_arrow, ty)) => ty_to_type_info(handler, type_engine, ty)?,
+            Some((_right_arrow, ty)) => ty_to_type_info(context, handler, type_engine, ty)?,
             None => TypeInfo::Tuple(Vec::new()),
         },
         type_parameters: generic_params_opt_to_type_parameters(
+            context,
             handler,
             type_engine,
             item_fn.fn_signature.generics,
@@ -446,13 +494,13 @@ fn get_attributed_purity(
     match attributes.get(&AttributeKind::Storage) {
         Some(attrs) if !attrs.is_empty() => {
             for arg in attrs.iter().flat_map(|attr| &attr.args) {
-                match arg.as_str() {
+                match arg.name.as_str() {
                     STORAGE_PURITY_READ_NAME => add_impurity(Purity::Reads, Purity::Writes),
                     STORAGE_PURITY_WRITE_NAME => add_impurity(Purity::Writes, Purity::Reads),
                     _otherwise => {
                         let error = ConvertParseTreeError::InvalidAttributeArgument {
                             attribute: "storage".to_owned(),
-                            span: arg.span(),
+                            span: arg.span.clone(),
                         };
                         return Err(handler.emit_err(error.into()));
                     }
@@ -465,6 +513,7 @@ fn get_attributed_purity(
 }
 
 fn item_trait_to_trait_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_trait: ItemTrait,
@@ -472,6 +521,7 @@ fn item_trait_to_trait_declaration(
 ) -> Result<TraitDeclaration, ErrorEmitted> {
     let span = item_trait.span();
     let type_parameters = generic_params_opt_to_type_parameters(
+        context,
         handler,
         type_engine,
         item_trait.generics,
@@ -484,7 +534,13 @@ fn item_trait_to_trait_declaration(
             .into_iter()
             .map(|(fn_signature, _)| {
                 let attributes = item_attrs_to_map(handler, &fn_signature.attribute_list)?;
-                fn_signature_to_trait_fn(handler, type_engine, fn_signature.value, attributes)
+                fn_signature_to_trait_fn(
+                    context,
+                    handler,
+                    type_engine,
+                    fn_signature.value,
+                    attributes,
+                )
             })
             .collect::<Result<_, _>>()?
     };
@@ -495,7 +551,13 @@ fn item_trait_to_trait_declaration(
             .into_iter()
             .map(|item_fn| {
                 let attributes = item_attrs_to_map(handler, &item_fn.attribute_list)?;
-                item_fn_to_function_declaration(handler, type_engine, item_fn.value, attributes)
+                item_fn_to_function_declaration(
+                    context,
+                    handler,
+                    type_engine,
+                    item_fn.value,
+                    attributes,
+                )
             })
             .collect::<Result<_, _>>()?,
     };
@@ -517,24 +579,26 @@ fn item_trait_to_trait_declaration(
 }
 
 fn item_impl_to_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_impl: ItemImpl,
 ) -> Result<Declaration, ErrorEmitted> {
     let block_span = item_impl.span();
     let type_implementing_for_span = item_impl.ty.span();
-    let type_implementing_for = ty_to_type_info(handler, type_engine, item_impl.ty)?;
+    let type_implementing_for = ty_to_type_info(context, handler, type_engine, item_impl.ty)?;
     let functions = item_impl
         .contents
         .into_inner()
         .into_iter()
         .map(|item| {
             let attributes = item_attrs_to_map(handler, &item.attribute_list)?;
-            item_fn_to_function_declaration(handler, type_engine, item.value, attributes)
+            item_fn_to_function_declaration(context, handler, type_engine, item.value, attributes)
         })
         .collect::<Result<_, _>>()?;
 
     let impl_type_parameters = generic_params_opt_to_type_parameters(
+        context,
         handler,
         type_engine,
         item_impl.generic_params_opt,
@@ -543,8 +607,12 @@ fn item_impl_to_declaration(
 
     match item_impl.trait_opt {
         Some((path_type, _)) => {
-            let (trait_name, trait_type_arguments) =
-                path_type_to_call_path_and_type_arguments(handler, type_engine, path_type)?;
+            let (trait_name, trait_type_arguments) = path_type_to_call_path_and_type_arguments(
+                context,
+                handler,
+                type_engine,
+                path_type,
+            )?;
             let impl_trait = ImplTrait {
                 impl_type_parameters,
                 trait_name,
@@ -574,6 +642,7 @@ fn item_impl_to_declaration(
 }
 
 fn path_type_to_call_path_and_type_arguments(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     PathType {
@@ -603,7 +672,7 @@ fn path_type_to_call_path_and_type_arguments(
 
     let ty_args = match suffix.generics_opt {
         Some((_, generic_args)) => {
-            generic_args_to_type_arguments(handler, type_engine, generic_args)?
+            generic_args_to_type_arguments(context, handler, type_engine, generic_args)?
         }
         None => vec![],
     };
@@ -612,6 +681,7 @@ fn path_type_to_call_path_and_type_arguments(
 }
 
 fn item_abi_to_abi_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_abi: ItemAbi,
@@ -627,7 +697,13 @@ fn item_abi_to_abi_declaration(
                 .into_iter()
                 .map(|(fn_signature, _semicolon_token)| {
                     let attributes = item_attrs_to_map(handler, &fn_signature.attribute_list)?;
-                    fn_signature_to_trait_fn(handler, type_engine, fn_signature.value, attributes)
+                    fn_signature_to_trait_fn(
+                        context,
+                        handler,
+                        type_engine,
+                        fn_signature.value,
+                        attributes,
+                    )
                 })
                 .collect::<Result<_, _>>()?
         },
@@ -638,7 +714,13 @@ fn item_abi_to_abi_declaration(
                 .into_iter()
                 .map(|item_fn| {
                     let attributes = item_attrs_to_map(handler, &item_fn.attribute_list)?;
-                    item_fn_to_function_declaration(handler, type_engine, item_fn.value, attributes)
+                    item_fn_to_function_declaration(
+                        context,
+                        handler,
+                        type_engine,
+                        item_fn.value,
+                        attributes,
+                    )
                 })
                 .collect::<Result<_, _>>()?,
         },
@@ -648,6 +730,7 @@ fn item_abi_to_abi_declaration(
 }
 
 pub(crate) fn item_const_to_constant_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_const: ItemConst,
@@ -656,7 +739,7 @@ pub(crate) fn item_const_to_constant_declaration(
     let span = item_const.span();
     let (type_ascription, type_ascription_span) = match item_const.ty_opt {
         Some((_colon_token, ty)) => {
-            let type_ascription = ty_to_type_info(handler, type_engine, ty.clone())?;
+            let type_ascription = ty_to_type_info(context, handler, type_engine, ty.clone())?;
             let type_ascription_span = if let Ty::Path(path_type) = &ty {
                 path_type.prefix.name.span()
             } else {
@@ -671,7 +754,7 @@ pub(crate) fn item_const_to_constant_declaration(
         name: item_const.name,
         type_ascription,
         type_ascription_span,
-        value: expr_to_expression(handler, type_engine, item_const.expr)?,
+        value: expr_to_expression(context, handler, type_engine, item_const.expr)?,
         visibility: pub_token_opt_to_visibility(item_const.visibility),
         attributes,
         span,
@@ -679,6 +762,7 @@ pub(crate) fn item_const_to_constant_declaration(
 }
 
 fn item_storage_to_storage_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     item_storage: ItemStorage,
@@ -692,7 +776,13 @@ fn item_storage_to_storage_declaration(
         .into_iter()
         .map(|storage_field| {
             let attributes = item_attrs_to_map(handler, &storage_field.attribute_list)?;
-            storage_field_to_storage_field(handler, type_engine, storage_field.value, attributes)
+            storage_field_to_storage_field(
+                context,
+                handler,
+                type_engine,
+                storage_field.value,
+                attributes,
+            )
         })
         .collect::<Result<_, _>>()?;
 
@@ -720,6 +810,7 @@ fn item_storage_to_storage_declaration(
 }
 
 fn type_field_to_struct_field(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     type_field: TypeField,
@@ -730,7 +821,7 @@ fn type_field_to_struct_field(
     let struct_field = StructField {
         name: type_field.name,
         attributes,
-        type_info: ty_to_type_info(handler, type_engine, type_field.ty)?,
+        type_info: ty_to_type_info(context, handler, type_engine, type_field.ty)?,
         span,
         type_span,
     };
@@ -738,6 +829,7 @@ fn type_field_to_struct_field(
 }
 
 fn generic_params_opt_to_type_parameters(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     generic_params_opt: Option<GenericParams>,
@@ -793,7 +885,7 @@ fn generic_params_opt_to_type_parameters(
         param_to_edit.trait_constraints_span = Span::join(ty_name.span(), bounds.span());
 
         param_to_edit.trait_constraints.extend(
-            traits_to_call_paths(handler, type_engine, bounds)?
+            traits_to_call_paths(context, handler, type_engine, bounds)?
                 .into_iter()
                 .map(|(trait_name, type_arguments)| TraitConstraint {
                     trait_name,
@@ -816,6 +908,7 @@ fn pub_token_opt_to_visibility(pub_token_opt: Option<PubToken>) -> Visibility {
 }
 
 fn type_field_to_enum_variant(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     type_field: TypeField,
@@ -832,7 +925,7 @@ fn type_field_to_enum_variant(
     let enum_variant = EnumVariant {
         name: type_field.name,
         attributes,
-        type_info: ty_to_type_info(handler, type_engine, type_field.ty)?,
+        type_info: ty_to_type_info(context, handler, type_engine, type_field.ty)?,
         type_span,
         tag,
         span,
@@ -841,6 +934,7 @@ fn type_field_to_enum_variant(
 }
 
 fn braced_code_block_contents_to_code_block(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     braced_code_block_contents: Braces<CodeBlockContents>,
@@ -850,11 +944,11 @@ fn braced_code_block_contents_to_code_block(
     let contents = {
         let mut contents = Vec::new();
         for statement in code_block_contents.statements {
-            let ast_nodes = statement_to_ast_nodes(handler, type_engine, statement)?;
+            let ast_nodes = statement_to_ast_nodes(context, handler, type_engine, statement)?;
             contents.extend(ast_nodes);
         }
         if let Some(expr) = code_block_contents.final_expr_opt {
-            let final_ast_node = expr_to_ast_node(handler, type_engine, *expr, false)?;
+            let final_ast_node = expr_to_ast_node(context, handler, type_engine, *expr, false)?;
             contents.push(final_ast_node);
         }
         contents
@@ -866,6 +960,7 @@ fn braced_code_block_contents_to_code_block(
 }
 
 fn fn_args_to_function_parameters(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     fn_args: FnArgs,
@@ -873,7 +968,7 @@ fn fn_args_to_function_parameters(
     let function_parameters = match fn_args {
         FnArgs::Static(args) => args
             .into_iter()
-            .map(|fn_arg| fn_arg_to_function_parameter(handler, type_engine, fn_arg))
+            .map(|fn_arg| fn_arg_to_function_parameter(context, handler, type_engine, fn_arg))
             .collect::<Result<_, _>>()?,
         FnArgs::NonStatic {
             self_token,
@@ -898,7 +993,7 @@ fn fn_args_to_function_parameters(
             if let Some((_comma_token, args)) = args_opt {
                 for arg in args {
                     let function_parameter =
-                        fn_arg_to_function_parameter(handler, type_engine, arg)?;
+                        fn_arg_to_function_parameter(context, handler, type_engine, arg)?;
                     function_parameters.push(function_parameter);
                 }
             }
@@ -939,14 +1034,16 @@ pub(crate) fn type_name_to_type_info_opt(name: &Ident) -> Option<TypeInfo> {
 }
 
 fn ty_to_type_info(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     ty: Ty,
 ) -> Result<TypeInfo, ErrorEmitted> {
     let type_info = match ty {
-        Ty::Path(path_type) => path_type_to_type_info(handler, type_engine, path_type)?,
+        Ty::Path(path_type) => path_type_to_type_info(context, handler, type_engine, path_type)?,
         Ty::Tuple(parenthesized_ty_tuple_descriptor) => {
             TypeInfo::Tuple(ty_tuple_descriptor_to_type_arguments(
+                context,
                 handler,
                 type_engine,
                 parenthesized_ty_tuple_descriptor.into_inner(),
@@ -955,7 +1052,7 @@ fn ty_to_type_info(
         Ty::Array(bracketed_ty_array_descriptor) => {
             let ty_array_descriptor = bracketed_ty_array_descriptor.into_inner();
             TypeInfo::Array(
-                ty_to_type_argument(handler, type_engine, *ty_array_descriptor.ty)?,
+                ty_to_type_argument(context, handler, type_engine, *ty_array_descriptor.ty)?,
                 expr_to_length(handler, *ty_array_descriptor.length)?,
             )
         }
@@ -966,12 +1063,14 @@ fn ty_to_type_info(
 }
 
 fn ty_to_type_argument(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     ty: Ty,
 ) -> Result<TypeArgument, ErrorEmitted> {
     let span = ty.span();
-    let initial_type_id = type_engine.insert_type(ty_to_type_info(handler, type_engine, ty)?);
+    let initial_type_id =
+        type_engine.insert_type(ty_to_type_info(context, handler, type_engine, ty)?);
     let type_argument = TypeArgument {
         type_id: initial_type_id,
         initial_type_id,
@@ -981,6 +1080,7 @@ fn ty_to_type_argument(
 }
 
 fn fn_signature_to_trait_fn(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     fn_signature: FnSignature,
@@ -995,12 +1095,13 @@ fn fn_signature_to_trait_fn(
         purity: get_attributed_purity(handler, &attributes)?,
         attributes,
         parameters: fn_args_to_function_parameters(
+            context,
             handler,
             type_engine,
             fn_signature.arguments.into_inner(),
         )?,
         return_type: match fn_signature.return_type_opt {
-            Some((_right_arrow_token, ty)) => ty_to_type_info(handler, type_engine, ty)?,
+            Some((_right_arrow_token, ty)) => ty_to_type_info(context, handler, type_engine, ty)?,
             None => TypeInfo::Tuple(Vec::new()),
         },
         return_type_span,
@@ -1009,17 +1110,20 @@ fn fn_signature_to_trait_fn(
 }
 
 fn traits_to_call_paths(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     traits: Traits,
 ) -> Result<Vec<(CallPath, Vec<TypeArgument>)>, ErrorEmitted> {
     let mut parsed_traits = vec![path_type_to_call_path_and_type_arguments(
+        context,
         handler,
         type_engine,
         traits.prefix,
     )?];
     for (_add_token, suffix) in traits.suffixes {
-        let supertrait = path_type_to_call_path_and_type_arguments(handler, type_engine, suffix)?;
+        let supertrait =
+            path_type_to_call_path_and_type_arguments(context, handler, type_engine, suffix)?;
         parsed_traits.push(supertrait);
     }
     Ok(parsed_traits)
@@ -1070,6 +1174,7 @@ fn path_type_to_call_path(
 }
 
 fn expr_to_ast_node(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     expr: Expr,
@@ -1077,7 +1182,7 @@ fn expr_to_ast_node(
 ) -> Result<AstNode, ErrorEmitted> {
     let span = expr.span();
     let ast_node = {
-        let expression = expr_to_expression(handler, type_engine, expr)?;
+        let expression = expr_to_expression(context, handler, type_engine, expr)?;
         if !is_statement {
             AstNode {
                 content: AstNodeContent::ImplicitReturnExpression(expression),
@@ -1094,29 +1199,32 @@ fn expr_to_ast_node(
 }
 
 fn abi_cast_args_to_abi_cast_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     args: Parens<AbiCastArgs>,
 ) -> Result<Box<AbiCastExpression>, ErrorEmitted> {
     let AbiCastArgs { name, address, .. } = args.into_inner();
     let abi_name = path_type_to_call_path(handler, name)?;
-    let address = Box::new(expr_to_expression(handler, type_engine, *address)?);
+    let address = Box::new(expr_to_expression(context, handler, type_engine, *address)?);
     Ok(Box::new(AbiCastExpression { abi_name, address }))
 }
 
 fn struct_path_and_fields_to_struct_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     path: PathExpr,
     fields: Braces<Punctuated<ExprStructField, CommaToken>>,
 ) -> Result<Box<StructExpression>, ErrorEmitted> {
-    let call_path_binding = path_expr_to_call_path_binding(handler, type_engine, path)?;
+    let call_path_binding = path_expr_to_call_path_binding(context, handler, type_engine, path)?;
     let fields = {
         fields
             .into_inner()
             .into_iter()
             .map(|expr_struct_field| {
                 expr_struct_field_to_struct_expression_field(
+                    context,
                     handler,
                     type_engine,
                     expr_struct_field,
@@ -1131,6 +1239,7 @@ fn struct_path_and_fields_to_struct_expression(
 }
 
 fn method_call_fields_to_method_application_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     target: Box<Expr>,
@@ -1139,7 +1248,7 @@ fn method_call_fields_to_method_application_expression(
     args: Parens<Punctuated<Expr, CommaToken>>,
 ) -> Result<Box<MethodApplicationExpression>, ErrorEmitted> {
     let (method_name, type_arguments) =
-        path_expr_segment_to_ident_or_type_argument(handler, type_engine, path_seg)?;
+        path_expr_segment_to_ident_or_type_argument(context, handler, type_engine, path_seg)?;
 
     let span = match &*type_arguments {
         [] => method_name.span(),
@@ -1158,6 +1267,7 @@ fn method_call_fields_to_method_application_expression(
             .into_iter()
             .map(|expr_struct_field| {
                 expr_struct_field_to_struct_expression_field(
+                    context,
                     handler,
                     type_engine,
                     expr_struct_field,
@@ -1167,7 +1277,7 @@ fn method_call_fields_to_method_application_expression(
     };
     let arguments = iter::once(*target)
         .chain(args.into_inner().into_iter())
-        .map(|expr| expr_to_expression(handler, type_engine, expr))
+        .map(|expr| expr_to_expression(context, handler, type_engine, expr))
         .collect::<Result<_, _>>()?;
     Ok(Box::new(MethodApplicationExpression {
         method_name_binding,
@@ -1177,6 +1287,7 @@ fn method_call_fields_to_method_application_expression(
 }
 
 fn expr_func_app_to_expression_kind(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     func: Box<Expr>,
@@ -1199,11 +1310,12 @@ fn expr_func_app_to_expression_kind(
 
     let is_absolute = path_root_opt_to_bool(handler, root_opt)?;
 
-    let convert_ty_args = |generics_opt: Option<(_, GenericArgs)>| {
+    let convert_ty_args = |context: &mut Context, generics_opt: Option<(_, GenericArgs)>| {
         Ok(match generics_opt {
             Some((_, generic_args)) => {
                 let span = generic_args.span();
-                let ty_args = generic_args_to_type_arguments(handler, type_engine, generic_args)?;
+                let ty_args =
+                    generic_args_to_type_arguments(context, handler, type_engine, generic_args)?;
                 (ty_args, Some(span))
             }
             None => <_>::default(),
@@ -1227,7 +1339,7 @@ fn expr_func_app_to_expression_kind(
     let arguments = args
         .into_inner()
         .into_iter()
-        .map(|expr| expr_to_expression(handler, type_engine, expr))
+        .map(|expr| expr_to_expression(context, handler, type_engine, expr))
         .collect::<Result<_, _>>()?;
 
     let name_args_span = |start, end: Option<_>| match end {
@@ -1235,7 +1347,7 @@ fn expr_func_app_to_expression_kind(
         None => start,
     };
 
-    let (type_arguments, type_arguments_span) = convert_ty_args(call_seg.generics_opt)?;
+    let (type_arguments, type_arguments_span) = convert_ty_args(context, call_seg.generics_opt)?;
 
     // Route intrinsic calls to different AST node.
     match Intrinsic::try_from_str(call_seg.name.as_str()) {
@@ -1283,7 +1395,7 @@ fn expr_func_app_to_expression_kind(
 
     // Ambiguous call. Could be a method call or a normal function call.
     // We don't know until type checking what `last` refers to, so let's defer.
-    let (last_ty_args, last_ty_args_span) = convert_ty_args(last.generics_opt)?;
+    let (last_ty_args, last_ty_args_span) = convert_ty_args(context, last.generics_opt)?;
     let before = TypeBinding {
         span: name_args_span(last.name.span(), last_ty_args_span),
         inner: last.name,
@@ -1312,6 +1424,7 @@ fn expr_func_app_to_expression_kind(
 }
 
 fn expr_to_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     expr: Expr,
@@ -1329,15 +1442,20 @@ fn expr_to_expression(
         },
         Expr::AbiCast { args, .. } => {
             let abi_cast_expression =
-                abi_cast_args_to_abi_cast_expression(handler, type_engine, args)?;
+                abi_cast_args_to_abi_cast_expression(context, handler, type_engine, args)?;
             Expression {
                 kind: ExpressionKind::AbiCast(abi_cast_expression),
                 span,
             }
         }
         Expr::Struct { path, fields } => {
-            let struct_expression =
-                struct_path_and_fields_to_struct_expression(handler, type_engine, path, fields)?;
+            let struct_expression = struct_path_and_fields_to_struct_expression(
+                context,
+                handler,
+                type_engine,
+                path,
+                fields,
+            )?;
             Expression {
                 kind: ExpressionKind::Struct(struct_expression),
                 span,
@@ -1345,6 +1463,7 @@ fn expr_to_expression(
         }
         Expr::Tuple(parenthesized_expr_tuple_descriptor) => {
             let fields = expr_tuple_descriptor_to_expressions(
+                context,
                 handler,
                 type_engine,
                 parenthesized_expr_tuple_descriptor.into_inner(),
@@ -1354,8 +1473,11 @@ fn expr_to_expression(
                 span,
             }
         }
-        Expr::Parens(parens) => expr_to_expression(handler, type_engine, *parens.into_inner())?,
+        Expr::Parens(parens) => {
+            expr_to_expression(context, handler, type_engine, *parens.into_inner())?
+        }
         Expr::Block(braced_code_block_contents) => braced_code_block_contents_to_expression(
+            context,
             handler,
             type_engine,
             braced_code_block_contents,
@@ -1365,7 +1487,7 @@ fn expr_to_expression(
                 ExprArrayDescriptor::Sequence(exprs) => {
                     let contents = exprs
                         .into_iter()
-                        .map(|expr| expr_to_expression(handler, type_engine, expr))
+                        .map(|expr| expr_to_expression(context, handler, type_engine, expr))
                         .collect::<Result<_, _>>()?;
                     Expression {
                         kind: ExpressionKind::Array(contents),
@@ -1373,7 +1495,7 @@ fn expr_to_expression(
                     }
                 }
                 ExprArrayDescriptor::Repeat { value, length, .. } => {
-                    let expression = expr_to_expression(handler, type_engine, *value)?;
+                    let expression = expr_to_expression(context, handler, type_engine, *value)?;
                     let length = expr_to_usize(handler, *length)?;
                     let contents = iter::repeat_with(|| expression.clone())
                         .take(length)
@@ -1386,7 +1508,8 @@ fn expr_to_expression(
             }
         }
         Expr::Asm(asm_block) => {
-            let asm_expression = asm_block_to_asm_expression(handler, type_engine, asm_block)?;
+            let asm_expression =
+                asm_block_to_asm_expression(context, handler, type_engine, asm_block)?;
             Expression {
                 kind: ExpressionKind::Asm(asm_expression),
                 span,
@@ -1394,7 +1517,7 @@ fn expr_to_expression(
         }
         Expr::Return { expr_opt, .. } => {
             let expression = match expr_opt {
-                Some(expr) => expr_to_expression(handler, type_engine, *expr)?,
+                Some(expr) => expr_to_expression(context, handler, type_engine, *expr)?,
                 None => Expression {
                     kind: ExpressionKind::Tuple(Vec::new()),
                     span: span.clone(),
@@ -1405,22 +1528,19 @@ fn expr_to_expression(
                 span,
             }
         }
-        Expr::If(if_expr) => if_expr_to_expression(handler, type_engine, if_expr)?,
+        Expr::If(if_expr) => if_expr_to_expression(context, handler, type_engine, if_expr)?,
         Expr::Match {
             value, branches, ..
         } => {
-            let value = expr_to_expression(handler, type_engine, *value)?;
+            let value = expr_to_expression(context, handler, type_engine, *value)?;
             let var_decl_span = value.span();
 
             // Generate a deterministic name for the variable returned by the match expression.
-            // Because the parser is single threaded, the name generated below will be stable.
-            static COUNTER: AtomicUsize = AtomicUsize::new(0);
             let match_return_var_name = format!(
                 "{}{}",
                 MATCH_RETURN_VAR_NAME_PREFIX,
-                COUNTER.load(Ordering::SeqCst)
+                context.next_match_return_var_suffix()
             );
-            COUNTER.fetch_add(1, Ordering::SeqCst);
             let var_decl_name = Ident::new_with_override(
                 Box::leak(match_return_var_name.into_boxed_str()),
                 var_decl_span.clone(),
@@ -1435,7 +1555,7 @@ fn expr_to_expression(
                     .into_inner()
                     .into_iter()
                     .map(|match_branch| {
-                        match_branch_to_match_branch(handler, type_engine, match_branch)
+                        match_branch_to_match_branch(context, handler, type_engine, match_branch)
                     })
                     .collect::<Result<_, _>>()?
             };
@@ -1474,19 +1594,34 @@ fn expr_to_expression(
             condition, block, ..
         } => Expression {
             kind: ExpressionKind::WhileLoop(WhileLoopExpression {
-                condition: Box::new(expr_to_expression(handler, type_engine, *condition)?),
-                body: braced_code_block_contents_to_code_block(handler, type_engine, block)?,
+                condition: Box::new(expr_to_expression(
+                    context,
+                    handler,
+                    type_engine,
+                    *condition,
+                )?),
+                body: braced_code_block_contents_to_code_block(
+                    context,
+                    handler,
+                    type_engine,
+                    block,
+                )?,
             }),
             span,
         },
         Expr::FuncApp { func, args } => {
-            let kind = expr_func_app_to_expression_kind(handler, type_engine, func, args)?;
+            let kind = expr_func_app_to_expression_kind(context, handler, type_engine, func, args)?;
             Expression { kind, span }
         }
         Expr::Index { target, arg } => Expression {
             kind: ExpressionKind::ArrayIndex(ArrayIndexExpression {
-                prefix: Box::new(expr_to_expression(handler, type_engine, *target)?),
-                index: Box::new(expr_to_expression(handler, type_engine, *arg.into_inner())?),
+                prefix: Box::new(expr_to_expression(context, handler, type_engine, *target)?),
+                index: Box::new(expr_to_expression(
+                    context,
+                    handler,
+                    type_engine,
+                    *arg.into_inner(),
+                )?),
             }),
             span,
         },
@@ -1499,6 +1634,7 @@ fn expr_to_expression(
         } => {
             let method_application_expression =
                 method_call_fields_to_method_application_expression(
+                    context,
                     handler,
                     type_engine,
                     target,
@@ -1542,7 +1678,7 @@ fn expr_to_expression(
                     field_names: field_names.into_iter().rev().cloned().collect(),
                 }),
                 None => ExpressionKind::Subfield(SubfieldExpression {
-                    prefix: Box::new(expr_to_expression(handler, type_engine, *target)?),
+                    prefix: Box::new(expr_to_expression(context, handler, type_engine, *target)?),
                     field_to_access: name,
                 }),
             };
@@ -1555,7 +1691,7 @@ fn expr_to_expression(
             ..
         } => Expression {
             kind: ExpressionKind::TupleIndex(TupleIndexExpression {
-                prefix: Box::new(expr_to_expression(handler, type_engine, *target)?),
+                prefix: Box::new(expr_to_expression(context, handler, type_engine, *target)?),
                 index: match usize::try_from(field) {
                     Ok(index) => index,
                     Err(..) => {
@@ -1571,7 +1707,7 @@ fn expr_to_expression(
         Expr::Ref { .. } => unimplemented!(),
         Expr::Deref { .. } => unimplemented!(),
         Expr::Not { bang_token, expr } => {
-            let expr = expr_to_expression(handler, type_engine, *expr)?;
+            let expr = expr_to_expression(context, handler, type_engine, *expr)?;
             op_call("not", bang_token.span(), span, &[expr])?
         }
         Expr::Pow {
@@ -1579,8 +1715,8 @@ fn expr_to_expression(
             double_star_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("pow", double_star_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Mul {
@@ -1588,8 +1724,8 @@ fn expr_to_expression(
             star_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("multiply", star_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Div {
@@ -1597,8 +1733,8 @@ fn expr_to_expression(
             forward_slash_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("divide", forward_slash_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Modulo {
@@ -1606,8 +1742,8 @@ fn expr_to_expression(
             percent_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("modulo", percent_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Add {
@@ -1615,8 +1751,8 @@ fn expr_to_expression(
             add_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("add", add_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Sub {
@@ -1624,8 +1760,8 @@ fn expr_to_expression(
             sub_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("subtract", sub_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Shl {
@@ -1633,8 +1769,8 @@ fn expr_to_expression(
             shl_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("lsh", shl_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Shr {
@@ -1642,8 +1778,8 @@ fn expr_to_expression(
             shr_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("rsh", shr_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::BitAnd {
@@ -1651,8 +1787,8 @@ fn expr_to_expression(
             ampersand_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("binary_and", ampersand_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::BitXor {
@@ -1660,8 +1796,8 @@ fn expr_to_expression(
             caret_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("binary_xor", caret_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::BitOr {
@@ -1669,8 +1805,8 @@ fn expr_to_expression(
             pipe_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("binary_or", pipe_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::Equal {
@@ -1678,8 +1814,8 @@ fn expr_to_expression(
             double_eq_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("eq", double_eq_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::NotEqual {
@@ -1687,8 +1823,8 @@ fn expr_to_expression(
             bang_eq_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("neq", bang_eq_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::LessThan {
@@ -1696,8 +1832,8 @@ fn expr_to_expression(
             less_than_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("lt", less_than_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::GreaterThan {
@@ -1705,8 +1841,8 @@ fn expr_to_expression(
             greater_than_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("gt", greater_than_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::LessThanEq {
@@ -1714,8 +1850,8 @@ fn expr_to_expression(
             less_than_eq_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("le", less_than_eq_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::GreaterThanEq {
@@ -1723,23 +1859,23 @@ fn expr_to_expression(
             greater_than_eq_token,
             rhs,
         } => {
-            let lhs = expr_to_expression(handler, type_engine, *lhs)?;
-            let rhs = expr_to_expression(handler, type_engine, *rhs)?;
+            let lhs = expr_to_expression(context, handler, type_engine, *lhs)?;
+            let rhs = expr_to_expression(context, handler, type_engine, *rhs)?;
             op_call("ge", greater_than_eq_token.span(), span, &vec![lhs, rhs])?
         }
         Expr::LogicalAnd { lhs, rhs, .. } => Expression {
             kind: ExpressionKind::LazyOperator(LazyOperatorExpression {
                 op: LazyOp::And,
-                lhs: Box::new(expr_to_expression(handler, type_engine, *lhs)?),
-                rhs: Box::new(expr_to_expression(handler, type_engine, *rhs)?),
+                lhs: Box::new(expr_to_expression(context, handler, type_engine, *lhs)?),
+                rhs: Box::new(expr_to_expression(context, handler, type_engine, *rhs)?),
             }),
             span,
         },
         Expr::LogicalOr { lhs, rhs, .. } => Expression {
             kind: ExpressionKind::LazyOperator(LazyOperatorExpression {
                 op: LazyOp::Or,
-                lhs: Box::new(expr_to_expression(handler, type_engine, *lhs)?),
-                rhs: Box::new(expr_to_expression(handler, type_engine, *rhs)?),
+                lhs: Box::new(expr_to_expression(context, handler, type_engine, *lhs)?),
+                rhs: Box::new(expr_to_expression(context, handler, type_engine, *rhs)?),
             }),
             span,
         },
@@ -1754,21 +1890,30 @@ fn expr_to_expression(
         } => match op_variant {
             ReassignmentOpVariant::Equals => Expression {
                 kind: ExpressionKind::Reassignment(ReassignmentExpression {
-                    lhs: assignable_to_reassignment_target(handler, type_engine, assignable)?,
-                    rhs: Box::new(expr_to_expression(handler, type_engine, *expr)?),
+                    lhs: assignable_to_reassignment_target(
+                        context,
+                        handler,
+                        type_engine,
+                        assignable,
+                    )?,
+                    rhs: Box::new(expr_to_expression(context, handler, type_engine, *expr)?),
                 }),
                 span,
             },
             op_variant => {
-                let lhs =
-                    assignable_to_reassignment_target(handler, type_engine, assignable.clone())?;
+                let lhs = assignable_to_reassignment_target(
+                    context,
+                    handler,
+                    type_engine,
+                    assignable.clone(),
+                )?;
                 let rhs = Box::new(op_call(
                     op_variant.core_name(),
                     op_span,
                     span.clone(),
                     &vec![
-                        assignable_to_expression(handler, type_engine, assignable)?,
-                        expr_to_expression(handler, type_engine, *expr)?,
+                        assignable_to_expression(context, handler, type_engine, assignable)?,
+                        expr_to_expression(context, handler, type_engine, *expr)?,
                     ],
                 )?);
                 Expression {
@@ -1820,6 +1965,7 @@ fn op_call(
 }
 
 fn storage_field_to_storage_field(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     storage_field: sway_ast::StorageField,
@@ -1833,24 +1979,25 @@ fn storage_field_to_storage_field(
     let storage_field = StorageField {
         attributes,
         name: storage_field.name,
-        type_info: ty_to_type_info(handler, type_engine, storage_field.ty)?,
+        type_info: ty_to_type_info(context, handler, type_engine, storage_field.ty)?,
         type_info_span,
-        initializer: expr_to_expression(handler, type_engine, storage_field.initializer)?,
+        initializer: expr_to_expression(context, handler, type_engine, storage_field.initializer)?,
     };
     Ok(storage_field)
 }
 
 fn statement_to_ast_nodes(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     statement: Statement,
 ) -> Result<Vec<AstNode>, ErrorEmitted> {
     let ast_nodes = match statement {
         Statement::Let(statement_let) => {
-            statement_let_to_ast_nodes(handler, type_engine, statement_let)?
+            statement_let_to_ast_nodes(context, handler, type_engine, statement_let)?
         }
         Statement::Item(item) => {
-            let nodes = item_to_ast_nodes(handler, type_engine, item, false, None)?;
+            let nodes = item_to_ast_nodes(context, handler, type_engine, item, false, None)?;
             nodes.iter().fold(Ok(()), |res, node| {
                 if ast_node_is_test_fn(node) {
                     let span = node.span.clone();
@@ -1862,12 +2009,15 @@ fn statement_to_ast_nodes(
             })?;
             nodes
         }
-        Statement::Expr { expr, .. } => vec![expr_to_ast_node(handler, type_engine, expr, true)?],
+        Statement::Expr { expr, .. } => {
+            vec![expr_to_ast_node(context, handler, type_engine, expr, true)?]
+        }
     };
     Ok(ast_nodes)
 }
 
 fn fn_arg_to_function_parameter(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     fn_arg: FnArg,
@@ -1917,7 +2067,7 @@ fn fn_arg_to_function_parameter(
         is_reference: reference.is_some(),
         is_mutable: mutable.is_some(),
         mutability_span,
-        type_info: ty_to_type_info(handler, type_engine, fn_arg.ty)?,
+        type_info: ty_to_type_info(context, handler, type_engine, fn_arg.ty)?,
         type_span,
     };
     Ok(function_parameter)
@@ -2016,12 +2166,13 @@ fn path_type_segment_to_ident(
 /// Similar to [path_type_segment_to_ident],
 /// but allows for the item to be either type arguments _or_ an ident.
 fn path_expr_segment_to_ident_or_type_argument(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     PathExprSegment { name, generics_opt }: PathExprSegment,
 ) -> Result<(Ident, Vec<TypeArgument>), ErrorEmitted> {
     let type_args = match generics_opt {
-        Some((_, x)) => generic_args_to_type_arguments(handler, type_engine, x)?,
+        Some((_, x)) => generic_args_to_type_arguments(context, handler, type_engine, x)?,
         None => Default::default(),
     };
     Ok((name, type_args))
@@ -2070,13 +2221,18 @@ fn path_expr_to_expression(
 }
 
 fn braced_code_block_contents_to_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     braced_code_block_contents: Braces<CodeBlockContents>,
 ) -> Result<Expression, ErrorEmitted> {
     let span = braced_code_block_contents.span();
-    let code_block =
-        braced_code_block_contents_to_code_block(handler, type_engine, braced_code_block_contents)?;
+    let code_block = braced_code_block_contents_to_code_block(
+        context,
+        handler,
+        type_engine,
+        braced_code_block_contents,
+    )?;
     Ok(Expression {
         kind: ExpressionKind::CodeBlock(code_block),
         span,
@@ -2084,6 +2240,7 @@ fn braced_code_block_contents_to_expression(
 }
 
 fn if_expr_to_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     if_expr: IfExpr,
@@ -2098,6 +2255,7 @@ fn if_expr_to_expression(
     let then_block_span = then_block.span();
     let then_block = Expression {
         kind: ExpressionKind::CodeBlock(braced_code_block_contents_to_code_block(
+            context,
             handler,
             type_engine,
             then_block,
@@ -2110,13 +2268,14 @@ fn if_expr_to_expression(
             let expression = match tail {
                 ControlFlow::Break(braced_code_block_contents) => {
                     braced_code_block_contents_to_expression(
+                        context,
                         handler,
                         type_engine,
                         braced_code_block_contents,
                     )?
                 }
                 ControlFlow::Continue(if_expr) => {
-                    if_expr_to_expression(handler, type_engine, *if_expr)?
+                    if_expr_to_expression(context, handler, type_engine, *if_expr)?
                 }
             };
             Some(expression)
@@ -2125,7 +2284,12 @@ fn if_expr_to_expression(
     let expression = match condition {
         IfCondition::Expr(condition) => Expression {
             kind: ExpressionKind::If(IfExpression {
-                condition: Box::new(expr_to_expression(handler, type_engine, *condition)?),
+                condition: Box::new(expr_to_expression(
+                    context,
+                    handler,
+                    type_engine,
+                    *condition,
+                )?),
                 then: Box::new(then_block),
                 r#else: else_block.map(Box::new),
             }),
@@ -2171,7 +2335,7 @@ fn if_expr_to_expression(
             });
             Expression {
                 kind: ExpressionKind::Match(MatchExpression {
-                    value: Box::new(expr_to_expression(handler, type_engine, *rhs)?),
+                    value: Box::new(expr_to_expression(context, handler, type_engine, *rhs)?),
                     branches,
                 }),
                 span,
@@ -2328,6 +2492,7 @@ fn literal_to_literal(
 /// Use this when converting a call path that could potentially include type arguments, i.e. the
 /// turbofish.
 fn path_expr_to_call_path_binding(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     path_expr: PathExpr,
@@ -2349,6 +2514,7 @@ fn path_expr_to_call_path_binding(
             }
             let span = call_path_suffix.span();
             let (suffix, ty_args) = path_expr_segment_to_ident_or_type_argument(
+                context,
                 handler,
                 type_engine,
                 call_path_suffix,
@@ -2358,7 +2524,7 @@ fn path_expr_to_call_path_binding(
         None => {
             let span = prefix.span();
             let (suffix, ty_args) =
-                path_expr_segment_to_ident_or_type_argument(handler, type_engine, prefix)?;
+                path_expr_segment_to_ident_or_type_argument(context, handler, type_engine, prefix)?;
             (vec![], suffix, span, ty_args)
         }
     };
@@ -2406,13 +2572,14 @@ fn path_expr_to_call_path(
 }
 
 fn expr_struct_field_to_struct_expression_field(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     expr_struct_field: ExprStructField,
 ) -> Result<StructExpressionField, ErrorEmitted> {
     let span = expr_struct_field.span();
     let value = match expr_struct_field.expr_opt {
-        Some((_colon_token, expr)) => expr_to_expression(handler, type_engine, *expr)?,
+        Some((_colon_token, expr)) => expr_to_expression(context, handler, type_engine, *expr)?,
         None => Expression {
             kind: ExpressionKind::Variable(expr_struct_field.field_name.clone()),
             span: span.clone(),
@@ -2426,6 +2593,7 @@ fn expr_struct_field_to_struct_expression_field(
 }
 
 fn expr_tuple_descriptor_to_expressions(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     expr_tuple_descriptor: ExprTupleDescriptor,
@@ -2433,9 +2601,9 @@ fn expr_tuple_descriptor_to_expressions(
     let expressions = match expr_tuple_descriptor {
         ExprTupleDescriptor::Nil => Vec::new(),
         ExprTupleDescriptor::Cons { head, tail, .. } => {
-            let mut expressions = vec![expr_to_expression(handler, type_engine, *head)?];
+            let mut expressions = vec![expr_to_expression(context, handler, type_engine, *head)?];
             for expr in tail {
-                expressions.push(expr_to_expression(handler, type_engine, expr)?);
+                expressions.push(expr_to_expression(context, handler, type_engine, expr)?);
             }
             expressions
         }
@@ -2444,6 +2612,7 @@ fn expr_tuple_descriptor_to_expressions(
 }
 
 fn asm_block_to_asm_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     asm_block: AsmBlock,
@@ -2457,7 +2626,7 @@ fn asm_block_to_asm_expression(
             };
             let returns = Some((asm_register, asm_final_expr.register.span()));
             let return_type = match asm_final_expr.ty_opt {
-                Some((_colon_token, ty)) => ty_to_type_info(handler, type_engine, ty)?,
+                Some((_colon_token, ty)) => ty_to_type_info(context, handler, type_engine, ty)?,
                 None => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
             };
             (returns, return_type)
@@ -2471,6 +2640,7 @@ fn asm_block_to_asm_expression(
             .into_iter()
             .map(|asm_register_declaration| {
                 asm_register_declaration_to_asm_register_declaration(
+                    context,
                     handler,
                     type_engine,
                     asm_register_declaration,
@@ -2495,6 +2665,7 @@ fn asm_block_to_asm_expression(
 }
 
 fn match_branch_to_match_branch(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     match_branch: sway_ast::MatchBranch,
@@ -2507,6 +2678,7 @@ fn match_branch_to_match_branch(
                 let span = block.span();
                 Expression {
                     kind: ExpressionKind::CodeBlock(braced_code_block_contents_to_code_block(
+                        context,
                         handler,
                         type_engine,
                         block,
@@ -2514,18 +2686,22 @@ fn match_branch_to_match_branch(
                     span,
                 }
             }
-            MatchBranchKind::Expr { expr, .. } => expr_to_expression(handler, type_engine, expr)?,
+            MatchBranchKind::Expr { expr, .. } => {
+                expr_to_expression(context, handler, type_engine, expr)?
+            }
         },
         span,
     })
 }
 
 fn statement_let_to_ast_nodes(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     statement_let: StatementLet,
 ) -> Result<Vec<AstNode>, ErrorEmitted> {
     fn unfold(
+        context: &mut Context,
         handler: &Handler,
         type_engine: &TypeEngine,
         pattern: Pattern,
@@ -2551,7 +2727,7 @@ fn statement_let_to_ast_nodes(
                 let (type_ascription, type_ascription_span) = match ty_opt {
                     Some(ty) => {
                         let type_ascription_span = ty.span();
-                        let type_ascription = ty_to_type_info(handler, type_engine, ty)?;
+                        let type_ascription = ty_to_type_info(context, handler, type_engine, ty)?;
                         (type_ascription, Some(type_ascription_span))
                     }
                     None => (TypeInfo::Unknown, None),
@@ -2586,11 +2762,11 @@ fn statement_let_to_ast_nodes(
                 let mut ast_nodes = Vec::new();
 
                 // Generate a deterministic name for the destructured struct
-                // Because the parser is single threaded, the name generated below will be stable.
-                static COUNTER: AtomicUsize = AtomicUsize::new(0);
-                let destructured_name =
-                    format!("{}{}", DESTRUCTURE_PREFIX, COUNTER.load(Ordering::SeqCst));
-                COUNTER.fetch_add(1, Ordering::SeqCst);
+                let destructured_name = format!(
+                    "{}{}",
+                    DESTRUCTURE_PREFIX,
+                    context.next_destructured_struct_suffix()
+                );
                 let destructure_name = Ident::new_with_override(
                     Box::leak(destructured_name.into_boxed_str()),
                     path.prefix.name.span(),
@@ -2602,7 +2778,8 @@ fn statement_let_to_ast_nodes(
                 let (type_ascription, type_ascription_span) = match &ty_opt {
                     Some(ty) => {
                         let type_ascription_span = ty.span();
-                        let type_ascription = ty_to_type_info(handler, type_engine, ty.clone())?;
+                        let type_ascription =
+                            ty_to_type_info(context, handler, type_engine, ty.clone())?;
                         (type_ascription, Some(type_ascription_span))
                     }
                     None => (TypeInfo::Unknown, None),
@@ -2655,6 +2832,7 @@ fn statement_let_to_ast_nodes(
                     // recursively create variable declarations for the subpatterns on the LHS
                     // and add them to the ast nodes
                     ast_nodes.extend(unfold(
+                        context,
                         handler,
                         type_engine,
                         recursive_pattern,
@@ -2675,10 +2853,11 @@ fn statement_let_to_ast_nodes(
                 let mut ast_nodes = Vec::new();
 
                 // Generate a deterministic name for the tuple.
-                // Because the parser is single threaded, the name generated below will be stable.
-                static COUNTER: AtomicUsize = AtomicUsize::new(0);
-                let tuple_name = format!("{}{}", TUPLE_NAME_PREFIX, COUNTER.load(Ordering::SeqCst));
-                COUNTER.fetch_add(1, Ordering::SeqCst);
+                let tuple_name = format!(
+                    "{}{}",
+                    TUPLE_NAME_PREFIX,
+                    context.next_destructured_tuple_suffix()
+                );
                 let tuple_name =
                     Ident::new_with_override(Box::leak(tuple_name.into_boxed_str()), span.clone());
 
@@ -2688,7 +2867,8 @@ fn statement_let_to_ast_nodes(
                 let (type_ascription, type_ascription_span) = match &ty_opt {
                     Some(ty) => {
                         let type_ascription_span = ty.span();
-                        let type_ascription = ty_to_type_info(handler, type_engine, ty.clone())?;
+                        let type_ascription =
+                            ty_to_type_info(context, handler, type_engine, ty.clone())?;
                         (type_ascription, Some(type_ascription_span))
                     }
                     None => (TypeInfo::Unknown, None),
@@ -2734,6 +2914,7 @@ fn statement_let_to_ast_nodes(
                     // recursively create variable declarations for the subpatterns on the LHS
                     // and add them to the ast nodes
                     ast_nodes.extend(unfold(
+                        context,
                         handler,
                         type_engine,
                         pattern,
@@ -2755,8 +2936,9 @@ fn statement_let_to_ast_nodes(
         Ok(ast_nodes)
     }
     let span = statement_let.span();
-    let initial_expression = expr_to_expression(handler, type_engine, statement_let.expr)?;
+    let initial_expression = expr_to_expression(context, handler, type_engine, statement_let.expr)?;
     unfold(
+        context,
         handler,
         type_engine,
         statement_let.pattern,
@@ -2789,6 +2971,7 @@ fn generic_args_to_type_parameters(
 }
 
 fn asm_register_declaration_to_asm_register_declaration(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     asm_register_declaration: sway_ast::AsmRegisterDeclaration,
@@ -2797,7 +2980,7 @@ fn asm_register_declaration_to_asm_register_declaration(
         name: asm_register_declaration.register,
         initializer: asm_register_declaration
             .value_opt
-            .map(|(_colon_token, expr)| expr_to_expression(handler, type_engine, *expr))
+            .map(|(_colon_token, expr)| expr_to_expression(context, handler, type_engine, *expr))
             .transpose()?,
     })
 }
@@ -2994,6 +3177,7 @@ fn pattern_struct_field_to_struct_scrutinee_field(
 }
 
 fn assignable_to_expression(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     assignable: Assignable,
@@ -3006,8 +3190,18 @@ fn assignable_to_expression(
         },
         Assignable::Index { target, arg } => Expression {
             kind: ExpressionKind::ArrayIndex(ArrayIndexExpression {
-                prefix: Box::new(assignable_to_expression(handler, type_engine, *target)?),
-                index: Box::new(expr_to_expression(handler, type_engine, *arg.into_inner())?),
+                prefix: Box::new(assignable_to_expression(
+                    context,
+                    handler,
+                    type_engine,
+                    *target,
+                )?),
+                index: Box::new(expr_to_expression(
+                    context,
+                    handler,
+                    type_engine,
+                    *arg.into_inner(),
+                )?),
             }),
             span,
         },
@@ -3041,7 +3235,12 @@ fn assignable_to_expression(
                 }
                 None => Expression {
                     kind: ExpressionKind::Subfield(SubfieldExpression {
-                        prefix: Box::new(assignable_to_expression(handler, type_engine, *target)?),
+                        prefix: Box::new(assignable_to_expression(
+                            context,
+                            handler,
+                            type_engine,
+                            *target,
+                        )?),
                         field_to_access: name,
                     }),
                     span,
@@ -3063,7 +3262,12 @@ fn assignable_to_expression(
             };
             Expression {
                 kind: ExpressionKind::TupleIndex(TupleIndexExpression {
-                    prefix: Box::new(assignable_to_expression(handler, type_engine, *target)?),
+                    prefix: Box::new(assignable_to_expression(
+                        context,
+                        handler,
+                        type_engine,
+                        *target,
+                    )?),
                     index,
                     index_span: field_span,
                 }),
@@ -3075,6 +3279,7 @@ fn assignable_to_expression(
 }
 
 fn assignable_to_reassignment_target(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     assignable: Assignable,
@@ -3098,11 +3303,12 @@ fn assignable_to_reassignment_target(
             Assignable::TupleFieldProjection { .. } => break,
         }
     }
-    let expression = assignable_to_expression(handler, type_engine, assignable)?;
+    let expression = assignable_to_expression(context, handler, type_engine, assignable)?;
     Ok(ReassignmentTarget::VariableExpression(Box::new(expression)))
 }
 
 fn generic_args_to_type_arguments(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     generic_args: GenericArgs,
@@ -3113,7 +3319,8 @@ fn generic_args_to_type_arguments(
         .into_iter()
         .map(|ty| {
             let span = ty.span();
-            let type_id = type_engine.insert_type(ty_to_type_info(handler, type_engine, ty)?);
+            let type_id =
+                type_engine.insert_type(ty_to_type_info(context, handler, type_engine, ty)?);
             Ok(TypeArgument {
                 type_id,
                 initial_type_id: type_id,
@@ -3124,6 +3331,7 @@ fn generic_args_to_type_arguments(
 }
 
 fn ty_tuple_descriptor_to_type_arguments(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     ty_tuple_descriptor: TyTupleDescriptor,
@@ -3131,9 +3339,10 @@ fn ty_tuple_descriptor_to_type_arguments(
     let type_arguments = match ty_tuple_descriptor {
         TyTupleDescriptor::Nil => vec![],
         TyTupleDescriptor::Cons { head, tail, .. } => {
-            let mut type_arguments = vec![ty_to_type_argument(handler, type_engine, *head)?];
+            let mut type_arguments =
+                vec![ty_to_type_argument(context, handler, type_engine, *head)?];
             for ty in tail.into_iter() {
-                type_arguments.push(ty_to_type_argument(handler, type_engine, ty)?);
+                type_arguments.push(ty_to_type_argument(context, handler, type_engine, ty)?);
             }
             type_arguments
         }
@@ -3142,6 +3351,7 @@ fn ty_tuple_descriptor_to_type_arguments(
 }
 
 fn path_type_to_type_info(
+    context: &mut Context,
     handler: &Handler,
     type_engine: &TypeEngine,
     path_type: PathType,
@@ -3200,7 +3410,7 @@ fn path_type_to_type_info(
             } else {
                 let type_arguments = match generics_opt {
                     Some((_double_colon_token, generic_args)) => {
-                        generic_args_to_type_arguments(handler, type_engine, generic_args)?
+                        generic_args_to_type_arguments(context, handler, type_engine, generic_args)?
                     }
                     None => Vec::new(),
                 };
@@ -3268,7 +3478,17 @@ fn item_attrs_to_map(
             let args = attr
                 .args
                 .as_ref()
-                .map(|parens| parens.get().into_iter().cloned().collect())
+                .map(|parens| {
+                    parens
+                        .get()
+                        .into_iter()
+                        .map(|arg| AttributeArg {
+                            name: arg.name.clone(),
+                            value: arg.value.clone(),
+                            span: arg.span(),
+                        })
+                        .collect()
+                })
                 .unwrap_or_else(Vec::new);
 
             let attribute = Attribute {
@@ -1,3 +1,5 @@
+mod context;
 mod convert_parse_tree;
 
+pub(crate) use context::*;
 pub(crate) use convert_parse_tree::*;
@@ -1,5 +1,6 @@
 use std::hash::Hash;
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{Span, Spanned};
 
@@ -12,7 +13,7 @@ use crate::{
     CompileResult,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraitConstraint {
     pub(crate) trait_name: CallPath,
     pub(crate) type_arguments: Vec<TypeArgument>,
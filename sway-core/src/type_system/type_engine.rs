@@ -2,6 +2,7 @@ use core::fmt;
 use core::hash::{Hash, Hasher};
 use hashbrown::hash_map::RawEntryMut;
 use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::hash::BuildHasher;
 use std::sync::RwLock;
@@ -47,6 +48,15 @@ where
     }
 }
 
+/// The contents of a [TypeEngine], from which an identical engine may be restored.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TypeEngineSnapshot {
+    types: Vec<TypeInfo>,
+    storage_only_types: Vec<TypeInfo>,
+    ids: Vec<(TypeInfo, TypeId)>,
+    unified_types: Vec<(TypeId, Vec<TypeId>)>,
+}
+
 impl TypeEngine {
     /// Inserts a [TypeInfo] into the [TypeEngine] and returns a [TypeId]
     /// referring to that [TypeInfo].
@@ -104,6 +114,48 @@ impl TypeEngine {
         }
     }
 
+    pub(crate) fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+            && self.storage_only_types.is_empty()
+            && self.id_map.read().unwrap().is_empty()
+            && self.unify_map.read().unwrap().is_empty()
+    }
+
+    pub(crate) fn snapshot(&self) -> TypeEngineSnapshot {
+        let id_map = self.id_map.read().unwrap();
+        let unify_map = self.unify_map.read().unwrap();
+        TypeEngineSnapshot {
+            types: self.slab.to_vec(),
+            storage_only_types: self.storage_only_types.to_vec(),
+            ids: id_map.iter().map(|(ty, id)| (ty.clone(), *id)).collect(),
+            unified_types: unify_map
+                .iter()
+                .map(|(id, ids)| (*id, ids.clone()))
+                .collect(),
+        }
+    }
+
+    /// Replace the contents of the [TypeEngine] with those of the given snapshot.
+    pub(crate) fn restore(&self, snapshot: TypeEngineSnapshot) {
+        // The types must be restored first, as hashing a type may look up the types it refers to.
+        self.slab.set_all(snapshot.types);
+        self.storage_only_types.set_all(snapshot.storage_only_types);
+        let mut id_map = self.id_map.write().unwrap();
+        id_map.clear();
+        let hash_builder = id_map.hasher().clone();
+        for (ty, type_id) in snapshot.ids {
+            let ty_hash = make_hasher(&hash_builder, self)(&ty);
+            let raw_entry = id_map
+                .raw_entry_mut()
+                .from_hash(ty_hash, |x| x.eq(&ty, self));
+            if let RawEntryMut::Vacant(v) = raw_entry {
+                v.insert_with_hasher(ty_hash, ty, type_id, make_hasher(&hash_builder, self));
+            }
+        }
+        let mut unify_map = self.unify_map.write().unwrap();
+        *unify_map = snapshot.unified_types.into_iter().collect();
+    }
+
     /// Currently the [TypeEngine] is a lazy static object, so when we run
     /// cargo tests, we can either choose to use a local [TypeEngine] and bypass
     /// all of the global methods or we can use the lazy static [TypeEngine].
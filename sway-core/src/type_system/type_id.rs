@@ -1,8 +1,10 @@
 use super::*;
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 /// A identifier to uniquely refer to our type terms
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd, Debug, Serialize, Deserialize)]
 pub struct TypeId(usize);
 
 impl DisplayWithTypeEngine for TypeId {
@@ -3,6 +3,7 @@ use crate::{
     language::{ty, CallPath},
     Ident,
 };
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{integer_bits::IntegerBits, span::Span};
 
@@ -12,7 +13,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum AbiName {
     Deferred,
     Known(CallPath),
@@ -30,7 +31,7 @@ impl fmt::Display for AbiName {
 }
 
 /// A slow set primitive using `==` to check for containment.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct VecSet<T>(pub Vec<T>);
 
 impl<T: fmt::Debug> fmt::Debug for VecSet<T> {
@@ -64,7 +65,7 @@ impl<T: PartialEqWithTypeEngine> PartialEqWithTypeEngine for VecSet<T> {
 
 /// Type information without an associated value, used for type inferencing and definition.
 // TODO use idents instead of Strings when we have arena spans
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeInfo {
     Unknown,
     UnknownGeneric {
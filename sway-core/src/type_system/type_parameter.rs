@@ -6,6 +6,7 @@ use crate::{
     type_system::*,
 };
 
+use serde::{Deserialize, Serialize};
 use sway_error::error::CompileError;
 use sway_types::{ident::Ident, span::Span, Spanned};
 
@@ -15,7 +16,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TypeParameter {
     pub type_id: TypeId,
     pub(crate) initial_type_id: TypeId,
@@ -1,9 +1,9 @@
 use crate::priv_prelude::{Peek, Peeker};
 use crate::{Parse, ParseBracket, ParseResult, ParseToEnd, Parser, ParserConsumed};
 
-use sway_ast::attribute::{Annotated, Attribute, AttributeDecl};
+use sway_ast::attribute::{Annotated, Attribute, AttributeArg, AttributeDecl};
 use sway_ast::brackets::{Parens, SquareBrackets};
-use sway_ast::keywords::{HashToken, StorageToken, Token};
+use sway_ast::keywords::{EqToken, HashToken, StorageToken, Token};
 use sway_ast::punctuated::Punctuated;
 use sway_ast::token::{DocComment, DocStyle};
 use sway_error::parser_error::ParseErrorKind;
@@ -35,9 +35,12 @@ impl<T: Parse> Parse for Annotated<T> {
         }) = parser.peek()
         {
             let doc_comment = parser.parse::<DocComment>()?;
-            // TODO: Use a Literal instead of an Ident when Attribute args
-            // start supporting them and remove `Ident::new_no_trim`.
-            let value = Ident::new_no_trim(doc_comment.content_span.clone());
+            // TODO: Carry the content as a string `Literal` in the `value` of the `AttributeArg`,
+            // which now supports one, rather than as its `name`, and remove `Ident::new_no_trim`.
+            let value = AttributeArg {
+                name: Ident::new_no_trim(doc_comment.content_span.clone()),
+                value: None,
+            };
             attribute_list.push(AttributeDecl {
                 hash_token: HashToken::new(doc_comment.span.clone()),
                 attribute: SquareBrackets::new(
@@ -90,6 +93,17 @@ impl Parse for Attribute {
     }
 }
 
+impl Parse for AttributeArg {
+    fn parse(parser: &mut Parser) -> ParseResult<Self> {
+        let name = parser.parse()?;
+        let value = match parser.take::<EqToken>() {
+            Some(_eq_token) => Some(parser.parse()?),
+            None => None,
+        };
+        Ok(AttributeArg { name, value })
+    }
+}
+
 impl ParseToEnd for Attribute {
     fn parse_to_end<'a, 'e>(mut parser: Parser<'a, 'e>) -> ParseResult<(Self, ParserConsumed<'a>)> {
         let attrib = parser.parse()?;
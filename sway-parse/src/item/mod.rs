@@ -164,7 +164,7 @@ impl Parse for FnSignature {
 mod tests {
     use super::*;
     use std::sync::Arc;
-    use sway_ast::{AttributeDecl, Item};
+    use sway_ast::{AttributeDecl, Item, Literal};
 
     fn parse_item(input: &str) -> Item {
         let handler = <_>::default();
@@ -188,9 +188,9 @@ mod tests {
                     .map(|att| {
                         (
                             att.name.as_str(),
-                            att.args
-                                .as_ref()
-                                .map(|arg| arg.get().into_iter().map(|a| a.as_str()).collect()),
+                            att.args.as_ref().map(|arg| {
+                                arg.get().into_iter().map(|a| a.name.as_str()).collect()
+                            }),
                         )
                     })
                     .collect()
@@ -399,6 +399,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_attributes_fn_arg_with_value() {
+        let item = parse_item(
+            r#"
+            #[foo(one = "1", two)]
+            fn f() -> bool {
+                false
+            }
+            "#,
+        );
+
+        assert!(matches!(item.value, ItemKind::Fn(_)));
+        assert_eq!(
+            attributes(&item.attribute_list),
+            vec![[("foo", Some(vec!["one", "two"]))]]
+        );
+
+        let args = item.attribute_list[0]
+            .attribute
+            .get()
+            .final_value_opt
+            .as_ref();
+        let args: Vec<_> = args
+            .and_then(|attr| attr.args.as_ref())
+            .map(|args| args.get().into_iter().collect())
+            .unwrap();
+        assert!(matches!(
+            &args[0].value,
+            Some(Literal::String(lit)) if lit.parsed == "1"
+        ));
+        assert!(args[1].value.is_none());
+    }
+
     #[test]
     fn parse_attributes_fn_zero_one_and_three_args() {
         let item = parse_item(
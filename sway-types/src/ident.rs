@@ -1,29 +1,33 @@
 use crate::{span::Span, Spanned};
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     cmp::{Ord, Ordering},
     fmt,
     hash::{Hash, Hasher},
+    sync::Arc,
 };
 
 #[derive(Debug, Clone)]
 pub struct BaseIdent {
-    name_override_opt: Option<&'static str>,
+    name_override_opt: Option<Arc<str>>,
     span: Span,
     is_raw_ident: bool,
 }
 
 impl BaseIdent {
     pub fn as_str(&self) -> &str {
-        self.name_override_opt.unwrap_or_else(|| self.span.as_str())
+        self.name_override_opt
+            .as_deref()
+            .unwrap_or_else(|| self.span.as_str())
     }
 
     pub fn is_raw_ident(&self) -> bool {
         self.is_raw_ident
     }
 
-    pub fn name_override_opt(&self) -> Option<&'static str> {
-        self.name_override_opt
+    pub fn name_override_opt(&self) -> Option<&str> {
+        self.name_override_opt.as_deref()
     }
 
     pub fn new(span: Span) -> Ident {
@@ -54,7 +58,7 @@ impl BaseIdent {
 
     pub fn new_with_override(name_override: &'static str, span: Span) -> Ident {
         Ident {
-            name_override_opt: Some(name_override),
+            name_override_opt: Some(Arc::from(name_override)),
             span,
             is_raw_ident: false,
         }
@@ -62,13 +66,31 @@ impl BaseIdent {
 
     pub fn new_no_span(name: &'static str) -> Ident {
         Ident {
-            name_override_opt: Some(name),
+            name_override_opt: Some(Arc::from(name)),
             span: Span::dummy(),
             is_raw_ident: false,
         }
     }
 }
 
+impl Serialize for BaseIdent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.name_override_opt(), &self.span, self.is_raw_ident).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BaseIdent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (name_override, span, is_raw_ident) =
+            <(Option<String>, Span, bool)>::deserialize(deserializer)?;
+        Ok(BaseIdent {
+            name_override_opt: name_override.map(Arc::from),
+            span,
+            is_raw_ident,
+        })
+    }
+}
+
 /// An [Ident] is an _identifier_ with a corresponding `span` from which it was derived.
 /// It relies on a custom implementation of Hash which only looks at its textual name
 /// representation, so that namespacing isn't reliant on the span itself, which will
@@ -133,11 +155,7 @@ impl From<&Ident> for IdentUnique {
 
 impl From<&IdentUnique> for Ident {
     fn from(item: &IdentUnique) -> Self {
-        Ident {
-            name_override_opt: item.0.name_override_opt(),
-            span: item.0.span(),
-            is_raw_ident: item.0.is_raw_ident(),
-        }
+        item.0.clone()
     }
 }
 
@@ -1,6 +1,8 @@
 use std::fmt;
 
-#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum IntegerBits {
     Eight,
     Sixteen,
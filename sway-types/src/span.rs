@@ -1,12 +1,19 @@
 use {
     lazy_static::lazy_static,
-    std::{borrow::Cow, cmp, fmt, path::PathBuf, sync::Arc},
+    serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+    std::{borrow::Cow, cell::RefCell, cmp, collections::HashMap, fmt, path::PathBuf, sync::Arc},
 };
 
 lazy_static! {
     static ref DUMMY_SPAN: Span = Span::new(Arc::from(""), 0, 0, None).unwrap();
 }
 
+thread_local! {
+    /// The sources shared by the spans currently being serialized or deserialized by a
+    /// [SharedSpanSources], if any.
+    static SHARED_SPAN_SOURCES: RefCell<Option<SpanSources>> = const { RefCell::new(None) };
+}
+
 pub struct Position {
     input: Arc<str>,
     pos: usize,
@@ -207,6 +214,136 @@ impl fmt::Debug for Span {
     }
 }
 
+/// The serialized form of a [Span].
+#[derive(Serialize, Deserialize)]
+enum SerializedSpan {
+    /// A span along with the whole source it refers to.
+    Source {
+        src: Arc<str>,
+        path: Option<Arc<PathBuf>>,
+        start: usize,
+        end: usize,
+    },
+    /// A span whose source is at the given index of the sources of a [SharedSpanSources].
+    Shared {
+        source: usize,
+        start: usize,
+        end: usize,
+    },
+}
+
+/// Spans are serialized along with the entire source they refer to, unless they are serialized
+/// within [SharedSpanSources::serialize].
+impl Serialize for Span {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let shared =
+            SHARED_SPAN_SOURCES.with(|sources| Some(sources.borrow_mut().as_mut()?.index_of(self)));
+        let span = match shared {
+            Some(source) => SerializedSpan::Shared {
+                source,
+                start: self.start,
+                end: self.end,
+            },
+            None => SerializedSpan::Source {
+                src: self.src.clone(),
+                path: self.path.clone(),
+                start: self.start,
+                end: self.end,
+            },
+        };
+        span.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Span {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (src, path, start, end) = match SerializedSpan::deserialize(deserializer)? {
+            SerializedSpan::Source {
+                src,
+                path,
+                start,
+                end,
+            } => (src, path, start, end),
+            SerializedSpan::Shared { source, start, end } => {
+                let (src, path) = SHARED_SPAN_SOURCES
+                    .with(|sources| sources.borrow().as_ref()?.sources.get(source).cloned())
+                    .ok_or_else(|| {
+                        de::Error::custom(format!(
+                            "span refers to shared source {source}, which was not provided"
+                        ))
+                    })?;
+                (src, path, start, end)
+            }
+        };
+        Span::new(src, start, end, path)
+            .ok_or_else(|| de::Error::custom("span exceeds the bounds of its source"))
+    }
+}
+
+/// A serialized value whose spans share the distinct sources they refer to, rather than each
+/// span being serialized along with its whole source.
+///
+/// The spans deserialized from the value by [SharedSpanSources::deserialize] also share their
+/// sources, so that they may be joined like the spans of a freshly parsed program.
+#[derive(Serialize, Deserialize)]
+pub struct SharedSpanSources {
+    sources: Vec<(Arc<str>, Option<Arc<PathBuf>>)>,
+    value: Vec<u8>,
+}
+
+/// The sources of the spans being serialized or deserialized by a [SharedSpanSources].
+#[derive(Default)]
+struct SpanSources {
+    sources: Vec<(Arc<str>, Option<Arc<PathBuf>>)>,
+    /// The index of each source by the address of its contents and its path.
+    indices: HashMap<(usize, Option<Arc<PathBuf>>), usize>,
+}
+
+impl SharedSpanSources {
+    /// Serialize a value to bytes with `serialize`, sharing the sources of its spans.
+    pub fn serialize<E>(
+        serialize: impl FnOnce() -> Result<Vec<u8>, E>,
+    ) -> Result<SharedSpanSources, E> {
+        let (value, sources) = with_span_sources(SpanSources::default(), serialize);
+        Ok(SharedSpanSources {
+            sources: sources.sources,
+            value: value?,
+        })
+    }
+
+    /// Deserialize the value from its bytes with `deserialize`, providing the shared sources to
+    /// its spans.
+    pub fn deserialize<T, E>(
+        self,
+        deserialize: impl FnOnce(&[u8]) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let sources = SpanSources {
+            sources: self.sources,
+            indices: HashMap::new(),
+        };
+        with_span_sources(sources, || deserialize(&self.value)).0
+    }
+}
+
+impl SpanSources {
+    fn index_of(&mut self, span: &Span) -> usize {
+        let key = (span.src.as_ptr() as usize, span.path.clone());
+        let sources = &mut self.sources;
+        *self.indices.entry(key).or_insert_with(|| {
+            sources.push((span.src.clone(), span.path.clone()));
+            sources.len() - 1
+        })
+    }
+}
+
+/// Run `f` with the given sources shared by the spans it serializes or deserializes.
+fn with_span_sources<T>(sources: SpanSources, f: impl FnOnce() -> T) -> (T, SpanSources) {
+    let outer = SHARED_SPAN_SOURCES.with(|shared| shared.replace(Some(sources)));
+    let result = f();
+    let sources = SHARED_SPAN_SOURCES.with(|shared| shared.replace(outer));
+    (result, sources.unwrap_or_default())
+}
+
 pub trait Spanned {
     fn span(&self) -> Span;
 }
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// Represents the position in a storage statement that a field was declared.
 /// For example, in the following storage declaration, `foo` has [StateIndex] 0 and `bar` has
 /// [StateIndex] 1.
@@ -15,7 +17,7 @@
 ///
 /// `bar`'s [StorageSlot] is `sha256(format!("{}{}", STORAGE_DOMAIN_SEPARATOR, 1))` or
 /// `DE9090CB50E71C2588C773487D1DA7066D0C719849A7E58DC8B6397A25C567C0`.
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StateIndex(usize);
 
 impl StateIndex {
@@ -1348,6 +1348,25 @@ fn foo() {}
 #[storage(read, write)]
 #[test, inline(always)]
 fn foo() {}
+"#;
+        let mut formatter = Formatter::default();
+        let formatted_sway_code =
+            Formatter::format(&mut formatter, Arc::from(sway_code_to_format), None).unwrap();
+        assert_eq!(correct_sway_code, formatted_sway_code);
+        assert!(test_stability(formatted_sway_code, formatter));
+    }
+
+    #[test]
+    fn test_attribute_args_with_values() {
+        let sway_code_to_format = r#"library my_lib;
+
+#[test(should_revert="42",ignore)]
+fn foo() {}
+"#;
+        let correct_sway_code = r#"library my_lib;
+
+#[test(should_revert = "42", ignore)]
+fn foo() {}
 "#;
         let mut formatter = Formatter::default();
         let formatted_sway_code =
@@ -201,7 +201,10 @@ pub(crate) async fn compile_and_run_unit_tests(
             },
             ..Default::default()
         })?;
-        let tested = built_tests.run()?;
+        let test_filter = forc_test::TestFilter::default();
+        let fuzz_config = forc_test::FuzzConfig::default();
+        let tested =
+            built_tests.run(forc_test::TestRunnerCount::Auto, &test_filter, &fuzz_config)?;
 
         match tested {
             forc_test::Tested::Package(tested_pkg) => Ok(vec![*tested_pkg]),
@@ -0,0 +1,5 @@
+library test_lib;
+
+pub fn double(x: u64) -> u64 {
+    x * 2
+}
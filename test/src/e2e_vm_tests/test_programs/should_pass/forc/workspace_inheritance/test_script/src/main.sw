@@ -0,0 +1,7 @@
+script;
+
+use test_lib::double;
+
+fn main() -> u64 {
+    double(21)
+}
@@ -0,0 +1,8 @@
+library adder_abi;
+
+abi Adder {
+    #[storage(read, write)]
+    fn add(amount: u64) -> u64;
+    #[storage(read)]
+    fn total() -> u64;
+}
@@ -0,0 +1,20 @@
+contract;
+
+use adder_abi::Adder;
+
+storage {
+    total: u64 = 10,
+}
+
+impl Adder for Contract {
+    #[storage(read, write)]
+    fn add(amount: u64) -> u64 {
+        storage.total = storage.total + amount;
+        storage.total
+    }
+
+    #[storage(read)]
+    fn total() -> u64 {
+        storage.total
+    }
+}
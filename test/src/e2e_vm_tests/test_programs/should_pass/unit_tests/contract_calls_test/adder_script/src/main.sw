@@ -0,0 +1,24 @@
+script;
+
+use adder_abi::Adder;
+
+fn main() {}
+
+#[test]
+fn test_initial_storage() {
+    let adder = abi(Adder, adder_contract::CONTRACT_ID);
+    assert(adder.total() == 10);
+}
+
+#[test]
+fn test_add() {
+    let adder = abi(Adder, adder_contract::CONTRACT_ID);
+    assert(adder.add(5) == 15);
+    assert(adder.total() == 15);
+}
+
+#[test]
+fn test_contract_dependency_call() {
+    let adder = abi(Adder, adder_contract::CONTRACT_ID);
+    assert(adder.add(32) == 42);
+}
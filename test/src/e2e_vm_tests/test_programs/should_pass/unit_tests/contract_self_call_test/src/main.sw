@@ -0,0 +1,40 @@
+contract;
+
+abi Counter {
+    #[storage(read, write)]
+    fn increment(amount: u64) -> u64;
+
+    #[storage(read)]
+    fn count() -> u64;
+}
+
+storage {
+    count: u64 = 10,
+}
+
+impl Counter for Contract {
+    #[storage(read, write)]
+    fn increment(amount: u64) -> u64 {
+        storage.count = storage.count + amount;
+        storage.count
+    }
+
+    #[storage(read)]
+    fn count() -> u64 {
+        storage.count
+    }
+}
+
+#[test]
+fn test_increment() {
+    let counter = abi(Counter, CONTRACT_ID);
+    assert(counter.increment(32) == 42);
+    assert(counter.count() == 42);
+}
+
+#[test]
+fn test_initial_storage() {
+    // Each test begins with a fresh copy of the deployed contract's storage.
+    let counter = abi(Counter, CONTRACT_ID);
+    assert(counter.count() == 10);
+}
@@ -0,0 +1,76 @@
+library fuzz_test;
+
+use std::vec::Vec;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+enum Shape {
+    Circle: u64,
+    Rect: Point,
+}
+
+fn saturating_add(a: u64, b: u64) -> u64 {
+    if a > 18446744073709551615 - b {
+        18446744073709551615
+    } else {
+        a + b
+    }
+}
+
+#[test]
+fn test_single_arg(a: u64) {
+    assert(a ^ a == 0);
+}
+
+#[test]
+fn test_multi_arg(a: u64, b: u64, c: bool) {
+    let sum = saturating_add(a, b);
+    assert(sum >= a && sum >= b);
+    assert(c || !c);
+}
+
+#[test]
+fn test_small_ints(a: u8, b: u16, c: u32) {
+    assert(a <= 255 && b <= 65535 && c <= 4294967295);
+}
+
+#[test]
+fn test_b256(x: b256) {
+    assert(x == x);
+}
+
+#[test]
+fn test_struct(p: Point) {
+    assert(saturating_add(p.x, p.y) == saturating_add(p.y, p.x));
+}
+
+#[test]
+fn test_enum(shape: Shape) {
+    let size = match shape {
+        Shape::Circle(r) => r,
+        Shape::Rect(p) => saturating_add(p.x, p.y),
+    };
+    assert(size == size);
+}
+
+#[test]
+fn test_arrays_and_tuples(a: [u8; 3], t: (bool, u64), s: str[4]) {
+    assert(a[0] <= 255 && (t.0 || !t.0) && t.1 == t.1);
+}
+
+#[test]
+fn test_vec(v: Vec<u64>) {
+    let mut i = 0;
+    while i < v.len() {
+        assert(v.get(i).unwrap() == v.get(i).unwrap());
+        i += 1;
+    }
+}
+
+#[test(should_revert)]
+fn test_always_overflows(a: u64) {
+    assert(18446744073709551615 + a + 1 > a);
+}
@@ -32,3 +32,33 @@ fn test_lt() {
 fn test_local() {
     assert(pow2(4) == 16)
 }
+
+#[test(ignore)]
+fn test_ignored() {
+    assert(false);
+}
+
+#[test(should_revert)]
+fn test_revert() {
+    revert(0);
+}
+
+#[test(should_revert = "42")]
+fn test_revert_code() {
+    revert(42);
+}
+
+#[test(should_revert = "0x2a")]
+fn test_revert_code_hex() {
+    revert(42);
+}
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+#[test]
+fn test_log_struct() {
+    log(Point { x: 1, y: 2 });
+}
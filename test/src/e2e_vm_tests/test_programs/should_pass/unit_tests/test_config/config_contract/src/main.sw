@@ -0,0 +1,13 @@
+contract;
+
+use std::block::height;
+
+abi Config {
+    fn block_height() -> u64;
+}
+
+impl Config for Contract {
+    fn block_height() -> u64 {
+        height()
+    }
+}
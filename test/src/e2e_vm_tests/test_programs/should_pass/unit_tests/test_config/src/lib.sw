@@ -0,0 +1,33 @@
+library test_config;
+
+use std::{block::height, inputs::input_count, outputs::output_count, tx::{tx_gas_limit, tx_gas_price, tx_maturity}};
+
+#[test]
+fn test_block_height() {
+    assert(height() == 100);
+}
+
+#[test]
+fn test_tx_params() {
+    assert(tx_gas_limit() == 1000000);
+    assert(tx_gas_price() == 1);
+    assert(tx_maturity() == 50);
+}
+
+#[test]
+fn test_inputs_and_outputs() {
+    // The configured coins and outputs, along with the deployed contract's input and output.
+    assert(input_count() == 3u8);
+    assert(output_count() == 3);
+}
+
+abi Config {
+    fn block_height() -> u64;
+}
+
+// The contract is deployed with the same block height and maturity as the tests.
+#[test]
+fn test_contract_deployment() {
+    let config = abi(Config, config_contract::CONTRACT_ID);
+    assert(config.block_height() == 100);
+}